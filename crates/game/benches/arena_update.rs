@@ -0,0 +1,102 @@
+//! Benchmarks `Arena::update` with thousands of bullets in flight.
+//!
+//! Two kinds of matches are simulated: "minigun" matches, where long streams of bullets fly
+//! along the rows of platforms, and "shotgun" matches, where bullets are fired in dense bursts
+//! spread around the players. Both are run with an increasing number of bullets, to show that
+//! the cost of an update grows linearly with the bullets rather than with bullets × players.
+use std::collections::HashMap;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use game::arena::Arena;
+use game::entity::EntityId;
+use game::map::{HORIZONTAL_BLOCKS, VERTICAL_BLOCKS};
+use game::player::Player;
+use game::weapon::Bullet;
+use game::weaponscatalog::BulletType;
+use game::{BLOCK_WIDTH, HORIZONTAL_PADDING, VERTICAL_BLOCK_SPACING, VERTICAL_PADDING};
+use glam::Vec2;
+
+const PLAYERS: u8 = 8;
+const BULLET_COUNTS: [u16; 4] = [100, 1000, 5000, 10000];
+const DT: f32 = 1.0 / 60.0;
+
+/// fills an arena with the given number of bullets.
+type AddBullets = fn(&mut Arena, u16);
+
+/// creates an arena with `PLAYERS` players spread over the rows of the default map.
+fn create_arena() -> Arena {
+    let mut arena = Arena::default();
+    for id in 0..PLAYERS {
+        let col = (id as usize * 2) % HORIZONTAL_BLOCKS;
+        let row = id as usize % VERTICAL_BLOCKS;
+        let top = arena.get_block_position_at(row, col);
+        let mut player = Player::new(&id.to_string());
+        player.update_position(top - Vec2::new(0.0, player.height), 0.0);
+        arena.add_player(player);
+    }
+    arena
+}
+
+/// streams of bullets flying horizontally along the rows, like a minigun being fired.
+fn add_minigun_bullets(arena: &mut Arena, count: u16) {
+    for id in 0..count {
+        let row = id as usize % VERTICAL_BLOCKS;
+        let owner = EntityId::new(id % PLAYERS as u16, 0);
+        let x = HORIZONTAL_PADDING + (id as f32 * 7.0) % (HORIZONTAL_BLOCKS as f32 * BLOCK_WIDTH);
+        let y = VERTICAL_PADDING + row as f32 * VERTICAL_BLOCK_SPACING - 16.0;
+        let direction = if id % 2 == 0 { 1.0 } else { -1.0 };
+
+        let velocity = Vec2::new(direction * 1200.0, 0.0);
+        let bullet = Bullet::new(Vec2::new(x, y), velocity, BulletType::Rifle, 0, owner);
+        arena.add_bullet(bullet);
+    }
+}
+
+/// bursts of bullets spread in a cone in front of the players, like shotguns being fired.
+fn add_shotgun_bullets(arena: &mut Arena, count: u16) {
+    let origins: Vec<(EntityId, Vec2)> = arena.get_players()
+        .iter()
+        .map(|(id, player)| (id, player.get_position()))
+        .collect();
+
+    for id in 0..count {
+        let (owner, origin) = origins[id as usize % origins.len()];
+        let angle = ((id / PLAYERS as u16) % 16) as f32 / 16.0 - 0.5;
+        let distance = (id % 32) as f32 * 4.0;
+        let direction = Vec2::new(angle.cos(), angle.sin());
+
+        let position = origin + direction * distance;
+        let bullet = Bullet::new(position, direction * 900.0, BulletType::Pistol, 0, owner);
+        arena.add_bullet(bullet);
+    }
+}
+
+fn bench_update(c: &mut Criterion) {
+    let matches: [(&str, AddBullets); 2] = [
+        ("minigun", add_minigun_bullets),
+        ("shotgun", add_shotgun_bullets),
+    ];
+
+    for (name, add_bullets) in matches {
+        let mut group = c.benchmark_group(format!("arena_update_{}", name));
+        let inputs = HashMap::new();
+
+        for count in BULLET_COUNTS {
+            group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+                b.iter_batched_ref(
+                    || {
+                        let mut arena = create_arena();
+                        add_bullets(&mut arena, count);
+                        arena
+                    },
+                    |arena| arena.update(DT, &inputs),
+                    criterion::BatchSize::LargeInput,
+                );
+            });
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_update);
+criterion_main!(benches);
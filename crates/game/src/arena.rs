@@ -13,22 +13,51 @@ use crate::block::BlockType;
 use crate::block::BlockRect;
 use crate::BLOCK_WIDTH;
 use crate::BLOCK_HEIGHT;
+use crate::{MELEE_DAMAGE, MELEE_MOMENTUM};
+use crate::{BODY_PUSH_STRENGTH, STOMP_BOUNCE_VELOCITY, STOMP_MOMENTUM};
 use crate::map::VERTICAL_BLOCKS;
 use crate::map::HORIZONTAL_BLOCKS;
 use crate::input::Input;
+use crate::event::GameEvent;
+use crate::hazard::{HazardType, LAVA_BOUNCE_VELOCITY, LAVA_DAMAGE};
+use crate::broadphase::Broadphase;
+use crate::entity::{EntityId, EntityStore};
+use crate::fixed::{self, RealVec2, StateHasher};
+use crate::pickup::{Pickup, PickupKind, PickupSpawner, PICKUP_SIZE};
 use crate::player::Player;
-use crate::weapon::Bullet;
+use crate::stats::MatchStats;
+use crate::query::{self, PlatformEdge, RayHit, RayTarget};
+use crate::teleporter;
+use crate::weapon::{Bullet, Weapon};
 use glam::Vec2;
 
 
 /// represents the entire world of the game (entire map + players).
+///
+/// All the entities of the arena (players, bullets, thrown weapons and pickups) are kept in
+/// `EntityStore`s, and are referred to by their generational `EntityId`.
 #[derive(Debug)]
 pub struct Arena {
     map: Map,
-    bullets: HashMap<u16, Bullet>,
+    bullets: EntityStore<Bullet>,
     blocks: [Option<BlockType>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS],
-    bulletcount: u16,
-    players: HashMap<u8, Player>,
+    /// health left of each block, or None if there is no block or it cannot be broken.
+    block_healths: [Option<f32>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS],
+    /// index, type and regrow time in ms of the broken blocks which grow back.
+    broken_blocks: Vec<(usize, BlockType, u128)>,
+    players: EntityStore<Player>,
+    thrown_weapons: EntityStore<Weapon>,
+    pickups: EntityStore<Pickup>,
+    /// spawner of random pickups, if the arena spawns any.
+    spawner: Option<PickupSpawner>,
+    player_grid: Broadphase<EntityId>,
+    bullet_grid: Broadphase<EntityId>,
+    stats: MatchStats,
+    /// fraction of the overlap between two players pushed apart on every update.
+    body_push_strength: f32,
+    /// events which happened between two updates (e.g. explosions), reported by the next update.
+    pending_events: Vec<GameEvent>,
+    time: f64,
 }
 
 impl Default for Arena {
@@ -40,10 +69,23 @@ impl Default for Arena {
 impl Arena {
     pub fn new(map: Map) -> Self {
         let blocks = map.to_blocktypes();
-        let bullets = HashMap::new();
-        let bulletcount = 0;
-        let players = HashMap::new();
-        Self { map, blocks, bullets, bulletcount, players }
+        let block_healths = blocks.map(|blocktype| blocktype.and_then(block::get_block_health));
+        let broken_blocks = Vec::new();
+        let bullets = EntityStore::new();
+        let players = EntityStore::new();
+        let thrown_weapons = EntityStore::new();
+        let pickups = EntityStore::new();
+        let spawner = None;
+        let player_grid = Broadphase::new();
+        let bullet_grid = Broadphase::new();
+        let stats = MatchStats::new(0);
+        let body_push_strength = BODY_PUSH_STRENGTH;
+        let pending_events = Vec::new();
+        let time = 0.0;
+        Self {
+            map, blocks, block_healths, broken_blocks, bullets, players, thrown_weapons, pickups, spawner,
+            player_grid, bullet_grid, stats, body_push_strength, pending_events, time,
+        }
     }
 
     pub fn get_map(&self) -> &Map {
@@ -57,47 +99,206 @@ impl Arena {
         Vec2::new(x, y)
     }
 
-    /// adds a new player to the arena. Returns the added player.
-    pub fn add_player(&mut self, player: Player, id: u8) -> &mut Player {
-        self.players.insert(id, player);
-        self.players.get_mut(&id).expect("Player not found. This should not happen.")
+    /// obtains the type of the block at `row` and `col`, or None if there is no block there.
+    pub fn get_block(&self, row: usize, col: usize) -> Option<BlockType> {
+        self.blocks[col * VERTICAL_BLOCKS + row]
+    }
+
+    /// obtains the health left of the block at `row` and `col`, or None if there is no block
+    /// there or it cannot be broken.
+    pub fn get_block_health(&self, row: usize, col: usize) -> Option<f32> {
+        self.block_healths[col * VERTICAL_BLOCKS + row]
+    }
+
+    /// replaces the block at `row` and `col` with a block of type `blocktype` at full health,
+    /// or removes it if `blocktype` is None (e.g. with a change received from the server).
+    pub fn set_block(&mut self, row: usize, col: usize, blocktype: Option<BlockType>) {
+        let i = col * VERTICAL_BLOCKS + row;
+        self.blocks[i] = blocktype;
+        self.block_healths[i] = blocktype.and_then(block::get_block_health);
+        self.map.set_block(row, col, blocktype);
+    }
+
+    /// removes the blocks at the cells (row, col) for good, along with the broken blocks which
+    /// would have grown back there. The removed blocks are reported by the next update.
+    pub(crate) fn collapse_blocks(&mut self, cells: &[(usize, usize)]) {
+        for &(row, col) in cells {
+            let i = col * VERTICAL_BLOCKS + row;
+            self.broken_blocks.retain(|(broken, _, _)| *broken != i);
+            if self.blocks[i].is_some() {
+                self.set_block(row, col, None);
+                self.pending_events.push(GameEvent::BlockChanged { row, col, blocktype: None });
+            }
+        }
+    }
+
+    /// adds a new player to the arena. Returns the id of the added player.
+    pub fn add_player(&mut self, player: Player) -> EntityId {
+        self.players.insert(player)
+    }
+
+    /// adds a player with a known id (e.g. received from the server). Returns the added player.
+    pub fn insert_player(&mut self, id: EntityId, player: Player) -> &mut Player {
+        self.players.insert_at(id, player);
+        self.players.get_mut(id).expect("Player not found. This should not happen.")
+    }
+
+    pub fn remove_player(&mut self, id: EntityId) -> Option<Player> {
+        self.players.remove(id)
     }
 
-    pub fn remove_player(&mut self, id: u8) {
-        self.players.remove(&id);
+    /// brings a knocked out player back to the spawn point, with no damage and the weapon of
+    /// its class.
+    pub fn respawn_player(&mut self, id: EntityId) {
+        let curr_time = self.get_time();
+        if let Some(player) = self.players.get_mut(id) {
+            player.respawn(curr_time);
+        }
     }
 
-    pub fn get_player(&self, id: u8) -> Option<&Player> {
-        self.players.get(&id)
+    pub fn get_player(&self, id: EntityId) -> Option<&Player> {
+        self.players.get(id)
     }
 
-    pub fn get_mut_player(&mut self, id: u8) -> Option<&mut Player> {
-        self.players.get_mut(&id)
+    pub fn get_mut_player(&mut self, id: EntityId) -> Option<&mut Player> {
+        self.players.get_mut(id)
     }
 
-    pub fn get_players(&self) -> &HashMap<u8, Player> {
+    pub fn get_players(&self) -> &EntityStore<Player> {
         &self.players
     }
 
-    pub fn get_bullets(&self) -> &HashMap<u16, Bullet> {
+    pub fn get_bullets(&self) -> &EntityStore<Bullet> {
         &self.bullets
     }
 
-    pub fn update_bullet(&mut self, bullet: Bullet) {
-        self.bullets.insert(bullet.get_id(), bullet);
+    /// adds a new bullet to the arena. Returns the id of the added bullet.
+    pub fn add_bullet(&mut self, bullet: Bullet) -> EntityId {
+        self.bullets.insert(bullet)
+    }
+
+    /// adds or replaces a bullet with a known id (e.g. received from the server).
+    pub fn update_bullet(&mut self, id: EntityId, bullet: Bullet) {
+        self.bullets.insert_at(id, bullet);
     }
 
     pub fn clear_bullets(&mut self) {
         self.bullets.clear();
     }
 
+    /// obtains the weapons thrown away by the players, which are still flying.
+    pub fn get_thrown_weapons(&self) -> &EntityStore<Weapon> {
+        &self.thrown_weapons
+    }
+
+    /// adds or replaces a thrown weapon with a known id (e.g. received from the server).
+    pub fn update_thrown_weapon(&mut self, id: EntityId, weapon: Weapon) {
+        self.thrown_weapons.insert_at(id, weapon);
+    }
+
+    pub fn clear_thrown_weapons(&mut self) {
+        self.thrown_weapons.clear();
+    }
+
+    pub fn get_pickups(&self) -> &EntityStore<Pickup> {
+        &self.pickups
+    }
+
+    /// places a new pickup on the map. Returns the id of the added pickup.
+    pub fn add_pickup(&mut self, pickup: Pickup) -> EntityId {
+        self.pickups.insert(pickup)
+    }
+
+    /// adds or replaces a pickup with a known id (e.g. received from the server).
+    pub fn update_pickup(&mut self, id: EntityId, pickup: Pickup) {
+        self.pickups.insert_at(id, pickup);
+    }
+
+    pub fn remove_pickup(&mut self, id: EntityId) -> Option<Pickup> {
+        self.pickups.remove(id)
+    }
+
+    pub fn clear_pickups(&mut self) {
+        self.pickups.clear();
+    }
+
+    /// starts spawning random pickups with the `spawner`, or stops spawning them if None.
+    /// Arenas do not spawn any pickups until they are given a spawner.
+    pub fn set_pickup_spawner(&mut self, spawner: Option<PickupSpawner>) {
+        self.spawner = spawner;
+    }
+
+    /// obtains the statistics of the players since the start of the match.
+    pub fn get_stats(&self) -> &MatchStats {
+        &self.stats
+    }
+
+    /// starts recording the statistics of a new match.
+    pub fn reset_stats(&mut self) {
+        self.stats = MatchStats::new(self.get_time());
+    }
+
+    pub fn get_body_push_strength(&self) -> f32 {
+        self.body_push_strength
+    }
+
+    /// sets the fraction of the overlap between two players pushed apart on every update,
+    /// between 0.0 (players walk through each other) and 1.0.
+    pub fn set_body_push_strength(&mut self, strength: f32) {
+        self.body_push_strength = strength.clamp(0.0, 1.0);
+    }
+
+    /// obtains the simulated time of the arena in milliseconds. Cooldowns of the players and
+    /// weapons are measured with this time rather than the system clock, so the arena can be
+    /// simulated faster or slower than real time.
+    pub fn get_time(&self) -> u128 {
+        (self.time * 1000.0) as u128
+    }
+
+    /// hashes the time and the movement of the players, bullets and thrown weapons, along with
+    /// the damage and knockouts of the players. Two runs which are in the same state have the same
+    /// hash on every build.
+    pub fn get_state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write(&self.time.to_bits().to_le_bytes());
+
+        for (id, player) in self.players.iter() {
+            hasher.write_u32(id.to_bits());
+            hasher.write_real_vec2(player.position);
+            hasher.write_real_vec2(player.velocity);
+            hasher.write_f32(player.get_damage());
+            hasher.write(&[player.is_knocked_out() as u8]);
+        }
+
+        for (id, bullet) in self.bullets.iter() {
+            let (position, velocity) = bullet.get_movement();
+            hasher.write_u32(id.to_bits());
+            hasher.write_real_vec2(position);
+            hasher.write_real_vec2(velocity);
+        }
+
+        for (id, weapon) in self.thrown_weapons.iter() {
+            let (position, velocity) = weapon.get_movement();
+            hasher.write_u32(id.to_bits());
+            hasher.write_real_vec2(position);
+            hasher.write_real_vec2(velocity);
+        }
+        hasher.finish()
+    }
+
+    /// whether the position is outside of the region where the players are considered alive.
+    /// Falling players are stopped at the bottom of the arena, so reaching it counts as outside.
+    pub fn is_out_of_bounds(position: Vec2) -> bool {
+        !(0.0..=ARENA_WIDTH).contains(&position.x) || position.y >= ARENA_HEIGHT
+    }
+
     /// obtains the position of the row as f32.
-    fn get_block_row_position(row: usize) -> f32 {
+    pub(crate) fn get_block_row_position(row: usize) -> f32 {
         VERTICAL_PADDING + VERTICAL_BLOCK_SPACING * row as f32
     }
 
     /// obtains the position of the col as f32.
-    fn get_block_col_position(col: usize) -> f32 {
+    pub(crate) fn get_block_col_position(col: usize) -> f32 {
         HORIZONTAL_PADDING + BLOCK_WIDTH * col as f32
     }
 
@@ -127,7 +328,7 @@ impl Arena {
     /// row below yourself" rather than just being contained within the spacing of the row above.
     ///
     /// TODO - write test cases for this function. Tentatively, this works for now.
-    fn to_row_col(point: Vec2) -> Option<(usize, usize)> {
+    pub(crate) fn to_row_col(point: Vec2) -> Option<(usize, usize)> {
         let mut col = (point.x - HORIZONTAL_PADDING) / BLOCK_WIDTH;
 
         // 0.0625 is just a correcting constant to make the player "higher" than it is supposed to
@@ -161,67 +362,617 @@ impl Arena {
     /// the `inputs` variable represents the vector of inputs that the arena has received from the
     /// players (probably through network). It MUST have the same size as the players array and
     /// also have the indices of the input match the indices of the players that inputted them.
+    ///
+    /// Returns the events that happened during the update, in the order they happened.
     pub fn update(&mut self,
                   dt: f32,
-                  inputs: &HashMap<u8, InputMask>) {
+                  inputs: &HashMap<EntityId, InputMask>) -> Vec<GameEvent> {
+
+        let mut events = std::mem::take(&mut self.pending_events);
+        self.time += dt as f64;
+        let curr_time = self.get_time();
 
         for (id, player) in self.players.iter_mut() {
+            for effecttype in player.update_effects(curr_time) {
+                events.push(GameEvent::EffectExpired { player: id, effecttype });
+            }
+
             let default_input = InputMask::new();
-            let input = inputs.get(id).unwrap_or(&default_input);
-            Arena::update_player(player, *input, dt, &mut self.bulletcount, &mut self.bullets, &self.map, &self.blocks);
+            let input = inputs.get(&id).unwrap_or(&default_input);
+            Arena::update_player(player, id, *input, dt, curr_time, &mut self.bullets, &self.map, &self.blocks, &mut events);
+
+            if let Some(weapon) = player.take_thrown_weapon() {
+                self.thrown_weapons.insert(weapon);
+            }
+        }
+
+        self.update_bodies(&mut events);
+        self.update_bullets(dt, &mut events);
+        self.update_melee(curr_time, &mut events);
+        self.update_thrown_weapons(dt);
+        self.update_pickups(curr_time, &mut events);
+        self.update_knockouts(&mut events);
+        self.update_broken_blocks(curr_time, &mut events);
+
+        if self.spawner.as_ref().is_some_and(|spawner| spawner.is_due(curr_time)) {
+            let blocks: Vec<BlockRect> = self.get_blocks_iter().collect();
+            let pickups_count = self.pickups.len();
+            if let Some(pickup) = self.spawner.as_mut().and_then(|spawner| spawner.spawn(curr_time, &blocks, pickups_count)) {
+                self.pickups.insert(pickup);
+            }
+        }
+
+        self.stats.record(&events, &self.players, curr_time);
+        events
+    }
+
+    /// knocks out the players who left the arena, or touched spikes. They stay knocked out
+    /// until they are respawned, e.g. by the game mode.
+    fn update_knockouts(&mut self, events: &mut Vec<GameEvent>) {
+        for (id, player) in self.players.iter_mut() {
+            if player.is_knocked_out() {
+                continue;
+            }
+
+            let position = player.get_position();
+            let bottomright = position + Vec2::new(player.width, player.height);
+            let spiked = self.map.get_hazard_touching(position, bottomright) == Some(HazardType::Spikes);
+            if spiked {
+                events.push(GameEvent::HazardTouched { player: id, hazardtype: HazardType::Spikes, damage: 0.0 });
+            }
+
+            if spiked || Arena::is_out_of_bounds(position) {
+                let killer = player.get_last_hit_by().filter(|killer| *killer != id);
+                events.push(GameEvent::PlayerKilled { victim: id, killer });
+                player.knock_out();
+            }
+        }
+    }
+
+    /// damages the block at index `i` of the grid, if it can be broken. The block is removed once
+    /// it runs out of health, and grows back later if its type does.
+    fn damage_block(&mut self, i: usize, damage: f32, curr_time: u128, events: &mut Vec<GameEvent>) {
+        if let (Some(blocktype), Some(health)) = (self.blocks[i], self.block_healths[i]) {
+            if health > damage {
+                self.block_healths[i] = Some(health - damage);
+                return;
+            }
+
+            let (row, col) = (i % VERTICAL_BLOCKS, i / VERTICAL_BLOCKS);
+            self.set_block(row, col, None);
+            if let Some(regrow_time) = block::get_block_regrow_time(blocktype) {
+                self.broken_blocks.push((i, blocktype, curr_time + regrow_time));
+            }
+            events.push(GameEvent::BlockChanged { row, col, blocktype: None });
+        }
+    }
+
+    /// grows back the broken blocks whose regrow time has passed, unless a player is in the way.
+    fn update_broken_blocks(&mut self, curr_time: u128, events: &mut Vec<GameEvent>) {
+        let mut regrown = Vec::new();
+        let players = &self.players;
+
+        self.broken_blocks.retain(|&(i, blocktype, regrow_time)| {
+            let (row, col) = (i % VERTICAL_BLOCKS, i / VERTICAL_BLOCKS);
+            let min = Vec2::new(Arena::get_block_col_position(col), Arena::get_block_row_position(row));
+            let max = min + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT);
+            let blocked = players.iter().any(|(_, player)| player.overlaps(min, max));
+
+            let grows = regrow_time <= curr_time && !blocked;
+            if grows {
+                regrown.push((row, col, blocktype));
+            }
+            !grows
+        });
+
+        for (row, col, blocktype) in regrown {
+            self.set_block(row, col, Some(blocktype));
+            events.push(GameEvent::BlockChanged { row, col, blocktype: Some(blocktype) });
+        }
+    }
+
+    /// pushes apart the players whose bodies overlap, so players cannot walk through each other.
+    fn update_bodies(&mut self, events: &mut Vec<GameEvent>) {
+        let mut pairs = Vec::new();
+        self.rebuild_player_grid();
+
+        for (id, player) in self.players.iter() {
+            let position = player.get_position();
+            let (min, max) = (position, position + Vec2::new(player.width, player.height));
+            for other in self.player_grid.query(min, max) {
+                let touching = id < other && self.players.get(other).is_some_and(|p| p.overlaps(min, max));
+                if touching && !pairs.contains(&(id, other)) {
+                    pairs.push((id, other));
+                }
+            }
         }
 
-        self.update_bullets(dt);
+        for (first, second) in pairs {
+            self.separate_bodies(first, second, events);
+        }
+    }
+
+    /// resolves the overlap between two players. A player falling onto the head of the other
+    /// player stomps it. Otherwise, the players are pushed apart horizontally, and exchange
+    /// momentum if they were moving towards each other.
+    fn separate_bodies(&mut self, first: EntityId, second: EntityId, events: &mut Vec<GameEvent>) {
+        let a = self.players.get(first).expect("Player should be in the arena");
+        let b = self.players.get(second).expect("Player should be in the arena");
+        let (a_min, a_max) = (a.position, a.position + fixed::real_vec2(Vec2::new(a.width, a.height)));
+        let (b_min, b_max) = (b.position, b.position + fixed::real_vec2(Vec2::new(b.width, b.height)));
+        let overlap = a_max.min(b_max) - a_min.max(b_min);
+
+        if overlap.y < overlap.x {
+            let (top, bottom) = if a_min.y < b_min.y { (first, second) } else { (second, first) };
+            let (top_player, bottom_player) = if top == first { (a, b) } else { (b, a) };
+            if top_player.velocity.y <= bottom_player.velocity.y {
+                return;
+            }
+
+            // the top player lands on the head of the bottom player and bounces off.
+            let landing = RealVec2::new(top_player.position.x, bottom_player.position.y - fixed::real(top_player.height));
+            let top_player = self.players.get_mut(top).expect("Player should be in the arena");
+            top_player.move_to(landing, 0.0);
+            top_player.velocity.y = fixed::real(STOMP_BOUNCE_VELOCITY.y);
+
+            let bottom_player = self.players.get_mut(bottom).expect("Player should be in the arena");
+            bottom_player.add_impulse(fixed::real_vec2(STOMP_MOMENTUM));
+            events.push(GameEvent::PlayerStomped { attacker: top, victim: bottom });
+            return;
+        }
+
+        let a_center = a_min.x + fixed::real(a.width / 2.0);
+        let b_center = b_min.x + fixed::real(b.width / 2.0);
+        let (left, right) = if a_center <= b_center { (first, second) } else { (second, first) };
+        let (left_player, right_player) = if left == first { (a, b) } else { (b, a) };
+        let (left_mass, right_mass) = (fixed::real(left_player.get_total_mass()), fixed::real(right_player.get_total_mass()));
+        let total_mass = left_mass + right_mass;
+
+        // the heavier player is pushed back less.
+        let push = overlap.x * fixed::real(self.body_push_strength) / total_mass;
+
+        // players moving towards each other carry on at the velocity of their combined momentum.
+        let (left_velocity, right_velocity) = (left_player.velocity.x, right_player.velocity.x);
+        let approaching = left_velocity > right_velocity;
+        let common_velocity = (left_mass * left_velocity + right_mass * right_velocity) / total_mass;
+
+        for (id, mass, other_mass, side, velocity) in [
+            (left, left_mass, right_mass, fixed::real(-1.0), left_velocity),
+            (right, right_mass, left_mass, fixed::real(1.0), right_velocity),
+        ] {
+            let player = self.players.get_mut(id).expect("Player should be in the arena");
+            let position = player.position + RealVec2::X * (side * push * other_mass);
+            player.move_to(position, 0.0);
+
+            if approaching {
+                player.add_impulse(RealVec2::X * (mass * (common_velocity - velocity)));
+            }
+        }
+    }
+
+    /// shoves the players inside the melee hitbox of the players who started a melee shove.
+    fn update_melee(&mut self, curr_time: u128, events: &mut Vec<GameEvent>) {
+        let mut shoves = Vec::new();
+
+        for (attacker, player) in self.players.iter() {
+            if !player.has_meleed() {
+                continue;
+            }
+
+            let (min, max) = player.get_melee_hitbox();
+            let momentum = Vec2::new(player.direction * MELEE_MOMENTUM, 0.0);
+            for victim in self.player_grid.query(min, max) {
+                let hit = victim != attacker && self.players.get(victim)
+                    .is_some_and(|p| p.overlaps(min, max) && !p.is_invulnerable(curr_time));
+                if hit && !shoves.contains(&(attacker, victim, momentum)) {
+                    shoves.push((attacker, victim, momentum));
+                }
+            }
+        }
+
+        for (attacker, victim, momentum) in shoves {
+            let player = self.players.get_mut(victim).expect("Player should be in the arena");
+            match player.add_knockback(fixed::real_vec2(momentum), MELEE_DAMAGE, attacker) {
+                Some(momentum) => events.push(GameEvent::PlayerShoved {
+                    attacker,
+                    victim,
+                    damage: MELEE_DAMAGE,
+                    momentum,
+                }),
+
+                None => events.push(GameEvent::ShieldBroken { attacker, victim }),
+            }
+        }
+    }
+
+    /// moves the thrown weapons, removing the ones which fell out of the arena.
+    fn update_thrown_weapons(&mut self, dt: f32) {
+        self.thrown_weapons.retain(|_, weapon| {
+            weapon.update(dt);
+            !Arena::is_out_of_bounds(weapon.get_position())
+        });
+    }
+
+    /// gives the weapon or effect of a pickup to the first player touching it.
+    fn update_pickups(&mut self, curr_time: u128, events: &mut Vec<GameEvent>) {
+        let mut picked_up = Vec::new();
+
+        for (pickup_id, pickup) in self.pickups.iter() {
+            let min = pickup.get_position();
+            let max = min + Vec2::splat(PICKUP_SIZE);
+            let players = &self.players;
+            let player_id = self.player_grid.query(min, max)
+                .find(|id| players.get(*id).is_some_and(|p| p.overlaps(min, max)));
+
+            if let Some(player_id) = player_id {
+                picked_up.push((pickup_id, player_id));
+            }
+        }
+
+        for (pickup_id, player_id) in picked_up {
+            let pickup = self.pickups.remove(pickup_id).expect("Pickup should be in the arena");
+            let player = self.players.get_mut(player_id).expect("Player should be in the arena");
+            match pickup.get_kind() {
+                PickupKind::Weapon(weapontype) => {
+                    player.equip_weapon(weapontype, curr_time);
+                    events.push(GameEvent::WeaponPickedUp { player: player_id, pickup: pickup_id, weapontype });
+                },
+
+                PickupKind::Effect(effecttype) => {
+                    player.add_effect(effecttype, curr_time);
+                    events.push(GameEvent::EffectPickedUp { player: player_id, pickup: pickup_id, effecttype });
+                },
+            }
+        }
     }
 
     /// handles bullets flying off the map or colliding with players.
-    fn update_bullets(&mut self, dt: f32) {
+    fn update_bullets(&mut self, dt: f32, events: &mut Vec<GameEvent>) {
         // Updates all of the bullets' positions. If bullets fly off the map, ends its lifetime,
         // or hits the player, then remove it from the collection. Reports it over the network.
-        let mut to_remove: Vec<u16> = Vec::with_capacity(self.players.len());
-        for (id, bullet) in self.bullets_iterator_mut() {
+        let mut to_remove: Vec<EntityId> = Vec::with_capacity(self.players.len());
+        let mut block_hits = Vec::new();
+        let curr_time = self.get_time();
+        self.rebuild_player_grid();
+
+        for (id, bullet) in self.bullets.iter_mut() {
+            let previous_position = bullet.get_position();
+            bullet.add_force(fixed::real_vec2(self.map.get_wind_force_at(previous_position)), dt);
             bullet.update(dt);
+            let mut start = previous_position;
+
+            // bullets entering a teleporter come out of the center of its partner.
+            if bullet.can_teleport(curr_time) {
+                if let Some((_, exit)) = self.map.get_teleporter_crossed(previous_position, bullet.get_position(), Vec2::ZERO) {
+                    let (min, max) = teleporter::get_teleporter_rect(exit);
+                    bullet.teleport(fixed::real_vec2((min + max) / 2.0), curr_time);
+                    start = bullet.get_position();
+                }
+            }
+
+            // a bullet can hit anyone except the player who fired it, and flies through the
+            // players who just dashed. The whole path of the bullet during the update is checked,
+            // so fast bullets cannot skip over a player, and the first player on the path is hit.
+            let position = bullet.get_position();
+            let owner = bullet.get_owner();
+            let players = &self.players;
+            let victim = self.player_grid.query(start.min(position), start.max(position))
+                .filter(|player_id| *player_id != owner)
+                .filter_map(|player_id| {
+                    let p = players.get(player_id).filter(|p| !p.is_invulnerable(curr_time))?;
+                    let topleft = p.get_position();
+                    let bottomright = topleft + Vec2::new(p.width, p.height);
+                    query::get_segment_entry(start, position, topleft, bottomright).map(|t| (t, player_id))
+                })
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+            // bullets are stopped by the blocks which can be broken, damaging them, unless they
+            // hit a player first.
+            let block_hit = Arena::get_first_breakable_block_hit(&self.map, &self.block_healths, start, position);
+            let victim = match (victim, block_hit) {
+                (Some((player_t, _)), Some((block_t, _))) if block_t < player_t => None,
+                (victim, _) => victim.map(|(_, player_id)| player_id),
+            };
+
+            if let Some(victim_id) = victim {
+                let player = self.players.get_mut(victim_id).expect("Player should be in the arena");
+                let damage = bullet.get_damage();
+                match player.add_knockback(bullet.get_momentum(), damage, owner) {
+                    Some(momentum) => events.push(GameEvent::PlayerHit {
+                        attacker: owner,
+                        victim: victim_id,
+                        bullettype: bullet.get_bullet_type(),
+                        damage,
+                        momentum,
+                    }),
+
+                    None => events.push(GameEvent::ShieldBroken { attacker: owner, victim: victim_id }),
+                }
+                to_remove.push(id);
+                continue;
+            }
+
+            if let Some((_, i)) = block_hit {
+                block_hits.push((i, bullet.get_damage()));
+                to_remove.push(id);
+                continue;
+            }
 
-            let position_x = bullet.get_position().x;
             // removes bullet when flies off the arena.
-            if !(0.0..=ARENA_WIDTH).contains(&position_x) {
-                to_remove.push(*id as u16);
+            if !(0.0..=ARENA_WIDTH).contains(&position.x) || !(0.0..=ARENA_HEIGHT).contains(&position.y) {
+                to_remove.push(id);
             }
         }
 
         for id in to_remove {
-            self.bullets.remove(&id);
+            self.bullets.remove(id);
+        }
+
+        for (i, damage) in block_hits {
+            self.damage_block(i, damage, curr_time, events);
+        }
+
+        self.rebuild_bullet_grid();
+    }
+
+    /// pushes away the players whose center is within `radius` of the `center` of an explosion.
+    /// Players right at the center receive an impulse of `momentum`, which falls off linearly
+    /// to zero at the edge of the explosion. The blocks which can be broken take up to `damage`
+    /// in the same way, and the blocks broken by the explosion are reported by the next update.
+    ///
+    /// Returns the ids of the pushed players, in increasing order.
+    pub fn add_explosion(&mut self, center: Vec2, radius: f32, momentum: f32, damage: f32) -> Vec<EntityId> {
+        let mut pushed = Vec::new();
+        let curr_time = self.get_time();
+        let mut events = std::mem::take(&mut self.pending_events);
+
+        for i in 0..self.blocks.len() {
+            let (row, col) = (i % VERTICAL_BLOCKS, i / VERTICAL_BLOCKS);
+            let block_center = self.get_block_position_at(row, col) + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT) / 2.0;
+            let distance = block_center.distance(center);
+            if distance <= radius {
+                self.damage_block(i, damage * (1.0 - distance / radius), curr_time, &mut events);
+            }
+        }
+        self.pending_events = events;
+
+        let (center, radius) = (fixed::real_vec2(center), fixed::real(radius));
+        for (id, player) in self.players.iter_mut() {
+            let offset = player.position + fixed::real_vec2(Vec2::new(player.width, player.height) / 2.0) - center;
+            let distance = offset.length();
+            if distance > radius || player.is_invulnerable(curr_time) {
+                continue;
+            }
+
+            // a player right at the center is pushed upwards.
+            let direction = if distance > fixed::real(0.0) { offset / distance } else { fixed::real_vec2(Vec2::new(0.0, -1.0)) };
+            player.add_impulse(direction * (fixed::real(momentum) * (fixed::real(1.0) - distance / radius)));
+            pushed.push(id);
         }
+
+        pushed.sort_unstable();
+        pushed
+    }
+
+    /// rebuilds the grid of the players from their current positions.
+    fn rebuild_player_grid(&mut self) {
+        self.player_grid.clear();
+        for (id, player) in self.players.iter() {
+            let position = player.get_position();
+            self.player_grid.insert(id, position, position + Vec2::new(player.width, player.height));
+        }
+    }
+
+    /// rebuilds the grid of the bullets from their current positions.
+    fn rebuild_bullet_grid(&mut self) {
+        self.bullet_grid.clear();
+        for (id, bullet) in self.bullets.iter() {
+            self.bullet_grid.insert_point(id, bullet.get_position());
+        }
+    }
+
+    /// obtains the ids of the players overlapping the rectangle from `min` (top left) to `max`
+    /// (bottom right), in increasing order.
+    ///
+    /// The search goes through the grid of the players, which is rebuilt on every update, so
+    /// players added since the last update are not found.
+    pub fn query_players(&self, min: Vec2, max: Vec2) -> Vec<EntityId> {
+        let mut ids: Vec<EntityId> = self.player_grid.query(min, max)
+            .filter(|id| self.players.get(*id).is_some_and(|p| p.overlaps(min, max)))
+            .collect();
+
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// obtains the cells (row, col) of the blocks overlapping the rectangle from `min` (top left)
+    /// to `max` (bottom right), in increasing order of column then row.
+    pub fn query_blocks(&self, min: Vec2, max: Vec2) -> Vec<(usize, usize)> {
+        let Some((rows, cols)) = query::get_cells_spanned(min, max) else {
+            return Vec::new();
+        };
+
+        self.map.get_occupied_cells(rows, cols).into_iter()
+            .filter(|&(row, col)| {
+                let (block_min, block_max) = query::get_block_rect(row, col);
+                block_min.cmple(max).all() && min.cmple(block_max).all()
+            })
+            .collect()
+    }
+
+    /// obtains the cells (row, col) of the blocks overlapping the circle, in increasing order of
+    /// column then row.
+    pub fn query_blocks_in_circle(&self, center: Vec2, radius: f32) -> Vec<(usize, usize)> {
+        self.query_blocks(center - Vec2::splat(radius), center + Vec2::splat(radius)).into_iter()
+            .filter(|&(row, col)| {
+                let (min, max) = query::get_block_rect(row, col);
+                query::circle_overlaps_rect(center, radius, min, max)
+            })
+            .collect()
+    }
+
+    /// obtains the ids of the players overlapping the circle, in increasing order. Like
+    /// `query_players`, the players added since the last update are not found.
+    pub fn query_players_in_circle(&self, center: Vec2, radius: f32) -> Vec<EntityId> {
+        self.query_players(center - Vec2::splat(radius), center + Vec2::splat(radius)).into_iter()
+            .filter(|id| {
+                self.players.get(*id).is_some_and(|p| {
+                    let topleft = p.get_position();
+                    let bottomright = topleft + Vec2::new(p.width, p.height);
+                    query::circle_overlaps_rect(center, radius, topleft, bottomright)
+                })
+            })
+            .collect()
+    }
+
+    /// casts a ray from `origin` towards `direction`, and obtains the first block or player
+    /// within `max_distance` of the origin it runs into. The player `ignore` (e.g. the player
+    /// casting the ray) is passed through, and so are the players added since the last update.
+    ///
+    /// Every block stops the ray, including the one-way platforms. A ray starting inside a
+    /// block or a player hits it right away.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32, ignore: Option<EntityId>) -> Option<RayHit> {
+        let direction = direction.try_normalize()?;
+        let end = origin + direction * max_distance;
+
+        let block_hit = self.get_first_block_hit(origin, end).map(|(t, row, col)| {
+            let blocktype = self.get_block(row, col).expect("Block should be in the arena");
+            (t, RayTarget::Block { row, col, blocktype })
+        });
+
+        let player_hit = self.query_players(origin.min(end), origin.max(end)).into_iter()
+            .filter(|id| Some(*id) != ignore)
+            .filter_map(|id| {
+                let player = self.players.get(id)?;
+                let topleft = player.get_position();
+                let bottomright = topleft + Vec2::new(player.width, player.height);
+                query::get_segment_entry(origin, end, topleft, bottomright).map(|t| (t, RayTarget::Player(id)))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        [block_hit, player_hit].into_iter()
+            .flatten()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(t, target)| RayHit { target, point: origin.lerp(end, t), distance: t * max_distance })
+    }
+
+    /// whether no block stands between the points `from` and `to`. Players do not block the
+    /// line of sight.
+    pub fn has_line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        self.get_first_block_hit(from, to).is_none()
+    }
+
+    /// obtains the end of a platform closest to the point, if there are any blocks.
+    pub fn get_nearest_platform_edge(&self, point: Vec2) -> Option<PlatformEdge> {
+        self.map.get_platform_ends().into_iter()
+            .map(|(row, col, side)| {
+                let (min, max) = query::get_block_rect(row, col);
+                let x = if side < 0.0 { min.x } else { max.x };
+                PlatformEdge { row, col, point: Vec2::new(x, min.y), side }
+            })
+            .min_by(|a, b| a.point.distance_squared(point).total_cmp(&b.point.distance_squared(point)))
+    }
+
+    /// obtains the fraction of the way from `start` to `end` at which the segment enters its
+    /// first block, along with the row and column of the block.
+    fn get_first_block_hit(&self, start: Vec2, end: Vec2) -> Option<(f32, usize, usize)> {
+        let (rows, cols) = query::get_cells_spanned(start.min(end), start.max(end))?;
+        self.map.get_occupied_cells(rows, cols).into_iter()
+            .filter_map(|(row, col)| {
+                let (min, max) = query::get_block_rect(row, col);
+                query::get_segment_entry(start, end, min, max).map(|t| (t, row, col))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    /// obtains the fraction of the way from `start` to `end` at which the segment enters its
+    /// first block which can be broken, along with the index of the block in the grid.
+    fn get_first_breakable_block_hit(map: &Map,
+                                     block_healths: &[Option<f32>],
+                                     start: Vec2,
+                                     end: Vec2) -> Option<(f32, usize)> {
+
+        let (rows, cols) = query::get_cells_spanned(start.min(end), start.max(end))?;
+        map.get_occupied_cells(rows, cols).into_iter()
+            .map(|(row, col)| (row, col, col * VERTICAL_BLOCKS + row))
+            .filter(|&(_, _, i)| block_healths[i].is_some())
+            .filter_map(|(row, col, i)| {
+                let (min, max) = query::get_block_rect(row, col);
+                query::get_segment_entry(start, end, min, max).map(|t| (t, i))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+
+    /// obtains the ids of the bullets inside the rectangle from `min` (top left) to `max`
+    /// (bottom right), in increasing order.
+    ///
+    /// The search goes through the grid of the bullets, which is rebuilt on every update, so
+    /// bullets added since the last update are not found.
+    pub fn query_bullets(&self, min: Vec2, max: Vec2) -> Vec<EntityId> {
+        let mut ids: Vec<EntityId> = self.bullet_grid.query(min, max)
+            .filter(|id| {
+                self.bullets.get(*id).is_some_and(|b| {
+                    let position = b.get_position();
+                    min.cmple(position).all() && position.cmple(max).all()
+                })
+            })
+            .collect();
+
+        ids.sort_unstable();
+        ids
     }
 
     /// updates the players in the arena based on their respective inputs.
+    ///
+    /// Continuous forces (weight, friction, running) act over `dt`, while jumps and recoil are
+    /// impulses which change the velocity right away.
+    #[allow(clippy::too_many_arguments)]
     fn update_player(player: &mut Player,
+                     id: EntityId,
                      input: InputMask,
                      dt: f32,
-                     next_bullet_id: &mut u16,
-                     bullets: &mut HashMap<u16, Bullet>,
+                     curr_time: u128,
+                     bullets: &mut EntityStore<Bullet>,
                      map: &Map,
-                     map_blocks: &[Option<BlockType>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS]) {
+                     map_blocks: &[Option<BlockType>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS],
+                     events: &mut Vec<GameEvent>) {
 
         // inputs
         let left_input = input.has_mask(Input::Left) as u8 as f32 * -1.0;
         let right_input = input.has_mask(Input::Right) as u8 as f32;
         let direction = left_input + right_input;
-        let jump_input = input.has_mask(Input::Up);
         let shoot_input = input.has_mask(Input::Shoot);
 
+        // while shooting, up and down aim the weapon instead of jumping or dropping down.
+        let up_input = input.has_mask(Input::Up);
+        let down_input = input.has_mask(Input::Down);
+        let jump_input = up_input && !shoot_input;
+        let vertical_aim = if shoot_input { down_input as u8 as f32 - up_input as u8 as f32 } else { 0.0 };
+        player.set_aim_input(direction, vertical_aim);
+
+        let dash_input = input.has_mask(Input::Dash);
+        let reload_input = input.has_mask(Input::Reload);
+        player.reload(reload_input, curr_time);
+
+        // shoving is the fallback attack while the weapon is reloading.
+        let melee_input = input.has_mask(Input::Melee) || (shoot_input && player.is_reloading(curr_time));
+
+        // slows down the player once its dash is over, before the forces of this tick are added.
+        player.update_dash(curr_time);
+
         // important positions
-        let player_bottom = player.position + Vec2::new(0.0, player.height);
+        let player_bottom = player.get_position() + Vec2::new(0.0, player.height);
         let left_grid_position = Arena::to_row_col(player_bottom);
         let right_grid_position = Arena::to_row_col(player_bottom + Vec2::new(player.width, 0.0));
 
         // TODO: calculates the acceleration experienced by the player, with all variables and
         // inputs accounted for.
         let mut lowest_block_y: f32 = ARENA_HEIGHT + player.height;
-        let mut block_friction = Vec2::ZERO;
-        let mut run_friction = direction * Vec2::new(AIR_FRICTION, 0.0);
-        let mut bullet_hit = Vec2::ZERO;
-        let mut run: Vec2;
+        let mut block_friction = RealVec2::ZERO;
+        let mut run_friction = RealVec2::X * fixed::real(direction * AIR_FRICTION);
+        let mut bullet_hit = RealVec2::ZERO;
+        let mut run: RealVec2;
         let mut drop_input: bool = false;
         let mut standing_on_block = false;
         let mut standing_on_blocktype: Option<BlockType> = None;
@@ -232,42 +983,58 @@ impl Arena {
         if let Some((row, col)) = first_rowcol_below_opt {
             // we are standing on block if y position is lowest and player is falling.
             lowest_block_y = Arena::get_block_row_position(row);
-            standing_on_block = player_bottom.y == lowest_block_y && player.velocity.y <= 0.0;
+            let bottom = player.position.y + fixed::real(player.height);
+            standing_on_block = bottom == fixed::real(lowest_block_y) && player.velocity.y <= fixed::real(0.0);
 
             if standing_on_block {
                 // sets player's velocity y component to zero.
-                player.velocity.y = 0.0;
+                player.velocity.y = fixed::real(0.0);
 
                 // we are already on a block, so the blocktype should not be None
                 standing_on_blocktype = map_blocks[col * VERTICAL_BLOCKS + row];
 
-                // can only drop down if we are standing on block, and not on the lowest platform.
-                drop_input = input.has_mask(Input::Down) && row != VERTICAL_BLOCKS - 1;
+                // can only drop down if we are standing on a block which is not solid, and not on
+                // the lowest platform.
+                let solid = standing_on_blocktype.is_some_and(block::is_block_solid);
+                drop_input = down_input && !shoot_input && !solid && row != VERTICAL_BLOCKS - 1;
             }
         }
 
         // Manages the normal force
         let normal_force = player.get_normal(standing_on_block);
-        let fric_direction: f32 = -normalize_float(player.velocity.x);
+        let fric_direction: f32 = -normalize_float(fixed::to_f32(player.velocity.x));
 
         // if player's velocity is normalized to be 0, then we can directly set it to
         // prevent floating point rounding errors.
-        player.velocity.x *= fric_direction.abs();
+        player.velocity.x *= fixed::real(fric_direction.abs());
 
         if let Some(blocktype) = standing_on_blocktype {
-            let coeff_friction = block::get_block_friction(blocktype);
+            let coeff_friction = fixed::real(block::get_block_friction(blocktype));
             let normal_magnitude = normal_force.y.abs();
 
             // compares frictional force to force required to set player's velocity to 0,
             // then choose the smaller of the two magnitudes.
-            let mut fric_magnitude = player.velocity.x.abs() * player.get_total_mass() / dt;
-            fric_magnitude = f32::min(coeff_friction * normal_magnitude, fric_magnitude);
-            block_friction = fric_direction * fric_magnitude * Vec2::X;
+            let mut fric_magnitude = player.velocity.x.abs() * fixed::real(player.get_total_mass()) / fixed::real(dt);
+            fric_magnitude = fric_magnitude.min(coeff_friction * normal_magnitude);
+            block_friction = RealVec2::X * (fixed::real(fric_direction) * fric_magnitude);
 
             // run_friction: the force to get the player moving (static friction).
-            run_friction = direction * coeff_friction * normal_magnitude * Vec2::X;
+            run_friction = RealVec2::X * (fixed::real(direction) * coeff_friction * normal_magnitude);
+        }
+
+
+        // players falling into (or standing in) lava are burned and bounce back up.
+        let topleft = player.get_position();
+        let bottomright = topleft + Vec2::new(player.width, player.height);
+        let burned = map.get_hazard_touching(topleft, bottomright) == Some(HazardType::Lava)
+            && player.velocity.y >= fixed::real(0.0);
+        if burned {
+            player.set_damage(player.get_damage() + LAVA_DAMAGE);
+            player.velocity.y = fixed::real(LAVA_BOUNCE_VELOCITY.y);
+            events.push(GameEvent::HazardTouched { player: id, hazardtype: HazardType::Lava, damage: LAVA_DAMAGE });
         }
 
+        let wind = fixed::real_vec2(map.get_wind_force_at(topleft + Vec2::new(player.width, player.height) / 2.0));
 
         // Disallows any acceleration input that is in the same direction as the player's
         // velocity if the player's velocity is already above its speed_cap.
@@ -276,33 +1043,136 @@ impl Arena {
         // against the maximum allowed acceleration to reach the speed cap rather than just zeroing
         // out the run input. This would probably result in a more "consistent" usage of the
         // speed_cap.
-        let multiplier = 2.0;
+        let multiplier = fixed::real(2.0);
         run = multiplier * run_friction;
-        if (run.x * player.velocity.x > 0.0) && (player.velocity.x.abs() >= player.speed_cap) {
-            run = Vec2::ZERO;
+        if (run.x * player.velocity.x > fixed::real(0.0)) && (player.velocity.x.abs() >= fixed::real(player.get_speed_cap())) {
+            run = RealVec2::ZERO;
         }
 
         // updates the player after calculating all the applied forces above.
         player
             .add_weight_force()
             .add_normal_force(standing_on_block)
-            .add_jump_force(standing_on_block, jump_input)
-            .add_recoil_force(shoot_input, dt, next_bullet_id, bullets)
+            .add_jump_impulse(standing_on_block, jump_input, curr_time)
+            .add_dash_impulse(standing_on_block, dash_input, direction, curr_time)
+            .add_recoil_impulse(shoot_input, curr_time, id, bullets, events)
             .add_force(block_friction)
             .add_force(bullet_hit)
+            .add_force(wind)
             .add_force(run);
 
+        player.melee(melee_input, curr_time);
+
+        if player.has_jumped() {
+            events.push(GameEvent::Jumped { player: id, midair: !standing_on_block });
+        }
+
+        if player.has_dashed() {
+            events.push(GameEvent::Dashed { player: id, midair: !standing_on_block });
+        }
+
+        if standing_on_block && !player.was_standing {
+            events.push(GameEvent::Landed { player: id });
+        }
+        player.was_standing = standing_on_block;
+
+        let previous_position = player.position;
         player.update(dt, lowest_block_y, drop_input, direction);
+        Arena::collide_solid_blocks(player, previous_position, map_blocks);
+        Arena::teleport_player(player, id, previous_position, curr_time, map, events);
     }
 
+    /// teleports the player if it entered a teleporter since `previous_position`. The player
+    /// comes out centered on the partner teleporter.
+    fn teleport_player(player: &mut Player,
+                       id: EntityId,
+                       previous_position: RealVec2,
+                       curr_time: u128,
+                       map: &Map,
+                       events: &mut Vec<GameEvent>) {
+
+        if !player.can_teleport(curr_time) {
+            return;
+        }
 
-    /// mutable iterator through all the bullets on the map
-    fn bullets_iterator_mut(&mut self) -> impl Iterator<Item = (&u16, &mut Bullet)> + '_ {
-        self.bullets.iter_mut()
+        let half_size = Vec2::new(player.width, player.height) / 2.0;
+        let (previous_center, center) = (fixed::to_vec2(previous_position) + half_size, player.get_position() + half_size);
+        if let Some((from, to)) = map.get_teleporter_crossed(previous_center, center, half_size) {
+            let (min, max) = teleporter::get_teleporter_rect(to);
+            player.teleport(fixed::real_vec2((min + max) / 2.0 - half_size), curr_time);
+            events.push(GameEvent::PlayerTeleported { player: id, from, to });
+        }
     }
 
+    /// stops the player at the sides and bottoms of the solid blocks it moved into since
+    /// `previous_position`. Landing on top of the blocks is handled by `update_player`.
+    ///
+    /// The horizontal movement is resolved first, then the vertical movement, so a player
+    /// sliding along a wall or a ceiling keeps moving along it.
+    fn collide_solid_blocks(player: &mut Player,
+                            previous_position: RealVec2,
+                            map_blocks: &[Option<BlockType>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS]) {
+
+        let size = fixed::real_vec2(Vec2::new(player.width, player.height));
+        let target = player.position;
+        let mut position = RealVec2::new(target.x, previous_position.y);
+
+        for block in Arena::get_solid_blocks_overlapping(map_blocks, position, position + size) {
+            if target.x > previous_position.x {
+                position.x = fixed::real(block.x) - size.x;
+            } else if target.x < previous_position.x {
+                position.x = fixed::real(block.x) + fixed::real(block.w);
+            }
+            player.velocity.x = fixed::real(0.0);
+        }
+
+        position.y = target.y;
+        for block in Arena::get_solid_blocks_overlapping(map_blocks, position, position + size) {
+            if target.y < previous_position.y {
+                position.y = fixed::real(block.y) + fixed::real(block.h);
+            } else if target.y > previous_position.y {
+                position.y = fixed::real(block.y) - size.y;
+            }
+            player.velocity.y = fixed::real(0.0);
+        }
+
+        if position != target {
+            player.move_to(position, 0.0);
+        }
+    }
+
+    /// obtains the solid blocks overlapping the rectangle from `min` (top left) to `max` (bottom
+    /// right). Only touching the edge of a block does not count as overlapping.
+    fn get_solid_blocks_overlapping(map_blocks: &[Option<BlockType>; VERTICAL_BLOCKS * HORIZONTAL_BLOCKS],
+                                    min: RealVec2,
+                                    max: RealVec2) -> Vec<BlockRect> {
+
+        // only the blocks in the columns and rows spanned by the rectangle can overlap it.
+        let mut blocks = Vec::new();
+        let Some((rows, cols)) = query::get_cells_spanned(fixed::to_vec2(min), fixed::to_vec2(max)) else {
+            return blocks;
+        };
+
+        for col in cols {
+            for row in rows.clone() {
+                let solid = map_blocks[col * VERTICAL_BLOCKS + row].filter(|b| block::is_block_solid(*b));
+
+                if let Some(blocktype) = solid {
+                    let (x, y) = (Arena::get_block_col_position(col), Arena::get_block_row_position(row));
+                    let (w, h) = (BLOCK_WIDTH, BLOCK_HEIGHT);
+                    let (block_min, block_max) = (fixed::real_vec2(Vec2::new(x, y)), fixed::real_vec2(Vec2::new(x, y)) + fixed::real_vec2(Vec2::new(w, h)));
+                    if min.x < block_max.x && block_min.x < max.x && min.y < block_max.y && block_min.y < max.y {
+                        blocks.push(BlockRect { x, y, w, h, blocktype });
+                    }
+                }
+            }
+        }
+        blocks
+    }
+
+
     /// iterator through all the bullets on the map
-    pub fn bullets_iterator(&self) -> impl Iterator<Item = (&u16, &Bullet)> + '_ {
+    pub fn bullets_iterator(&self) -> impl Iterator<Item = (EntityId, &Bullet)> + '_ {
         self.bullets.iter()
     }
 
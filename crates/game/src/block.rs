@@ -1,15 +1,17 @@
 use std::fmt::Display;
 
 use strum::EnumCount;
-use strum_macros::{EnumCount, EnumIter};
+use strum_macros::{EnumCount, EnumIter, FromRepr};
 use BlockType::*;
 
 
 /// types of blocks
-#[derive(Debug, Clone, Copy, EnumIter, EnumCount)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumCount, FromRepr)]
 pub enum BlockType {
     GrassBlock,
     IceBlock,
+    StoneBlock,
+    CrateBlock,
 }
 
 impl Display for BlockType {
@@ -27,15 +29,59 @@ pub(crate) const BLOCK_FRICTIONS: [f32; BlockType::COUNT] = {
 
     frictions[GrassBlock as usize] = 0.8;
     frictions[IceBlock as usize] = 0.2;
+    frictions[StoneBlock as usize] = 0.6;
+    frictions[CrateBlock as usize] = 0.7;
     frictions
 };
 
+/// whether the blocks are solid. Players collide with solid blocks from every side, while the
+/// other blocks are one-way platforms which can be jumped up through and dropped down from.
+pub(crate) const BLOCK_SOLIDS: [bool; BlockType::COUNT] = {
+    let mut solids = [false; BlockType::COUNT];
+
+    solids[StoneBlock as usize] = true;
+    solids[CrateBlock as usize] = true;
+    solids
+};
+
+/// health of the blocks which can be broken by bullets and explosions, or None for the blocks
+/// which cannot be broken.
+pub(crate) const BLOCK_HEALTHS: [Option<f32>; BlockType::COUNT] = {
+    let mut healths = [None; BlockType::COUNT];
+
+    healths[CrateBlock as usize] = Some(30.0);
+    healths
+};
+
+/// time in ms after which a broken block grows back, or None if it stays broken.
+pub(crate) const BLOCK_REGROW_TIMES: [Option<u128>; BlockType::COUNT] = {
+    let mut regrow_times = [None; BlockType::COUNT];
+
+    regrow_times[CrateBlock as usize] = Some(15000);
+    regrow_times
+};
+
 /// obtains the coefficient of friction of a block with type `blocktype`
 pub(crate) fn get_block_friction(blocktype: BlockType) -> f32 {
     let i = blocktype as usize;
     BLOCK_FRICTIONS[i]
 }
 
+/// whether a block with type `blocktype` is solid.
+pub(crate) fn is_block_solid(blocktype: BlockType) -> bool {
+    BLOCK_SOLIDS[blocktype as usize]
+}
+
+/// obtains the health of a block with type `blocktype`, or None if it cannot be broken.
+pub(crate) fn get_block_health(blocktype: BlockType) -> Option<f32> {
+    BLOCK_HEALTHS[blocktype as usize]
+}
+
+/// obtains the time in ms a broken block with type `blocktype` takes to grow back, if it does.
+pub(crate) fn get_block_regrow_time(blocktype: BlockType) -> Option<u128> {
+    BLOCK_REGROW_TIMES[blocktype as usize]
+}
+
 /// represents a rectangle of the block.
 pub struct BlockRect {
     pub x: f32,
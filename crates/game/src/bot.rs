@@ -0,0 +1,244 @@
+/// Computer controlled players.
+///
+/// A bot only reads the state of the arena and produces an `InputMask` each tick, the same way
+/// a remote player would send its inputs over the network. This means the arena does not need to
+/// know whether a player is controlled by a person or by a bot.
+use glam::Vec2;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use strum::EnumCount;
+use strum_macros::EnumCount;
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::input::{Input, InputMask};
+use crate::map::HORIZONTAL_BLOCKS;
+use crate::player::Player;
+use crate::{BLOCK_WIDTH, MELEE_RANGE};
+use Difficulty::*;
+
+/// difficulty levels of the bots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// number of ticks an enemy has to stay in the line of fire before the bot starts shooting.
+pub(crate) const REACTION_TICKS: [u32; Difficulty::COUNT] = {
+    let mut ticks = [0; Difficulty::COUNT];
+
+    ticks[Easy as usize] = 30;
+    ticks[Normal as usize] = 15;
+    ticks[Hard as usize] = 5;
+    ticks
+};
+
+/// maximum error (in pixels) of the bot's estimate of an enemy's height. A new error is rolled
+/// every time the bot acquires a target.
+pub(crate) const AIM_ERRORS: [f32; Difficulty::COUNT] = {
+    let mut errors = [0.0; Difficulty::COUNT];
+
+    errors[Easy as usize] = 40.0;
+    errors[Normal as usize] = 20.0;
+    errors[Hard as usize] = 4.0;
+    errors
+};
+
+/// horizontal distance the bot tries to keep from its target.
+const PREFERRED_DISTANCE: f32 = 2.0 * BLOCK_WIDTH;
+
+/// A bot controlling the player with id `id` in the arena.
+#[derive(Debug)]
+pub struct Bot {
+    id: EntityId,
+    difficulty: Difficulty,
+    rng: StdRng,
+    target: Option<EntityId>,
+    ticks_on_target: u32,
+    aim_offset: f32,
+}
+
+impl Bot {
+    pub fn new(id: EntityId, difficulty: Difficulty, seed: u64) -> Self {
+        let rng = StdRng::seed_from_u64(seed);
+        Self { id, difficulty, rng, target: None, ticks_on_target: 0, aim_offset: 0.0 }
+    }
+
+    pub fn get_id(&self) -> EntityId {
+        self.id
+    }
+
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// decides the input of the bot for the next tick, based on the current state of `arena`.
+    ///
+    /// If the bot's player is not in the arena, then the empty input is returned.
+    pub fn think(&mut self, arena: &Arena) -> InputMask {
+        let mut input = InputMask::new();
+        let player = match arena.get_player(self.id) {
+            Some(player) => player,
+            None => return input,
+        };
+
+        // getting back on the stage is always more important than fighting.
+        if !Bot::is_above_stage(arena, player.get_position() + Vec2::new(player.width / 2.0, player.height)) {
+            self.ticks_on_target = 0;
+            Bot::recover(arena, player, &mut input);
+            return input;
+        }
+
+        let center = get_center(player);
+        let move_direction = match self.find_target(arena, center) {
+            Some(target_center) => self.engage(arena, player, target_center, &mut input),
+            None => {
+                // no one to fight, so walk back towards the middle of the stage.
+                let middle = Arena::get_block_col_position(HORIZONTAL_BLOCKS / 2);
+                let dx = middle - center.x;
+                if dx.abs() > BLOCK_WIDTH / 2.0 { dx.signum() } else { 0.0 }
+            },
+        };
+
+        // never walk off an edge unless we are about to jump.
+        let jumping = input.has_mask(Input::Up);
+        if move_direction != 0.0 && (jumping || Bot::has_ground_ahead(arena, player, move_direction)) {
+            Bot::add_direction(&mut input, move_direction);
+        }
+
+        input
+    }
+
+    /// chooses the closest enemy as the target, and returns the target's center. Resets the
+    /// reaction timer and rolls a new aiming error whenever the target changes.
+    fn find_target(&mut self, arena: &Arena, center: Vec2) -> Option<Vec2> {
+        let closest = arena.get_players()
+            .iter()
+            .filter(|(id, _)| *id != self.id)
+            .filter(|(_, p)| Bot::is_above_stage(arena, p.get_position() + Vec2::new(p.width / 2.0, p.height)))
+            .map(|(id, p)| (id, get_center(p)))
+            .min_by(|(_, a), (_, b)| {
+                let da = a.distance_squared(center);
+                let db = b.distance_squared(center);
+                da.total_cmp(&db)
+            });
+
+        let target_id = closest.map(|(id, _)| id);
+        if target_id != self.target {
+            self.target = target_id;
+            self.ticks_on_target = 0;
+            self.reroll_aim();
+        }
+
+        closest.map(|(_, target_center)| target_center)
+    }
+
+    /// fights the target located at `target_center`. Returns the direction the bot wants to walk
+    /// in (-1.0, 0.0 or +1.0).
+    fn engage(&mut self, arena: &Arena, player: &Player, target_center: Vec2, input: &mut InputMask) -> f32 {
+        let center = get_center(player);
+        let dx = target_center.x - center.x;
+        let dy = target_center.y + self.aim_offset - center.y;
+        let facing = if dx < 0.0 { -1.0 } else { 1.0 };
+
+        let mut move_direction = 0.0;
+        if dy.abs() <= player.height / 2.0 {
+            // enemy is level with the bot, so turn around and shoot once reacted.
+            // turning around is allowed even at an edge, since a single tick barely moves us.
+            self.ticks_on_target += 1;
+            if player.direction != facing {
+                Bot::add_direction(input, facing);
+            }
+
+            if self.ticks_on_target > REACTION_TICKS[self.difficulty as usize] {
+                // shoves enemies who are close enough instead of shooting them.
+                let in_reach = dx.abs() <= player.width + MELEE_RANGE;
+                input.add_mask(if in_reach { Input::Melee } else { Input::Shoot });
+            }
+        } else {
+            if self.ticks_on_target > 0 {
+                self.ticks_on_target = 0;
+                self.reroll_aim();
+            }
+
+            // enemy is on another platform. Jump up or drop down towards it.
+            let standing = Bot::is_standing(arena, player);
+            if dy < 0.0 && (standing || player.get_velocity().y > 0.0) {
+                input.add_mask(Input::Up);
+            } else if dy > 0.0 && standing {
+                input.add_mask(Input::Down);
+            }
+        }
+
+        if dx.abs() > PREFERRED_DISTANCE {
+            move_direction = facing;
+        }
+
+        move_direction
+    }
+
+    /// steers the player back above the closest column containing a block, jumping whenever the
+    /// player is falling.
+    fn recover(arena: &Arena, player: &Player, input: &mut InputMask) {
+        let center = get_center(player);
+        let map = arena.get_map();
+
+        let closest_col = (0..HORIZONTAL_BLOCKS)
+            .filter(|&col| map.first_row_below(0, col).is_some())
+            .map(|col| Arena::get_block_col_position(col) + BLOCK_WIDTH / 2.0)
+            .min_by(|a, b| (a - center.x).abs().total_cmp(&(b - center.x).abs()));
+
+        if let Some(x) = closest_col {
+            Bot::add_direction(input, (x - center.x).signum());
+        }
+
+        if player.get_velocity().y > 0.0 {
+            input.add_mask(Input::Up);
+        }
+    }
+
+    /// rolls the error of the bot's estimate of its target's height.
+    fn reroll_aim(&mut self) {
+        let error = AIM_ERRORS[self.difficulty as usize];
+        self.aim_offset = self.rng.gen_range(-error..=error);
+    }
+
+    /// whether there is a block somewhere below `point`.
+    fn is_above_stage(arena: &Arena, point: Vec2) -> bool {
+        match Arena::to_row_col(point) {
+            Some((row, col)) => arena.get_map().first_row_below(row, col).is_some(),
+            None => false,
+        }
+    }
+
+    /// whether the player is standing directly on top of a block.
+    fn is_standing(arena: &Arena, player: &Player) -> bool {
+        let feet = player.get_position() + Vec2::new(player.width / 2.0, player.height);
+        let row_below = Arena::to_row_col(feet)
+            .and_then(|(row, col)| arena.get_map().first_row_below(row, col));
+
+        match row_below {
+            Some(row) => Arena::get_block_row_position(row) == feet.y,
+            None => false,
+        }
+    }
+
+    /// whether walking in `direction` keeps the player above the stage.
+    fn has_ground_ahead(arena: &Arena, player: &Player, direction: f32) -> bool {
+        let lookahead = direction * (player.width / 2.0 + player.width);
+        let feet = player.get_position() + Vec2::new(player.width / 2.0 + lookahead, player.height);
+        Bot::is_above_stage(arena, feet)
+    }
+
+    fn add_direction(input: &mut InputMask, direction: f32) {
+        if direction < 0.0 {
+            input.add_mask(Input::Left);
+        } else if direction > 0.0 {
+            input.add_mask(Input::Right);
+        }
+    }
+}
+
+fn get_center(player: &Player) -> Vec2 {
+    player.get_position() + Vec2::new(player.width, player.height) / 2.0
+}
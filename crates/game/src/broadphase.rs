@@ -0,0 +1,87 @@
+/// Uniform grid over the arena, used to find the entities close to a point or a rectangle
+/// without checking every pair of entities.
+///
+/// Each cell of the grid is `BLOCK_WIDTH` wide and `VERTICAL_BLOCK_SPACING` tall, which is the
+/// same spacing as the blocks of the map. Anything outside of the arena is clamped into the
+/// closest cell on the border, so entities flying off the arena can still be found.
+use glam::Vec2;
+use crate::{ARENA_WIDTH, ARENA_HEIGHT, BLOCK_WIDTH, VERTICAL_BLOCK_SPACING};
+
+/// number of columns of cells in the grid.
+pub const GRID_COLUMNS: usize = (ARENA_WIDTH / BLOCK_WIDTH) as usize + 1;
+
+/// number of rows of cells in the grid.
+pub const GRID_ROWS: usize = (ARENA_HEIGHT / VERTICAL_BLOCK_SPACING) as usize + 1;
+
+/// The grid, with `K` being the key (id) of the entities stored.
+#[derive(Debug, Clone)]
+pub struct Broadphase<K> {
+    /// cells of the grid, stored column-wise like the blocks of the map.
+    cells: Vec<Vec<K>>,
+}
+
+impl<K: Copy> Default for Broadphase<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Copy> Broadphase<K> {
+    pub fn new() -> Self {
+        let cells = vec![Vec::new(); GRID_COLUMNS * GRID_ROWS];
+        Self { cells }
+    }
+
+    /// removes every entity from the grid. Keeps the memory of the cells, so rebuilding
+    /// the grid every tick does not reallocate.
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+    }
+
+    /// inserts the entity `key` occupying the rectangle from `min` (top left) to `max`
+    /// (bottom right) into every cell it overlaps.
+    pub fn insert(&mut self, key: K, min: Vec2, max: Vec2) {
+        let (min_col, min_row) = Broadphase::<K>::to_cell(min);
+        let (max_col, max_row) = Broadphase::<K>::to_cell(max);
+
+        for col in min_col..=max_col {
+            for row in min_row..=max_row {
+                self.cells[col * GRID_ROWS + row].push(key);
+            }
+        }
+    }
+
+    /// inserts the entity `key` located at a single point.
+    pub fn insert_point(&mut self, key: K, point: Vec2) {
+        let (col, row) = Broadphase::<K>::to_cell(point);
+        self.cells[col * GRID_ROWS + row].push(key);
+    }
+
+    /// iterates over the entities in the cell containing `point`.
+    pub fn query_point(&self, point: Vec2) -> impl Iterator<Item = K> + '_ {
+        let (col, row) = Broadphase::<K>::to_cell(point);
+        self.cells[col * GRID_ROWS + row].iter().copied()
+    }
+
+    /// iterates over the entities in the cells overlapping the rectangle from `min` to `max`.
+    ///
+    /// Entities spanning more than one of the cells are returned once for each cell, and the
+    /// entities are only close to the rectangle, so callers still need to do the exact check.
+    pub fn query(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = K> + '_ {
+        let (min_col, min_row) = Broadphase::<K>::to_cell(min);
+        let (max_col, max_row) = Broadphase::<K>::to_cell(max);
+
+        (min_col..=max_col)
+            .flat_map(move |col| (min_row..=max_row).map(move |row| col * GRID_ROWS + row))
+            .flat_map(move |i| self.cells[i].iter().copied())
+    }
+
+    /// obtains the (column, row) of the cell containing the point, clamped to the grid.
+    fn to_cell(point: Vec2) -> (usize, usize) {
+        let col = (point.x / BLOCK_WIDTH).floor().clamp(0.0, (GRID_COLUMNS - 1) as f32);
+        let row = (point.y / VERTICAL_BLOCK_SPACING).floor().clamp(0.0, (GRID_ROWS - 1) as f32);
+        (col as usize, row as usize)
+    }
+}
@@ -0,0 +1,82 @@
+/// A list of all the character classes and their various properties
+///
+/// A class sets the body of the player (its mass and size), how fast it runs, how it jumps,
+/// and the weapon it starts with. Heavier characters are harder to knock back, since the same
+/// momentum gives them less velocity.
+use glam::{Vec2, const_vec2};
+use strum::EnumCount;
+use strum_macros::{Display, EnumCount, FromRepr};
+use crate::weaponscatalog::WeaponType;
+use crate::{JUMP_VELOCITY, PLAYER_HEIGHT, PLAYER_MASS, PLAYER_SPEED_CAP, PLAYER_WIDTH};
+use CharacterClass::*;
+
+/// Class of the character played by a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount, FromRepr, Display)]
+pub enum CharacterClass {
+    /// the all-rounder.
+    Brawler,
+    /// light and fast with a triple jump, but easily knocked back.
+    Scout,
+    /// heavy and slow, but hard to knock back.
+    Tank,
+}
+
+/// mass of each class.
+pub const CLASS_MASSES: [f32; CharacterClass::COUNT] = {
+    let mut masses = [0.0; CharacterClass::COUNT];
+
+    masses[Brawler as usize] = PLAYER_MASS;
+    masses[Scout as usize] = 30.0;
+    masses[Tank as usize] = 90.0;
+    masses
+};
+
+/// width and height of each class.
+pub const CLASS_SIZES: [Vec2; CharacterClass::COUNT] = {
+    let mut sizes = [Vec2::ZERO; CharacterClass::COUNT];
+
+    sizes[Brawler as usize] = const_vec2!([PLAYER_WIDTH, PLAYER_HEIGHT]);
+    sizes[Scout as usize] = const_vec2!([26.0, 28.0]);
+    sizes[Tank as usize] = const_vec2!([40.0, 40.0]);
+    sizes
+};
+
+/// running speed cap of each class.
+pub const CLASS_SPEED_CAPS: [f32; CharacterClass::COUNT] = {
+    let mut speed_caps = [0.0; CharacterClass::COUNT];
+
+    speed_caps[Brawler as usize] = PLAYER_SPEED_CAP;
+    speed_caps[Scout as usize] = 520.0;
+    speed_caps[Tank as usize] = 300.0;
+    speed_caps
+};
+
+/// number of jumps (ground jump included) of each class.
+pub const CLASS_JUMPS_COUNTS: [u8; CharacterClass::COUNT] = {
+    let mut jumps_counts = [0; CharacterClass::COUNT];
+
+    jumps_counts[Brawler as usize] = 2;
+    jumps_counts[Scout as usize] = 3;
+    jumps_counts[Tank as usize] = 2;
+    jumps_counts
+};
+
+/// velocity gained by jumping for each class (before accounting for the mass of the weapon).
+pub const CLASS_JUMP_VELOCITIES: [Vec2; CharacterClass::COUNT] = {
+    let mut velocities = [Vec2::ZERO; CharacterClass::COUNT];
+
+    velocities[Brawler as usize] = JUMP_VELOCITY;
+    velocities[Scout as usize] = const_vec2!([0.0, -540.0]);
+    velocities[Tank as usize] = const_vec2!([0.0, -440.0]);
+    velocities
+};
+
+/// weapon each class starts with, and gets back after respawning.
+pub const CLASS_WEAPONS: [WeaponType; CharacterClass::COUNT] = {
+    let mut weapons = [WeaponType::BasicPistol; CharacterClass::COUNT];
+
+    weapons[Brawler as usize] = WeaponType::BasicPistol;
+    weapons[Scout as usize] = WeaponType::BasicPistol;
+    weapons[Tank as usize] = WeaponType::AssaultRifle;
+    weapons
+};
@@ -0,0 +1,112 @@
+/// Timed power-ups and status effects on the players.
+///
+/// Effects are obtained by walking into effect pickups, and last for a duration of simulation
+/// time. Picking up an effect which is already active follows the stacking rule of the effect.
+use strum::EnumCount;
+use strum_macros::{Display, EnumCount, EnumIter, FromRepr};
+use EffectType::*;
+use Stacking::*;
+
+/// Type of the status effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr, Display)]
+pub enum EffectType {
+    /// raises the speed cap of the player.
+    SpeedBoost,
+    /// gives the player an extra mid-air jump.
+    ExtraJump,
+    /// raises the mass of the player, so hits push the player less.
+    HeavyBody,
+    /// absorbs the next hit taken by the player.
+    Shield,
+    /// the weapon of the player never runs out of bullets.
+    InfiniteAmmo,
+}
+
+/// What happens when an effect is picked up while it is still active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// the duration starts over.
+    Refresh,
+    /// the duration is added to the remaining duration, up to `MAX_EFFECT_DURATION`.
+    Extend,
+}
+
+/// duration of the effects in ms.
+pub const EFFECT_DURATIONS: [u128; EffectType::COUNT] = {
+    let mut durations = [0; EffectType::COUNT];
+
+    durations[SpeedBoost as usize] = 8000;
+    durations[ExtraJump as usize] = 10000;
+    durations[HeavyBody as usize] = 8000;
+    durations[Shield as usize] = 15000;
+    durations[InfiniteAmmo as usize] = 5000;
+    durations
+};
+
+/// stacking rules of the effects.
+pub const EFFECT_STACKING: [Stacking; EffectType::COUNT] = {
+    let mut stacking = [Refresh; EffectType::COUNT];
+
+    stacking[SpeedBoost as usize] = Extend;
+    stacking[ExtraJump as usize] = Refresh;
+    stacking[HeavyBody as usize] = Extend;
+    stacking[Shield as usize] = Refresh;
+    stacking[InfiniteAmmo as usize] = Extend;
+    stacking
+};
+
+/// longest an effect can last after being extended, in ms.
+pub const MAX_EFFECT_DURATION: u128 = 20000;
+
+/// speed cap multiplier while `SpeedBoost` is active.
+pub const SPEED_BOOST_MULTIPLIER: f32 = 1.5;
+
+/// mass multiplier while `HeavyBody` is active.
+pub const HEAVY_BODY_MULTIPLIER: f32 = 2.0;
+
+/// An active status effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusEffect {
+    effecttype: EffectType,
+    /// simulation time in ms when the effect runs out.
+    expires_at: u128,
+}
+
+impl StatusEffect {
+    /// creates an effect which runs out at `expires_at` ms of simulation time.
+    pub fn new(effecttype: EffectType, expires_at: u128) -> Self {
+        Self { effecttype, expires_at }
+    }
+
+    pub fn get_effect_type(&self) -> EffectType {
+        self.effecttype
+    }
+
+    pub fn get_expires_at(&self) -> u128 {
+        self.expires_at
+    }
+
+    /// time in ms left before the effect runs out.
+    pub fn get_remaining(&self, curr_time: u128) -> u128 {
+        self.expires_at.saturating_sub(curr_time)
+    }
+
+    /// applies the stacking rule of the effect after it was picked up again at `curr_time`.
+    pub(crate) fn stack(&mut self, curr_time: u128) {
+        let i = self.effecttype as usize;
+        self.expires_at = match EFFECT_STACKING[i] {
+            Refresh => curr_time + EFFECT_DURATIONS[i],
+            Extend => {
+                let extended = self.expires_at.max(curr_time) + EFFECT_DURATIONS[i];
+                extended.min(curr_time + MAX_EFFECT_DURATION)
+            },
+        };
+    }
+}
+
+impl EffectType {
+    /// obtains the effect after being picked up at `curr_time`.
+    pub(crate) fn start(self, curr_time: u128) -> StatusEffect {
+        StatusEffect::new(self, curr_time + EFFECT_DURATIONS[self as usize])
+    }
+}
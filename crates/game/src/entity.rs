@@ -0,0 +1,197 @@
+/// Storage for the entities of the arena (players, bullets, thrown weapons and pickups).
+///
+/// Every entity is referred to by an `EntityId`, made of the index of its slot in the storage and
+/// the generation of the slot. Removing an entity bumps the generation of its slot, so an old id
+/// referring to a removed entity never matches the entity that reuses the slot later. A slot
+/// whose generation would wrap around is retired instead of being reused.
+///
+/// The ids are sent over the network as a single `u32`, so clients refer to the same entities
+/// as the server.
+use core::fmt;
+
+/// Generational id of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId {
+    index: u16,
+    generation: u16,
+}
+
+impl EntityId {
+    pub fn new(index: u16, generation: u16) -> Self {
+        Self { index, generation }
+    }
+
+    pub fn get_index(&self) -> u16 {
+        self.index
+    }
+
+    pub fn get_generation(&self) -> u16 {
+        self.generation
+    }
+
+    /// packs the id into a `u32`, with the generation in the upper 16 bits.
+    pub fn to_bits(self) -> u32 {
+        (self.generation as u32) << 16 | self.index as u32
+    }
+
+    /// unpacks the id from a `u32` created by `to_bits`.
+    pub fn from_bits(bits: u32) -> Self {
+        let index = (bits & 0xffff) as u16;
+        let generation = (bits >> 16) as u16;
+        Self { index, generation }
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u16,
+    value: Option<T>,
+}
+
+/// Storage of entities of type `T`. Iteration always goes in increasing order of the index,
+/// so two stores built by the same operations iterate in the same order.
+#[derive(Debug, Clone)]
+pub struct EntityStore<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u16>,
+    len: usize,
+}
+
+impl<T> Default for EntityStore<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> EntityStore<T> {
+    pub fn new() -> Self {
+        Self { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    /// number of entities in the store.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// adds a new entity to the store, reusing the slot of a removed entity if possible.
+    ///
+    /// Panics if there are more than `u16::MAX` entities at once.
+    pub fn insert(&mut self, value: T) -> EntityId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index: u16 = self.slots.len().try_into().expect("Too many entities");
+                self.slots.push(Slot { generation: 0, value: None });
+                index
+            },
+        };
+
+        let slot = &mut self.slots[index as usize];
+        slot.value = Some(value);
+        self.len += 1;
+        EntityId::new(index, slot.generation)
+    }
+
+    /// adds the entity with a known id, replacing whatever was in its slot. Used to mirror
+    /// the entities of another store (e.g. a client mirroring the server).
+    pub fn insert_at(&mut self, id: EntityId, value: T) {
+        let index = id.index as usize;
+        while self.slots.len() <= index {
+            let new_index = self.slots.len() as u16;
+            self.slots.push(Slot { generation: 0, value: None });
+            self.free.push(new_index);
+        }
+
+        let slot = &mut self.slots[index];
+        if slot.value.is_none() {
+            self.len += 1;
+            self.free.retain(|free_index| *free_index != id.index);
+        }
+
+        slot.generation = id.generation;
+        slot.value = Some(value);
+    }
+
+    /// removes the entity, invalidating its id. The slot is never reused once it ran out of
+    /// generations, so the old ids of the slot never match again.
+    pub fn remove(&mut self, id: EntityId) -> Option<T> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation || slot.value.is_none() {
+            return None;
+        }
+
+        if let Some(generation) = slot.generation.checked_add(1) {
+            slot.generation = generation;
+            self.free.push(id.index);
+        }
+        self.len -= 1;
+        slot.value.take()
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        self.slots.get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        self.slots.get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// removes every entity. Ids of the removed entities stay invalid.
+    pub fn clear(&mut self) {
+        let ids: Vec<EntityId> = self.ids().collect();
+        for id in ids {
+            self.remove(id);
+        }
+    }
+
+    /// removes every entity for which `keep` returns false.
+    pub fn retain(&mut self, mut keep: impl FnMut(EntityId, &mut T) -> bool) {
+        let to_remove: Vec<EntityId> = self.iter_mut()
+            .filter_map(|(id, value)| if keep(id, value) { None } else { Some(id) })
+            .collect();
+
+        for id in to_remove {
+            self.remove(id);
+        }
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.iter().map(|(id, _)| id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> + '_ {
+        self.slots.iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let id = EntityId::new(index as u16, slot.generation);
+                slot.value.as_ref().map(|value| (id, value))
+            })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut T)> + '_ {
+        self.slots.iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let id = EntityId::new(index as u16, slot.generation);
+                slot.value.as_mut().map(|value| (id, value))
+            })
+    }
+}
@@ -0,0 +1,374 @@
+/// Reinforcement learning environment over the arena.
+///
+/// The environment follows the usual "gym" interface: `reset(seed)` starts a new episode and
+/// `step(actions)` advances the arena by one tick given the input of every player, returning a
+/// fixed length observation vector and a reward for each of the players.
+///
+/// Players are indexed from `0` to `players - 1`, in the order they were added to the arena.
+use std::collections::HashMap;
+use std::thread;
+use glam::Vec2;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::input::InputMask;
+use crate::map::{Map, HORIZONTAL_BLOCKS};
+use crate::pickup::PickupSpawner;
+use crate::player::Player;
+use crate::{ARENA_WIDTH, ARENA_HEIGHT, BLOCK_WIDTH, PLAYER_SPEED_CAP, VERTICAL_BLOCK_SPACING};
+
+/// number of features describing a single player in an observation.
+pub const PLAYER_FEATURES: usize = 7;
+
+/// number of bullets (closest to the player) included in an observation.
+pub const NEAREST_BULLETS: usize = 4;
+
+/// number of features describing a single bullet in an observation.
+pub const BULLET_FEATURES: usize = 4;
+
+/// number of columns around the player (including its own) included in an observation.
+pub const TERRAIN_COLUMNS: usize = 5;
+
+/// weights of the different events making up the reward of a player.
+#[derive(Debug, Clone, Copy)]
+pub struct RewardConfig {
+    /// reward for knocking another player out of the arena.
+    pub ring_out: f32,
+    /// reward for being knocked out of the arena. Usually negative.
+    pub death: f32,
+    /// reward for every bullet or melee shove hitting another player.
+    pub damage_dealt: f32,
+    /// reward for every tick the player stays alive.
+    pub survival: f32,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self { ring_out: 1.0, death: -1.0, damage_dealt: 0.1, survival: 0.001 }
+    }
+}
+
+/// configuration of the episodes played in an environment.
+#[derive(Debug, Clone)]
+pub struct EnvironmentConfig {
+    pub map: Map,
+    pub players: u8,
+    /// simulated time of a single step in seconds.
+    pub dt: f32,
+    /// number of steps before the episode is cut short.
+    pub max_ticks: u32,
+    pub rewards: RewardConfig,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            map: Map::default(),
+            players: 2,
+            dt: 1.0 / 60.0,
+            max_ticks: 60 * 60,
+            rewards: RewardConfig::default(),
+        }
+    }
+}
+
+/// result of a single step of the environment. Observations and rewards are indexed by player.
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub observations: Vec<Vec<f32>>,
+    pub rewards: Vec<f32>,
+    pub done: bool,
+}
+
+#[derive(Debug)]
+pub struct Environment {
+    config: EnvironmentConfig,
+    arena: Arena,
+    /// id in the arena of the player with each index.
+    ids: Vec<EntityId>,
+    alive: Vec<bool>,
+    ticks: u32,
+}
+
+impl Environment {
+    /// creates a new environment. `reset` needs to be called before the first step.
+    pub fn new(config: EnvironmentConfig) -> Self {
+        let arena = Arena::new(config.map.clone());
+        let ids = Vec::new();
+        let alive = Vec::new();
+        Self { config, arena, ids, alive, ticks: 0 }
+    }
+
+    pub fn get_arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    pub fn get_mut_arena(&mut self) -> &mut Arena {
+        &mut self.arena
+    }
+
+    pub fn get_config(&self) -> &EnvironmentConfig {
+        &self.config
+    }
+
+    /// length of the observation vector of every player.
+    pub fn observation_len(&self) -> usize {
+        PLAYER_FEATURES * self.config.players as usize
+            + BULLET_FEATURES * NEAREST_BULLETS
+            + TERRAIN_COLUMNS
+    }
+
+    /// starts a new episode. The players are spawned on top of randomly chosen columns
+    /// of the map, which are determined by `seed`. Returns the first observations.
+    pub fn reset(&mut self, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.arena = Arena::new(self.config.map.clone());
+        self.arena.set_pickup_spawner(Some(PickupSpawner::new(seed)));
+        self.ids.clear();
+        self.ticks = 0;
+
+        let map = self.arena.get_map();
+        let mut columns: Vec<(usize, usize)> = (0..HORIZONTAL_BLOCKS)
+            .filter_map(|col| map.first_row_below(0, col).map(|row| (row, col)))
+            .collect();
+        columns.shuffle(&mut rng);
+
+        for id in 0..self.config.players {
+            let mut player = Player::new(&id.to_string());
+            if let Some((row, col)) = columns.get(id as usize % columns.len().max(1)) {
+                let top = self.arena.get_block_position_at(*row, *col);
+                player.update_position(top + Vec2::new((BLOCK_WIDTH - player.width) / 2.0, -player.height), 0.0);
+            }
+
+            // face towards the middle of the arena.
+            player.direction = if player.get_position().x < ARENA_WIDTH / 2.0 { 1.0 } else { -1.0 };
+            self.ids.push(self.arena.add_player(player));
+        }
+
+        self.alive = vec![true; self.config.players as usize];
+        self.get_observations()
+    }
+
+    /// advances the arena by one tick, with `actions` being the input of each player.
+    /// Actions of players who have already been knocked out are ignored.
+    pub fn step(&mut self, actions: &[InputMask]) -> Step {
+        let count = self.config.players as usize;
+        assert_eq!(actions.len(), count, "Expected one action per player");
+
+        let inputs: HashMap<EntityId, InputMask> = actions.iter()
+            .enumerate()
+            .filter(|(i, _)| self.alive[*i])
+            .map(|(i, action)| (self.ids[i], *action))
+            .collect();
+
+        let events = self.arena.update(self.config.dt, &inputs);
+        self.ticks += 1;
+
+        let rewards_config = self.config.rewards;
+        let mut rewards = vec![0.0; count];
+        for event in events {
+            match event {
+                GameEvent::PlayerHit { attacker, .. } | GameEvent::PlayerShoved { attacker, .. } => {
+                    if let Some(attacker) = self.get_index(attacker) {
+                        rewards[attacker] += rewards_config.damage_dealt;
+                    }
+                },
+
+                GameEvent::PlayerKilled { victim, killer } => {
+                    if let Some(killer) = killer.and_then(|killer| self.get_index(killer)) {
+                        rewards[killer] += rewards_config.ring_out;
+                    }
+
+                    // knocked out players do not respawn in an episode.
+                    if let Some(index) = self.get_index(victim) {
+                        rewards[index] += rewards_config.death;
+                        self.alive[index] = false;
+                    }
+                    self.arena.remove_player(victim);
+                },
+
+                _ => {},
+            }
+        }
+
+        for (i, alive) in self.alive.iter().enumerate() {
+            if *alive {
+                rewards[i] += rewards_config.survival;
+            }
+        }
+
+        let alive_count = self.alive.iter().filter(|a| **a).count();
+        let last_one_standing = count > 1 && alive_count <= 1;
+        let done = last_one_standing || alive_count == 0 || self.ticks >= self.config.max_ticks;
+
+        let observations = self.get_observations();
+        Step { observations, rewards, done }
+    }
+
+    /// whether the player is still in the arena.
+    pub fn is_alive(&self, id: u8) -> bool {
+        self.alive.get(id as usize).copied().unwrap_or(false)
+    }
+
+    /// obtains the id in the arena of the player with index `id`.
+    pub fn get_entity_id(&self, id: u8) -> Option<EntityId> {
+        self.ids.get(id as usize).copied()
+    }
+
+    /// obtains the index of the player with the id `entity` in the arena.
+    fn get_index(&self, entity: EntityId) -> Option<usize> {
+        self.ids.iter().position(|id| *id == entity)
+    }
+
+    fn get_observations(&self) -> Vec<Vec<f32>> {
+        (0..self.config.players)
+            .map(|id| self.get_observation(id))
+            .collect()
+    }
+
+    /// obtains the observation of player `id`. The observation is laid out as:
+    ///
+    /// 1. features of the player itself, with absolute position.
+    /// 2. features of every other player (in increasing id order, starting from `id + 1`),
+    ///    relative to this player's position. All zeros if knocked out.
+    /// 3. features of the closest bullets, relative to this player's position.
+    /// 4. height of the first block below the player for the columns around the player.
+    pub fn get_observation(&self, id: u8) -> Vec<f32> {
+        let mut observation = Vec::with_capacity(self.observation_len());
+        let count = self.config.players;
+
+        let player = match self.get_entity_id(id).and_then(|entity| self.arena.get_player(entity)) {
+            Some(player) if self.is_alive(id) => player,
+            _ => {
+                observation.resize(self.observation_len(), 0.0);
+                return observation;
+            },
+        };
+
+        let origin = player.get_position();
+        observation.extend(get_player_features(player, Vec2::ZERO));
+
+        for offset in 1..count {
+            let other_id = ((id as usize + offset as usize) % count as usize) as u8;
+            match self.get_entity_id(other_id).and_then(|entity| self.arena.get_player(entity)) {
+                Some(other) if self.is_alive(other_id) => {
+                    observation.extend(get_player_features(other, origin));
+                },
+                _ => observation.extend([0.0; PLAYER_FEATURES]),
+            }
+        }
+
+        let mut bullets: Vec<(Vec2, Vec2)> = self.arena.bullets_iterator()
+            .map(|(_, bullet)| (bullet.get_position() - origin, bullet.get_velocity()))
+            .collect();
+        bullets.sort_by(|(a, _), (b, _)| a.length_squared().total_cmp(&b.length_squared()));
+        bullets.resize(NEAREST_BULLETS, (Vec2::ZERO, Vec2::ZERO));
+
+        for (relative_position, velocity) in bullets {
+            observation.extend([
+                relative_position.x / ARENA_WIDTH,
+                relative_position.y / ARENA_HEIGHT,
+                velocity.x / PLAYER_SPEED_CAP,
+                velocity.y / PLAYER_SPEED_CAP,
+            ]);
+        }
+
+        let feet = player.get_position() + Vec2::new(player.width / 2.0, player.height);
+        let half = (TERRAIN_COLUMNS / 2) as f32;
+        for i in 0..TERRAIN_COLUMNS {
+            let point = feet + Vec2::new((i as f32 - half) * BLOCK_WIDTH, 0.0);
+            let height = Arena::to_row_col(point)
+                .and_then(|(row, col)| self.arena.get_map().first_row_below(row, col))
+                .map(|row| (Arena::get_block_row_position(row) - feet.y) / VERTICAL_BLOCK_SPACING)
+                .unwrap_or(-1.0);
+
+            observation.push(height);
+        }
+
+        observation
+    }
+}
+
+/// features describing the player, with the position relative to `origin`.
+fn get_player_features(player: &Player, origin: Vec2) -> [f32; PLAYER_FEATURES] {
+    let relative_position = player.get_position() - origin;
+    [
+        relative_position.x / ARENA_WIDTH,
+        relative_position.y / ARENA_HEIGHT,
+        player.get_velocity().x / PLAYER_SPEED_CAP,
+        player.get_velocity().y / PLAYER_SPEED_CAP,
+        player.direction,
+        player.jumps_left as f32 / player.get_jumps_count().max(1) as f32,
+        1.0,
+    ]
+}
+
+/// Many environments with the same configuration, which are stepped in parallel threads.
+#[derive(Debug)]
+pub struct EnvironmentPool {
+    environments: Vec<Environment>,
+    threads: usize,
+}
+
+impl EnvironmentPool {
+    /// creates `count` environments, which will be split between `threads` threads on each step.
+    pub fn new(config: EnvironmentConfig, count: usize, threads: usize) -> Self {
+        let environments = (0..count).map(|_| Environment::new(config.clone())).collect();
+        let threads = threads.max(1);
+        Self { environments, threads }
+    }
+
+    pub fn len(&self) -> usize {
+        self.environments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.environments.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Environment> {
+        self.environments.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Environment> {
+        self.environments.get_mut(index)
+    }
+
+    /// resets all the environments. Environment `i` is reset with seed `seed + i`.
+    pub fn reset(&mut self, seed: u64) -> Vec<Vec<Vec<f32>>> {
+        self.environments.iter_mut()
+            .enumerate()
+            .map(|(i, environment)| environment.reset(seed.wrapping_add(i as u64)))
+            .collect()
+    }
+
+    /// steps every environment with its own actions. `actions[i]` are the actions of the players
+    /// in environment `i`.
+    pub fn step(&mut self, actions: &[Vec<InputMask>]) -> Vec<Step> {
+        assert_eq!(actions.len(), self.environments.len(), "Expected actions for every environment");
+
+        let chunk_size = self.environments.len().div_ceil(self.threads);
+        if chunk_size == 0 {
+            return Vec::new();
+        }
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = self.environments.chunks_mut(chunk_size)
+                .zip(actions.chunks(chunk_size))
+                .map(|(environments, actions)| {
+                    scope.spawn(move || {
+                        environments.iter_mut()
+                            .zip(actions)
+                            .map(|(environment, action)| environment.step(action))
+                            .collect::<Vec<Step>>()
+                    })
+                })
+                .collect();
+
+            handles.into_iter()
+                .flat_map(|handle| handle.join().expect("Environment thread panicked"))
+                .collect()
+        })
+    }
+}
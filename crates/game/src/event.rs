@@ -0,0 +1,67 @@
+/// Events that happened inside the arena during a single update.
+///
+/// `Arena::update` returns the list of events of the tick, so sound, particles, kill feed,
+/// statistics and network messages can all be driven from the same source instead of
+/// diffing the state of the arena.
+use glam::Vec2;
+use crate::block::BlockType;
+use crate::effect::EffectType;
+use crate::hazard::HazardType;
+use crate::entity::EntityId;
+use crate::weaponscatalog::{BulletType, WeaponType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    /// `player` fired the bullet with id `bullet`.
+    BulletFired { player: EntityId, bullet: EntityId, bullettype: BulletType },
+
+    /// `player` jumped. `midair` is set if the player was not standing on a block.
+    Jumped { player: EntityId, midair: bool },
+
+    /// `player` dashed. `midair` is set if the player was not standing on a block.
+    Dashed { player: EntityId, midair: bool },
+
+    /// `player` landed on a block.
+    Landed { player: EntityId },
+
+    /// `player` tried to attack with a weapon without any bullets left, and threw it away.
+    WeaponEmptied { player: EntityId, weapontype: WeaponType },
+
+    /// a bullet fired by `attacker` hit `victim`, adding `damage` percent to the victim and
+    /// pushing it with `momentum` (scaled up by the victim's damage).
+    PlayerHit { attacker: EntityId, victim: EntityId, bullettype: BulletType, damage: f32, momentum: Vec2 },
+
+    /// `attacker` shoved `victim` with a melee attack, adding `damage` percent to the victim and
+    /// pushing it with `momentum` (scaled up by the victim's damage).
+    PlayerShoved { attacker: EntityId, victim: EntityId, damage: f32, momentum: Vec2 },
+
+    /// `victim` was knocked out of the arena. `killer` is the last player who hit the victim,
+    /// or None if the victim fell on its own.
+    PlayerKilled { victim: EntityId, killer: Option<EntityId> },
+
+    /// `player` walked into the `pickup`, equipping its weapon.
+    WeaponPickedUp { player: EntityId, pickup: EntityId, weapontype: WeaponType },
+
+    /// `player` walked into the `pickup`, gaining its effect.
+    EffectPickedUp { player: EntityId, pickup: EntityId, effecttype: EffectType },
+
+    /// the effect of `player` ran out.
+    EffectExpired { player: EntityId, effecttype: EffectType },
+
+    /// `attacker` landed on the head of `victim`, bouncing off and pushing the victim down.
+    PlayerStomped { attacker: EntityId, victim: EntityId },
+
+    /// `player` touched a hazard of the map, adding `damage` percent to the player. Touching
+    /// spikes is followed by a `PlayerKilled` event.
+    HazardTouched { player: EntityId, hazardtype: HazardType, damage: f32 },
+
+    /// the block at `row` and `col` was broken (`blocktype` is None) or grew back.
+    BlockChanged { row: usize, col: usize, blocktype: Option<BlockType> },
+
+    /// `player` entered the teleporter on the cell `from` and came out of its partner on the
+    /// cell `to`, both as (row, col).
+    PlayerTeleported { player: EntityId, from: (usize, usize), to: (usize, usize) },
+
+    /// a bullet fired by `attacker` was absorbed by the shield of `victim`, breaking it.
+    ShieldBroken { attacker: EntityId, victim: EntityId },
+}
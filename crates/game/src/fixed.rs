@@ -0,0 +1,362 @@
+/// Fixed-point numbers for a deterministic movement of the players, bullets and thrown weapons.
+///
+/// Floating-point results are not guaranteed to be identical across compilers and CPU features,
+/// which rules out lockstep and rollback multiplayer. With the `fixed-point` feature, the players,
+/// bullets and thrown weapons keep their positions, velocities and accelerations as `FixedVec2`,
+/// and their movement is computed with `Fixed` numbers: the forces, impulses, knockback, friction
+/// and the separation of overlapping players. The constants and inputs of the game enter the
+/// movement through `real` and `real_vec2`, and the rest of the game (e.g. drawing, the network
+/// and the queries of the arena) sees the movement as `f32` through `to_f32` and `to_vec2`.
+///
+/// Without the feature, `Real` and `RealVec2` are `f32` and `Vec2`, and the movement is computed
+/// as it always was.
+///
+/// `StateHasher` hashes the state of an arena the same way on every build, so two runs can be
+/// compared with `Arena::get_state_hash`.
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use glam::Vec2;
+
+/// number of bits after the binary point.
+const FRACTION_BITS: u32 = 16;
+
+/// A number with `FRACTION_BITS` bits after the binary point, i.e. a multiple of 1/65536.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << FRACTION_BITS);
+
+    /// rounds the value to the closest fixed-point number. The value is scaled as a `f64`,
+    /// which holds any `f32` times `ONE` exactly.
+    pub fn from_f32(value: f32) -> Self {
+        Self((value as f64 * Self::ONE.0 as f64).round() as i64)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        (self.0 as f64 / Self::ONE.0 as f64) as f32
+    }
+
+    pub fn abs(self) -> Self {
+        Self(self.0.abs())
+    }
+
+    pub fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    pub fn to_bits(self) -> i64 {
+        self.0
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Fixed) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0 - other.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    /// rounds towards negative infinity.
+    fn mul(self, other: Fixed) -> Fixed {
+        Fixed(((self.0 as i128 * other.0 as i128) >> FRACTION_BITS) as i64)
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, other: Fixed) {
+        *self = *self * other;
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    /// rounds towards zero. Panics if `other` is zero.
+    fn div(self, other: Fixed) -> Fixed {
+        Fixed((((self.0 as i128) << FRACTION_BITS) / other.0 as i128) as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+impl Mul<FixedVec2> for Fixed {
+    type Output = FixedVec2;
+
+    fn mul(self, vector: FixedVec2) -> FixedVec2 {
+        vector * self
+    }
+}
+
+/// A vector of two fixed-point numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FixedVec2 {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+impl FixedVec2 {
+    pub const ZERO: FixedVec2 = FixedVec2 { x: Fixed::ZERO, y: Fixed::ZERO };
+    pub const X: FixedVec2 = FixedVec2 { x: Fixed::ONE, y: Fixed::ZERO };
+
+    pub fn new(x: Fixed, y: Fixed) -> Self {
+        Self { x, y }
+    }
+
+    /// obtains the smallest of the coordinates of both vectors.
+    pub fn min(self, other: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// obtains the largest of the coordinates of both vectors.
+    pub fn max(self, other: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    /// obtains the length of the vector, rounded down.
+    pub fn length(self) -> Fixed {
+        let squared = self.x.0 as i128 * self.x.0 as i128 + self.y.0 as i128 * self.y.0 as i128;
+        Fixed((squared as u128).isqrt() as i64)
+    }
+}
+
+impl From<Vec2> for FixedVec2 {
+    fn from(vector: Vec2) -> Self {
+        Self::new(Fixed::from_f32(vector.x), Fixed::from_f32(vector.y))
+    }
+}
+
+impl From<FixedVec2> for Vec2 {
+    fn from(vector: FixedVec2) -> Self {
+        Vec2::new(vector.x.to_f32(), vector.y.to_f32())
+    }
+}
+
+impl Add for FixedVec2 {
+    type Output = FixedVec2;
+
+    fn add(self, other: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for FixedVec2 {
+    fn add_assign(&mut self, other: FixedVec2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for FixedVec2 {
+    type Output = FixedVec2;
+
+    fn sub(self, other: FixedVec2) -> FixedVec2 {
+        FixedVec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for FixedVec2 {
+    fn sub_assign(&mut self, other: FixedVec2) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for FixedVec2 {
+    type Output = FixedVec2;
+
+    fn neg(self) -> FixedVec2 {
+        FixedVec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<Fixed> for FixedVec2 {
+    type Output = FixedVec2;
+
+    fn mul(self, scale: Fixed) -> FixedVec2 {
+        FixedVec2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Div<Fixed> for FixedVec2 {
+    type Output = FixedVec2;
+
+    fn div(self, scale: Fixed) -> FixedVec2 {
+        FixedVec2::new(self.x / scale, self.y / scale)
+    }
+}
+
+/// number the movement of the players, bullets and thrown weapons is computed with.
+#[cfg(feature = "fixed-point")]
+pub type Real = Fixed;
+
+/// number the movement of the players, bullets and thrown weapons is computed with.
+#[cfg(not(feature = "fixed-point"))]
+pub type Real = f32;
+
+/// vector the movement of the players, bullets and thrown weapons is computed with.
+#[cfg(feature = "fixed-point")]
+pub type RealVec2 = FixedVec2;
+
+/// vector the movement of the players, bullets and thrown weapons is computed with.
+#[cfg(not(feature = "fixed-point"))]
+pub type RealVec2 = Vec2;
+
+/// converts a constant or an input of the game to a number of the movement.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn real(value: f32) -> Real {
+    Fixed::from_f32(value)
+}
+
+/// converts a constant or an input of the game to a number of the movement.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn real(value: f32) -> Real {
+    value
+}
+
+/// converts a constant or an input of the game to a vector of the movement.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn real_vec2(vector: Vec2) -> RealVec2 {
+    FixedVec2::from(vector)
+}
+
+/// converts a constant or an input of the game to a vector of the movement.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn real_vec2(vector: Vec2) -> RealVec2 {
+    vector
+}
+
+/// converts a number of the movement to `f32`, e.g. for drawing or sending it.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn to_f32(value: Real) -> f32 {
+    value.to_f32()
+}
+
+/// converts a number of the movement to `f32`, e.g. for drawing or sending it.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn to_f32(value: Real) -> f32 {
+    value
+}
+
+/// converts a vector of the movement to `Vec2`, e.g. for drawing or sending it.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn to_vec2(vector: RealVec2) -> Vec2 {
+    Vec2::from(vector)
+}
+
+/// converts a vector of the movement to `Vec2`, e.g. for drawing or sending it.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn to_vec2(vector: RealVec2) -> Vec2 {
+    vector
+}
+
+/// moves `position` at `velocity` with a constant `acceleration` for `dt` seconds. Returns the
+/// new position and velocity.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn integrate(position: Vec2, velocity: Vec2, acceleration: Vec2, dt: f32) -> (Vec2, Vec2) {
+    // dx = vt + 1/2 at^2
+    (position + velocity * dt + 0.5 * acceleration * dt * dt, velocity + acceleration * dt)
+}
+
+/// moves `position` at `velocity` with a constant `acceleration` for `dt` seconds. Returns the
+/// new position and velocity.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn integrate(position: FixedVec2, velocity: FixedVec2, acceleration: FixedVec2, dt: Fixed) -> (FixedVec2, FixedVec2) {
+    let half = Fixed::from_bits(1 << (FRACTION_BITS - 1));
+
+    // dx = (v + 1/2 at) t, so the small dt^2 is never rounded on its own.
+    let velocity_change = acceleration * dt;
+    let new_position = position + (velocity + velocity_change * half) * dt;
+    (new_position, velocity + velocity_change)
+}
+
+/// obtains the velocity moving from `start` to `end` in `dt` seconds.
+#[cfg(not(feature = "fixed-point"))]
+pub(crate) fn get_velocity_between(start: Vec2, end: Vec2, dt: f32) -> Vec2 {
+    (end - start) / dt
+}
+
+/// obtains the velocity moving from `start` to `end` in `dt` seconds, which is zero if `dt`
+/// is too short for the fixed-point grid.
+#[cfg(feature = "fixed-point")]
+pub(crate) fn get_velocity_between(start: FixedVec2, end: FixedVec2, dt: Fixed) -> FixedVec2 {
+    if dt == Fixed::ZERO {
+        return FixedVec2::ZERO;
+    }
+
+    (end - start) / dt
+}
+
+/// FNV-1a hash which gives the same result on every build and platform, unlike the hashers
+/// of the standard library.
+pub struct StateHasher(u64);
+
+impl StateHasher {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// hashes the exact bits of the value.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    pub fn write_vec2(&mut self, vector: Vec2) {
+        self.write_f32(vector.x);
+        self.write_f32(vector.y);
+    }
+
+    /// hashes the exact bits of a vector of the movement.
+    pub fn write_real_vec2(&mut self, vector: RealVec2) {
+        #[cfg(feature = "fixed-point")]
+        {
+            self.write(&vector.x.to_bits().to_le_bytes());
+            self.write(&vector.y.to_bits().to_le_bytes());
+        }
+        #[cfg(not(feature = "fixed-point"))]
+        self.write_vec2(vector);
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
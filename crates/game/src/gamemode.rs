@@ -0,0 +1,463 @@
+/// Game modes played on top of the arena.
+///
+/// The arena only simulates the players, bullets and pickups. A `GameMode` is updated right
+/// after every `Arena::update` with the events of that tick, and adds the rules of the match:
+/// how the players (or teams) score, who wins, and what the mode does on every tick (e.g.
+/// moving a carried flag). Once a winner is decided, the scores stop changing.
+///
+/// Players joining or leaving the arena are picked up by the mode on its next update, so the
+/// mode does not need to be told about them. Knocked out players are respawned by the mode.
+///
+/// When the time limit of the match runs out with several players still alive, the mode goes
+/// into sudden death and the map starts collapsing.
+use std::collections::BTreeMap;
+use glam::Vec2;
+use strum::EnumCount;
+use strum_macros::{Display, EnumCount, FromRepr};
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::suddendeath::SuddenDeath;
+use crate::weaponscatalog::WeaponType;
+use crate::{ARENA_HEIGHT, ARENA_WIDTH, PLAYER_HEIGHT};
+use GameModeType::*;
+
+/// time (in ms) a player needs to hold the hill to win King of the Hill.
+pub const HILL_SCORE_LIMIT: u32 = 60000;
+
+/// number of captures a team needs to win Capture the Flag.
+pub const CAPTURE_LIMIT: u32 = 3;
+
+/// number of teams in Capture the Flag.
+pub const TEAMS_COUNT: usize = 2;
+
+/// time (in ms) after which a match goes into sudden death, in the modes with a time limit.
+pub const MATCH_TIME_LIMIT: u128 = 300000;
+
+/// width and height of a flag.
+pub const FLAG_SIZE: f32 = 24.0;
+
+/// Type of the game mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount, FromRepr, Display)]
+pub enum GameModeType {
+    /// every player for themselves, scoring a point for every knockout. Nobody ever wins.
+    FreeForAll,
+    /// players score the time they spend alone on the hill platform.
+    KingOfTheHill,
+    /// players advance through the weapon catalog, getting the next weapon on every knockout.
+    GunGame,
+    /// two teams steal the flag of the other team and bring it to their own flag.
+    CaptureTheFlag,
+}
+
+/// time limit of each mode, or None for the modes which never go into sudden death. Free for
+/// All has no time limit, since nobody ever wins it.
+pub const MODE_TIME_LIMITS: [Option<u128>; GameModeType::COUNT] = {
+    let mut time_limits = [None; GameModeType::COUNT];
+
+    time_limits[FreeForAll as usize] = None;
+    time_limits[KingOfTheHill as usize] = Some(MATCH_TIME_LIMIT);
+    time_limits[GunGame as usize] = Some(MATCH_TIME_LIMIT);
+    time_limits[CaptureTheFlag as usize] = Some(MATCH_TIME_LIMIT);
+    time_limits
+};
+
+/// Who a score belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scorer {
+    Player(EntityId),
+    Team(u8),
+}
+
+/// Summary of the game mode, which is sent to the clients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeState {
+    pub modetype: GameModeType,
+    /// score of every player or team.
+    pub scores: Vec<(Scorer, u32)>,
+    /// top left corners of the objectives of the mode: the hill in King of the Hill, and the
+    /// flags (in the order of the teams) in Capture the Flag.
+    pub objectives: Vec<Vec2>,
+    pub winner: Option<Scorer>,
+    pub sudden_death: bool,
+    /// cells (row, col) of the blocks about to collapse during sudden death.
+    pub collapsing: Vec<(usize, usize)>,
+}
+
+/// A flag of Capture the Flag.
+#[derive(Debug, Clone)]
+struct Flag {
+    /// top left corner of the flag when it is not carried.
+    home: Vec2,
+    position: Vec2,
+    carrier: Option<EntityId>,
+}
+
+impl Flag {
+    fn new(home: Vec2) -> Self {
+        Self { home, position: home, carrier: None }
+    }
+
+    /// drops the flag, which goes straight back to its home.
+    fn return_home(&mut self) {
+        self.position = self.home;
+        self.carrier = None;
+    }
+
+    /// obtains the top left and bottom right corners of the flag.
+    fn get_rect(&self) -> (Vec2, Vec2) {
+        (self.position, self.position + Vec2::splat(FLAG_SIZE))
+    }
+}
+
+/// Rules and state of each mode.
+#[derive(Debug, Clone)]
+enum Rules {
+    FreeForAll {
+        knockouts: BTreeMap<EntityId, u32>,
+    },
+
+    KingOfTheHill {
+        /// the zone above the hill platform, from its top left to its bottom right corner.
+        hill: Option<(Vec2, Vec2)>,
+        /// time (in ms) spent alone on the hill.
+        times: BTreeMap<EntityId, u32>,
+        last_time: u128,
+    },
+
+    GunGame {
+        /// index of the current weapon of each player in the weapon catalog.
+        levels: BTreeMap<EntityId, usize>,
+    },
+
+    CaptureTheFlag {
+        teams: BTreeMap<EntityId, u8>,
+        /// flags of each team. Empty if the map has no blocks to put the flags on.
+        flags: Vec<Flag>,
+        captures: [u32; TEAMS_COUNT],
+    },
+}
+
+/// The game mode being played in an arena.
+#[derive(Debug, Clone)]
+pub struct GameMode {
+    rules: Rules,
+    winner: Option<Scorer>,
+    /// time (in ms) from the start of the match to sudden death, or None for no time limit.
+    time_limit: Option<u128>,
+    start_time: u128,
+    sudden_death: Option<SuddenDeath>,
+}
+
+impl GameMode {
+    /// creates the mode for the map of the `arena`.
+    pub fn new(modetype: GameModeType, arena: &Arena) -> Self {
+        let rules = match modetype {
+            GameModeType::FreeForAll => Rules::FreeForAll { knockouts: BTreeMap::new() },
+
+            GameModeType::KingOfTheHill => {
+                // the hill is the block closest to the center of the arena.
+                let center = Vec2::new(ARENA_WIDTH, ARENA_HEIGHT) / 2.0;
+                let distance = |x: f32, y: f32| (Vec2::new(x, y) - center).length_squared();
+                let hill = arena.get_blocks_iter()
+                    .min_by(|a, b| distance(a.x + a.w / 2.0, a.y).total_cmp(&distance(b.x + b.w / 2.0, b.y)))
+                    .map(|block| (Vec2::new(block.x, block.y - PLAYER_HEIGHT), Vec2::new(block.x + block.w, block.y)));
+
+                Rules::KingOfTheHill { hill, times: BTreeMap::new(), last_time: arena.get_time() }
+            },
+
+            GameModeType::GunGame => Rules::GunGame { levels: BTreeMap::new() },
+
+            GameModeType::CaptureTheFlag => {
+                // the flags stand on the leftmost and the rightmost blocks.
+                let blocks: Vec<Vec2> = arena.get_blocks_iter()
+                    .map(|block| Vec2::new(block.x + (block.w - FLAG_SIZE) / 2.0, block.y - FLAG_SIZE))
+                    .collect();
+
+                let leftmost = blocks.iter().min_by(|a, b| a.x.total_cmp(&b.x));
+                let rightmost = blocks.iter().max_by(|a, b| a.x.total_cmp(&b.x));
+                let flags = match (leftmost, rightmost) {
+                    (Some(left), Some(right)) => vec![Flag::new(*left), Flag::new(*right)],
+                    _ => Vec::new(),
+                };
+
+                Rules::CaptureTheFlag { teams: BTreeMap::new(), flags, captures: [0; TEAMS_COUNT] }
+            },
+        };
+
+        Self {
+            rules,
+            winner: None,
+            time_limit: MODE_TIME_LIMITS[modetype as usize],
+            start_time: arena.get_time(),
+            sudden_death: None,
+        }
+    }
+
+    pub fn get_mode_type(&self) -> GameModeType {
+        match self.rules {
+            Rules::FreeForAll { .. } => GameModeType::FreeForAll,
+            Rules::KingOfTheHill { .. } => GameModeType::KingOfTheHill,
+            Rules::GunGame { .. } => GameModeType::GunGame,
+            Rules::CaptureTheFlag { .. } => GameModeType::CaptureTheFlag,
+        }
+    }
+
+    /// obtains the player or team who won, if the match is over.
+    pub fn get_winner(&self) -> Option<Scorer> {
+        self.winner
+    }
+
+    /// whether the match is over at time `curr_time`: a winner was decided, or the time limit ran
+    /// out and there is no sudden death going on (it did not start, or the map already collapsed
+    /// down to its center).
+    pub fn is_over(&self, curr_time: u128) -> bool {
+        let expired = self.time_limit.is_some_and(|limit| curr_time >= self.start_time + limit);
+        let sudden_death_done = self.sudden_death.as_ref().is_none_or(|sudden_death| sudden_death.is_done());
+        self.winner.is_some() || (expired && sudden_death_done)
+    }
+
+    pub fn get_time_limit(&self) -> Option<u128> {
+        self.time_limit
+    }
+
+    /// sets the time (in ms) from the start of the match to sudden death, or disables sudden
+    /// death if None.
+    pub fn set_time_limit(&mut self, time_limit: Option<u128>) {
+        self.time_limit = time_limit;
+    }
+
+    pub fn is_sudden_death(&self) -> bool {
+        self.sudden_death.is_some()
+    }
+
+    /// obtains the cells (row, col) of the blocks about to collapse during sudden death.
+    pub fn get_collapsing_cells(&self) -> Vec<(usize, usize)> {
+        self.sudden_death.iter().flat_map(|sudden_death| sudden_death.get_warned_cells()).copied().collect()
+    }
+
+    /// obtains the score of every player, or of every team in team modes.
+    pub fn get_scores(&self) -> Vec<(Scorer, u32)> {
+        let player_scores = |scores: &BTreeMap<EntityId, u32>| {
+            scores.iter().map(|(id, score)| (Scorer::Player(*id), *score)).collect()
+        };
+
+        match &self.rules {
+            Rules::FreeForAll { knockouts } => player_scores(knockouts),
+            Rules::KingOfTheHill { times, .. } => player_scores(times),
+            Rules::GunGame { levels } => {
+                levels.iter().map(|(id, level)| (Scorer::Player(*id), *level as u32)).collect()
+            },
+            Rules::CaptureTheFlag { captures, .. } => {
+                captures.iter().enumerate().map(|(team, score)| (Scorer::Team(team as u8), *score)).collect()
+            },
+        }
+    }
+
+    /// sets the score of a player, or of a team in team modes (e.g. from a script). The players
+    /// and teams which are not part of the match are ignored. In Gun Game, the score is the
+    /// level of the player, which is kept within the weapon catalog.
+    pub fn set_score(&mut self, scorer: Scorer, score: u32) {
+        match (&mut self.rules, scorer) {
+            (Rules::FreeForAll { knockouts: scores }, Scorer::Player(id))
+            | (Rules::KingOfTheHill { times: scores, .. }, Scorer::Player(id)) => {
+                if let Some(player_score) = scores.get_mut(&id) {
+                    *player_score = score;
+                }
+            },
+
+            (Rules::GunGame { levels }, Scorer::Player(id)) => {
+                if let Some(level) = levels.get_mut(&id) {
+                    *level = usize::min(score as usize, WeaponType::COUNT - 1);
+                }
+            },
+
+            (Rules::CaptureTheFlag { captures, .. }, Scorer::Team(team)) => {
+                if let Some(team_score) = captures.get_mut(team as usize) {
+                    *team_score = score;
+                }
+            },
+
+            _ => (),
+        }
+    }
+
+    /// obtains the top left corners of the objectives of the mode.
+    pub fn get_objectives(&self) -> Vec<Vec2> {
+        match &self.rules {
+            Rules::KingOfTheHill { hill, .. } => hill.iter().map(|(min, _)| *min).collect(),
+            Rules::CaptureTheFlag { flags, .. } => flags.iter().map(|flag| flag.position).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// obtains the team of the player in team modes.
+    pub fn get_team(&self, id: EntityId) -> Option<u8> {
+        match &self.rules {
+            Rules::CaptureTheFlag { teams, .. } => teams.get(&id).copied(),
+            _ => None,
+        }
+    }
+
+    /// summarizes the mode to be sent to the clients.
+    pub fn get_state(&self) -> ModeState {
+        ModeState {
+            modetype: self.get_mode_type(),
+            scores: self.get_scores(),
+            objectives: self.get_objectives(),
+            winner: self.winner,
+            sudden_death: self.is_sudden_death(),
+            collapsing: self.get_collapsing_cells(),
+        }
+    }
+
+    /// applies the rules of the mode after the `arena` was updated, with `events` being the
+    /// events returned by that update.
+    pub fn update(&mut self, arena: &mut Arena, events: &[GameEvent]) {
+        let knockouts = events.iter().filter_map(|event| match event {
+            GameEvent::PlayerKilled { victim, killer } => Some((*victim, *killer)),
+            _ => None,
+        });
+
+        // knocked out players come back right away, even once the match is over.
+        for (victim, _) in knockouts.clone() {
+            arena.respawn_player(victim);
+        }
+
+        if self.winner.is_some() {
+            return;
+        }
+
+        let curr_time = arena.get_time();
+        self.update_sudden_death(arena, curr_time);
+
+        match &mut self.rules {
+            Rules::FreeForAll { knockouts: scores } => {
+                GameMode::sync_players(scores, arena);
+                for killer in knockouts.filter_map(|(_, killer)| killer) {
+                    if let Some(score) = scores.get_mut(&killer) {
+                        *score += 1;
+                    }
+                }
+            },
+
+            Rules::KingOfTheHill { hill, times, last_time } => {
+                GameMode::sync_players(times, arena);
+                let elapsed: u32 = curr_time.saturating_sub(*last_time).try_into().unwrap_or(u32::MAX);
+                *last_time = curr_time;
+
+                // the hill only scores while a single player stands on it.
+                if let Some((min, max)) = hill {
+                    if let [king] = arena.query_players(*min, *max)[..] {
+                        if let Some(time) = times.get_mut(&king) {
+                            *time = time.saturating_add(elapsed);
+                            if *time >= HILL_SCORE_LIMIT {
+                                self.winner = Some(Scorer::Player(king));
+                            }
+                        }
+                    }
+                }
+            },
+
+            Rules::GunGame { levels } => {
+                GameMode::sync_players(levels, arena);
+                for killer in knockouts.filter_map(|(_, killer)| killer) {
+                    if let Some(level) = levels.get_mut(&killer) {
+                        *level += 1;
+                        if *level >= WeaponType::COUNT {
+                            self.winner = Some(Scorer::Player(killer));
+                            return;
+                        }
+                    }
+                }
+
+                // players always hold the weapon of their level, even after picking up
+                // another weapon or emptying theirs.
+                for (id, level) in levels.iter() {
+                    let weapontype = WeaponType::from_repr(*level).expect("Level should be within the catalog");
+                    if let Some(player) = arena.get_mut_player(*id) {
+                        if player.get_weapon().get_weapon_type() != weapontype {
+                            player.equip_weapon(weapontype, curr_time);
+                        }
+                    }
+                }
+            },
+
+            Rules::CaptureTheFlag { teams, flags, captures } => {
+                // new players join the team with fewer players.
+                teams.retain(|id, _| arena.get_player(*id).is_some());
+                let ids: Vec<EntityId> = arena.get_players().ids().collect();
+                for id in ids {
+                    if !teams.contains_key(&id) {
+                        let blue = teams.values().filter(|team| **team == 1).count();
+                        let team = (blue < teams.len() - blue) as u8;
+                        teams.insert(id, team);
+                        arena.get_mut_player(id).expect("Player should be in the arena").set_team(team);
+                    }
+                }
+
+                // carriers knocked out or gone drop the flag.
+                let knocked_out: Vec<EntityId> = knockouts.map(|(victim, _)| victim).collect();
+                for flag in flags.iter_mut() {
+                    if let Some(carrier) = flag.carrier {
+                        if knocked_out.contains(&carrier) || !teams.contains_key(&carrier) {
+                            flag.return_home();
+                        }
+                    }
+                }
+
+                for i in 0..flags.len() {
+                    // players of the other team touching the flag take it.
+                    if flags[i].carrier.is_none() {
+                        let (min, max) = flags[i].get_rect();
+                        flags[i].carrier = arena.query_players(min, max)
+                            .into_iter()
+                            .find(|id| teams.get(id).is_some_and(|team| *team as usize != i));
+                    }
+
+                    // the flag is carried above the head of the carrier, and is captured when the
+                    // carrier touches its own flag.
+                    if let Some(carrier) = flags[i].carrier {
+                        let player = arena.get_player(carrier).expect("Carrier should be in the arena");
+                        flags[i].position = player.get_position() + Vec2::new((player.width - FLAG_SIZE) / 2.0, -FLAG_SIZE);
+
+                        let team = teams[&carrier] as usize;
+                        let (min, max) = flags[team].get_rect();
+                        if flags[team].carrier.is_none() && player.overlaps(min, max) {
+                            flags[i].return_home();
+                            captures[team] += 1;
+                            if captures[team] >= CAPTURE_LIMIT {
+                                self.winner = Some(Scorer::Team(team as u8));
+                                return;
+                            }
+                        }
+                    }
+                }
+            },
+        }
+    }
+
+    /// starts sudden death once the time limit runs out with several players alive, and then
+    /// collapses the map following its schedule.
+    fn update_sudden_death(&mut self, arena: &mut Arena, curr_time: u128) {
+        if self.sudden_death.is_none() {
+            let expired = self.time_limit.is_some_and(|limit| curr_time >= self.start_time + limit);
+            let alive = arena.get_players().iter().filter(|(_, player)| !player.is_knocked_out()).count();
+            if expired && alive > 1 {
+                self.sudden_death = Some(SuddenDeath::new(arena.get_map().get_collapse_schedule(), curr_time));
+            }
+        }
+
+        if let Some(sudden_death) = &mut self.sudden_death {
+            sudden_death.update(arena, curr_time);
+        }
+    }
+
+    /// keeps track of the players who joined the arena, and forgets the players who left.
+    fn sync_players<T: Default>(scores: &mut BTreeMap<EntityId, T>, arena: &Arena) {
+        scores.retain(|id, _| arena.get_player(*id).is_some());
+        for id in arena.get_players().ids() {
+            scores.entry(id).or_default();
+        }
+    }
+}
@@ -0,0 +1,94 @@
+/// Environmental hazards which are part of the map.
+///
+/// Spikes and lava lie on top of the cells of the map grid, and hurt the players touching them.
+/// Wind zones push the players and bullets inside them with a constant force.
+use glam::{Vec2, const_vec2};
+use serde::{Serialize, Deserialize};
+use strum_macros::{Display, EnumCount, FromRepr};
+use crate::arena::Arena;
+use crate::BLOCK_WIDTH;
+
+/// height of the spikes or lava lying on top of a cell.
+pub const HAZARD_HEIGHT: f32 = 16.0;
+
+/// damage (in percent) added by falling into lava.
+pub const LAVA_DAMAGE: f32 = 25.0;
+
+/// velocity of a player bouncing off lava.
+pub const LAVA_BOUNCE_VELOCITY: Vec2 = const_vec2!([0.0, -700.0]);
+
+/// Type of the hazard lying on a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumCount, FromRepr, Display)]
+pub enum HazardType {
+    /// knocks out the players touching it.
+    Spikes,
+    /// adds `LAVA_DAMAGE` to the players falling into it, and bounces them up.
+    Lava,
+}
+
+/// A hazard lying on top of the cell at `row` and `col` of the map.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hazard {
+    row: usize,
+    col: usize,
+    hazardtype: HazardType,
+}
+
+impl Hazard {
+    pub fn new(row: usize, col: usize, hazardtype: HazardType) -> Self {
+        Self { row, col, hazardtype }
+    }
+
+    pub fn get_row(&self) -> usize {
+        self.row
+    }
+
+    pub fn get_col(&self) -> usize {
+        self.col
+    }
+
+    pub fn get_hazard_type(&self) -> HazardType {
+        self.hazardtype
+    }
+
+    /// obtains the top left and bottom right corners of the hazard. The bottom of the hazard
+    /// is at the top of the cell, so players standing on a block of the cell touch it.
+    pub fn get_rect(&self) -> (Vec2, Vec2) {
+        let bottom = Arena::get_block_row_position(self.row);
+        let left = Arena::get_block_col_position(self.col);
+        (Vec2::new(left, bottom - HAZARD_HEIGHT), Vec2::new(left + BLOCK_WIDTH, bottom))
+    }
+}
+
+/// A rectangle of the map where the wind pushes players and bullets with a constant `force`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindZone {
+    /// the top left corner of the zone.
+    min: Vec2,
+    /// the bottom right corner of the zone.
+    max: Vec2,
+    force: Vec2,
+}
+
+impl WindZone {
+    pub fn new(min: Vec2, max: Vec2, force: Vec2) -> Self {
+        Self { min, max, force }
+    }
+
+    pub fn get_min(&self) -> Vec2 {
+        self.min
+    }
+
+    pub fn get_max(&self) -> Vec2 {
+        self.max
+    }
+
+    pub fn get_force(&self) -> Vec2 {
+        self.force
+    }
+
+    /// whether the point is inside the zone.
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.min.cmple(point).all() && point.cmple(self.max).all()
+    }
+}
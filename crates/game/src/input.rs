@@ -10,10 +10,13 @@ pub enum Input {
     Shoot,
     Bomb,
     Throw,
+    Melee,
+    Reload,
+    Dash,
 }
 
 #[derive(Debug, Clone, Copy)]
-pub struct InputMask(u8);
+pub struct InputMask(u32);
 
 impl InputMask {
     pub fn new() -> Self {
@@ -45,13 +48,13 @@ impl fmt::Display for InputMask {
     }
 }
 
-impl From<u8> for InputMask {
-    fn from(inputdata: u8) -> Self {
+impl From<u32> for InputMask {
+    fn from(inputdata: u32) -> Self {
         Self(inputdata)
     }
 }
 
-impl From<InputMask> for u8 {
+impl From<InputMask> for u32 {
     fn from(inputmask: InputMask) -> Self {
         inputmask.0
     }
@@ -8,6 +8,22 @@ pub mod player;
 pub mod weapon;
 pub mod input;
 pub mod weaponscatalog;
+pub mod characterscatalog;
+pub mod bot;
+pub mod broadphase;
+pub mod environment;
+pub mod event;
+pub mod entity;
+pub mod pickup;
+pub mod effect;
+pub mod gamemode;
+pub mod stats;
+pub mod hazard;
+pub mod suddendeath;
+pub mod teleporter;
+pub mod query;
+pub mod fixed;
+pub mod script;
 
 #[cfg(test)]
 mod unittests;
@@ -25,8 +41,8 @@ pub const PLAYER_SPEED_CAP: f32 = 400.0;
 /// default gravity limit (positive orientation is downwards).
 pub const GRAVITY_DEFAULT: Vec2 = const_vec2!([0.0, 2500.0]);
 
-/// acceleration for jumping.
-pub const JUMP_ACCEL: Vec2 = const_vec2!([0.0, -60000.0]);
+/// velocity gained by jumping (before accounting for the mass of the weapon).
+pub const JUMP_VELOCITY: Vec2 = const_vec2!([0.0, -500.0]);
 
 /// friction in air to allow for minor control of character while not touching ground.
 pub const AIR_FRICTION: f32 = 20000.0;
@@ -55,3 +71,56 @@ pub const ERROR_THRESHOLD: f32 = 1e-6;
 
 /// jump cooldown in milliseconds
 pub const JUMP_COOLDOWN: u128 = 350;
+
+/// number of ticks a jump pressed too early (e.g. just before landing) is remembered for.
+pub const JUMP_BUFFER_TICKS: u32 = 6;
+
+/// number of ticks after walking off a block during which the player can still do a ground jump.
+pub const COYOTE_TICKS: u32 = 6;
+
+/// extra knockback per percent of damage taken. A player with 100% damage is knocked back
+/// twice as far as a player with no damage.
+pub const KNOCKBACK_SCALING: f32 = 0.01;
+
+/// share of the momentum of a fired bullet which pushes the shooter back. Recoil used to be a
+/// force over a single tick, which only gave half of the momentum, and the weapons are balanced
+/// for that.
+pub const RECOIL_SCALING: f32 = 0.5;
+
+/// time between two melee shoves in milliseconds.
+pub const MELEE_COOLDOWN: u128 = 400;
+
+/// how far the melee hitbox reaches in front of the player.
+pub const MELEE_RANGE: f32 = 24.0;
+
+/// horizontal momentum transferred by a melee shove (before damage scaling).
+pub const MELEE_MOMENTUM: f32 = 12000.0;
+
+/// damage (in percent) added by a melee shove.
+pub const MELEE_DAMAGE: f32 = 3.0;
+
+/// fraction of the overlap between two player bodies which is pushed apart on every update.
+/// 1.0 separates the players right away, while lower values let them squeeze past each other.
+pub const BODY_PUSH_STRENGTH: f32 = 0.5;
+
+/// velocity of a player bouncing off the head of another player.
+pub const STOMP_BOUNCE_VELOCITY: Vec2 = const_vec2!([0.0, -450.0]);
+
+/// downward momentum given to a player stomped on by another player.
+pub const STOMP_MOMENTUM: Vec2 = const_vec2!([0.0, 5000.0]);
+
+/// horizontal velocity gained by a dash (before accounting for the mass of the weapon).
+pub const DASH_VELOCITY: f32 = 1000.0;
+
+/// how long a dash lasts in milliseconds. The player is slowed back down to its speed cap
+/// afterwards.
+pub const DASH_DURATION: u128 = 150;
+
+/// time between two dashes in milliseconds, for a dash started on a block.
+pub const DASH_GROUND_COOLDOWN: u128 = 600;
+
+/// time between two dashes in milliseconds, for a dash started in the air.
+pub const DASH_AIR_COOLDOWN: u128 = 1200;
+
+/// time in milliseconds from the start of a dash during which the player cannot be hit.
+pub const DASH_INVULNERABILITY: u128 = 200;
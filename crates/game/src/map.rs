@@ -1,10 +1,16 @@
 use core::fmt;
+use std::ops::RangeInclusive;
 use std::fs::File;
-use std::io::BufReader;
 use std::io::BufWriter;
+use std::io::Write;
 use crate::block;
 use crate::block::BlockType;
-use bincode::deserialize_from;
+use crate::hazard::{Hazard, HazardType, WindZone};
+use crate::query;
+use crate::suddendeath::CollapseSchedule;
+use crate::teleporter::{self, TeleporterPair};
+use glam::Vec2;
+use bincode::deserialize;
 use bincode::serialize_into;
 use serde::{Serialize , Deserialize};
 use strum::EnumCount;
@@ -16,6 +22,14 @@ use strum::IntoEnumIterator;
 /// TODO: refactor into actual enum of different errors.
 const INVALID_MAP: &str = "Invalid Map";
 
+/// version of the format of the map files, written before the map. The first format had no
+/// version, and held only the grass and ice blocks.
+const MAP_VERSION: u8 = 1;
+
+/// size in bytes of a map file of the first format, which held the bits of the grass and ice
+/// blocks.
+const LEGACY_MAP_SIZE: usize = 2 * std::mem::size_of::<i128>();
+
 /// width of a map in blocks
 pub const HORIZONTAL_BLOCKS: usize = 16;
 
@@ -45,7 +59,7 @@ pub type MapBlocksList = [i128; BlockType::COUNT];
 
 
 /// Bits used to construct a map.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapBlocks(MapBlocksList);
 
 impl From<MapBlocksList> for MapBlocks {
@@ -67,6 +81,20 @@ impl From<MapBlocksList> for MapBlocks {
 /// 7 15 ... 127
 pub(crate) struct MapBits(i128);
 
+impl MapBits {
+    /// obtains the cells (row, col) of the set bits, in increasing order of column then row.
+    fn to_cells(&self) -> Vec<(usize, usize)> {
+        let mut bits = self.0 as u128;
+        let mut cells = Vec::new();
+        while bits != 0 {
+            let i = bits.trailing_zeros() as usize;
+            cells.push((i % VERTICAL_BLOCKS, i / VERTICAL_BLOCKS));
+            bits &= bits - 1;
+        }
+        cells
+    }
+}
+
 /// MapBits represented as a string.
 impl fmt::Display for MapBits {
 
@@ -101,7 +129,7 @@ impl fmt::Display for MapBits {
 /// A Map is represented by the locations of all the blocks, 
 /// in an 8x16 array. The surrounding padding is part of the arena.
 /// Look at the arena module for more details.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Map {
     /// an array containing information about all the blocks. Each type
     /// of block is represented as a `i128` type (the MapBits struct), 
@@ -111,6 +139,18 @@ pub struct Map {
     /// In order to access the information for a specific block type,
     /// call the `get_blocks_of_type(BlockType)` method.
     mapblocks: MapBlocks,
+
+    /// spikes and lava lying on top of the cells of the map.
+    hazards: Vec<Hazard>,
+
+    /// rectangles of the arena where the wind pushes players and bullets.
+    wind_zones: Vec<WindZone>,
+
+    /// pairs of linked teleporters standing on top of the cells of the map.
+    teleporters: Vec<TeleporterPair>,
+
+    /// how the map collapses during sudden death.
+    collapse_schedule: CollapseSchedule,
 }
 
 impl fmt::Display for Map {
@@ -123,13 +163,14 @@ impl fmt::Display for Map {
 impl Default for Map {
     /// creates a default map. Used only for testing.
     ///
-    /// alternates between all of the blocks of all types on each row,
-    /// covering all possible block locations. e.g. row 1 is all grass,
-    /// row 2 is all ice, etc...
+    /// alternates between grass and ice blocks on each row, covering all
+    /// possible block locations. e.g. row 1 is all grass, row 2 is all ice,
+    /// etc... Solid blocks are left out, so players can drop down every row.
     fn default() -> Map {
+        let platforms = [BlockType::GrassBlock, BlockType::IceBlock];
         let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
         for i in 0..VERTICAL_BLOCKS {
-            data[i % BlockType::COUNT] |= ROWMASK << i;
+            data[platforms[i % platforms.len()] as usize] |= ROWMASK << i;
         }
 
         let mapblocks: MapBlocks = data.into();
@@ -141,29 +182,63 @@ impl Map {
     /// Constructs a new map
     pub fn new(mapblocks: MapBlocks) -> Result<Map, &'static str> {
         let mapblocks = Map::verify_mapblocks(mapblocks)?;
-        Ok(Map { mapblocks })
+        let collapse_schedule = CollapseSchedule::default();
+        Ok(Map { mapblocks, hazards: Vec::new(), wind_zones: Vec::new(), teleporters: Vec::new(), collapse_schedule })
     }
 
-    /// Constructs a new map from data saved in a file.
-    pub(crate) fn read_from_file(filename: &str) -> Result<Map, String> {
-        match File::open(filename) {
-            Ok(f) => {
-                let reader = BufReader::new(f);
-                match deserialize_from::<_, Map>(reader) {
-                    Ok(map) => Ok(map),
-                    Err(e) => Err(e.to_string())
-                }
+    /// Constructs a new map from data saved in a file. A file of the first format, without a
+    /// version, is read as a map of only grass and ice blocks. The blocks, hazards, teleporters
+    /// and collapse schedule read from the file are verified like the ones of a new map.
+    pub fn read_from_file(filename: &str) -> Result<Map, String> {
+        let bytes = std::fs::read(filename).map_err(|e| e.to_string())?;
+        match bytes.split_first() {
+            _ if bytes.len() == LEGACY_MAP_SIZE => {
+                let legacy: [i128; 2] = deserialize(&bytes).map_err(|e| e.to_string())?;
+                let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+                data[..legacy.len()].copy_from_slice(&legacy);
+                Ok(Map::new(data.into())?)
             },
-            Err(e) => Err(e.to_string())
+
+            Some((&MAP_VERSION, map_bytes)) => {
+                let map: Map = deserialize(map_bytes).map_err(|e| e.to_string())?;
+                Ok(Map::verify_map(map)?)
+            },
+
+            _ => Err(INVALID_MAP.to_string()),
         }
     }
 
-    pub(crate) fn write_to_file(&self, filename: &str) -> Result<(), std::io::Error> {
+    /// saves the map to a file, after the version of the format (`MAP_VERSION`).
+    pub fn write_to_file(&self, filename: &str) -> Result<(), std::io::Error> {
         let mut f = BufWriter::new(File::create(filename)?);
-        match serialize_into(&mut f, &self) {
-            Ok(_) => Ok(()),
-            Err(_) => todo!(),
+        f.write_all(&[MAP_VERSION])?;
+        serialize_into(&mut f, &self).map_err(std::io::Error::other)
+    }
+
+    /// verifies if a map read from a file is legal, by building it again from its blocks,
+    /// hazards, wind zones, teleporters and collapse schedule.
+    fn verify_map(map: Map) -> Result<Map, &'static str> {
+        let mut verified = Map::new(map.mapblocks)?;
+        for hazard in map.hazards {
+            verified.add_hazard(hazard)?;
+        }
+
+        for wind_zone in map.wind_zones {
+            verified.add_wind_zone(wind_zone);
         }
+
+        for pair in map.teleporters {
+            verified.add_teleporter_pair(pair)?;
+        }
+
+        let schedule = map.collapse_schedule;
+        verified.set_collapse_schedule(CollapseSchedule::new(
+            schedule.get_interval(),
+            schedule.get_warning(),
+            schedule.get_center_cols(),
+            schedule.get_center_rows(),
+        )?);
+        Ok(verified)
     }
 
     /// verifies if mapblocks can form a legal map.
@@ -228,6 +303,123 @@ impl Map {
         &self.mapblocks.0
     }
 
+    /// replaces the block at `row` and `col` with a block of type `blocktype`, or removes it
+    /// if `blocktype` is None.
+    pub(crate) fn set_block(&mut self, row: usize, col: usize, blocktype: Option<BlockType>) {
+        let bit: i128 = 1 << (col * VERTICAL_BLOCKS + row);
+        let MapBlocks(mapblocks) = &mut self.mapblocks;
+        for bits in mapblocks.iter_mut() {
+            *bits &= !bit;
+        }
+
+        if let Some(blocktype) = blocktype {
+            mapblocks[blocktype as usize] |= bit;
+        }
+    }
+
+    /// places a hazard on top of a cell of the map. A cell holds at most one hazard, so this
+    /// replaces the hazard already on the cell.
+    pub fn add_hazard(&mut self, hazard: Hazard) -> Result<(), &'static str> {
+        if hazard.get_row() >= VERTICAL_BLOCKS || hazard.get_col() >= HORIZONTAL_BLOCKS {
+            return Err(INVALID_MAP);
+        }
+
+        self.hazards.retain(|h| (h.get_row(), h.get_col()) != (hazard.get_row(), hazard.get_col()));
+        self.hazards.push(hazard);
+        Ok(())
+    }
+
+    pub fn get_hazards(&self) -> &[Hazard] {
+        &self.hazards
+    }
+
+    pub fn add_wind_zone(&mut self, wind_zone: WindZone) {
+        self.wind_zones.push(wind_zone);
+    }
+
+    pub fn get_wind_zones(&self) -> &[WindZone] {
+        &self.wind_zones
+    }
+
+    /// links two teleporters standing on top of cells of the map. A cell holds at most one
+    /// teleporter, and a teleporter cannot be linked to itself.
+    pub fn add_teleporter_pair(&mut self, pair: TeleporterPair) -> Result<(), &'static str> {
+        let (first, second) = (pair.get_first(), pair.get_second());
+        let outside = |(row, col): (usize, usize)| row >= VERTICAL_BLOCKS || col >= HORIZONTAL_BLOCKS;
+        let taken = |cell| self.teleporters.iter().any(|p| p.get_partner(cell).is_some());
+        if first == second || outside(first) || outside(second) || taken(first) || taken(second) {
+            return Err(INVALID_MAP);
+        }
+
+        self.teleporters.push(pair);
+        Ok(())
+    }
+
+    pub fn get_teleporter_pairs(&self) -> &[TeleporterPair] {
+        &self.teleporters
+    }
+
+    /// finds the first teleporter entered by an object whose center moves from `start` to
+    /// `end`, where `half_size` is half of the size of the object. Returns the cells of the
+    /// entered teleporter and of its partner.
+    pub(crate) fn get_teleporter_crossed(&self, start: Vec2, end: Vec2, half_size: Vec2) -> Option<((usize, usize), (usize, usize))> {
+        self.teleporters.iter()
+            .flat_map(|pair| [(pair.get_first(), pair.get_second()), (pair.get_second(), pair.get_first())])
+            .filter_map(|(entrance, exit)| {
+                let (min, max) = teleporter::get_teleporter_rect(entrance);
+                query::sweep_segment(start, end, min - half_size, max + half_size).map(|t| (t, entrance, exit))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, entrance, exit)| (entrance, exit))
+    }
+
+    pub fn get_collapse_schedule(&self) -> CollapseSchedule {
+        self.collapse_schedule
+    }
+
+    pub fn set_collapse_schedule(&mut self, collapse_schedule: CollapseSchedule) {
+        self.collapse_schedule = collapse_schedule;
+    }
+
+    /// obtains the type of the first hazard overlapping the rectangle from `min` (top left) to
+    /// `max` (bottom right), if any.
+    pub(crate) fn get_hazard_touching(&self, min: Vec2, max: Vec2) -> Option<HazardType> {
+        self.hazards.iter()
+            .find(|hazard| {
+                let (hazard_min, hazard_max) = hazard.get_rect();
+                hazard_min.cmple(max).all() && min.cmple(hazard_max).all()
+            })
+            .map(|hazard| hazard.get_hazard_type())
+    }
+
+    /// obtains the total force of the wind zones containing the point.
+    pub(crate) fn get_wind_force_at(&self, point: Vec2) -> Vec2 {
+        self.wind_zones.iter()
+            .filter(|zone| zone.contains(point))
+            .fold(Vec2::ZERO, |force, zone| force + zone.get_force())
+    }
+
+    /// obtains the cells (row, col) occupied by a block within the `rows` and the `cols`, in
+    /// increasing order of column then row.
+    pub(crate) fn get_occupied_cells(&self, rows: RangeInclusive<usize>, cols: RangeInclusive<usize>) -> Vec<(usize, usize)> {
+        let rows_mask = COLMASK & !((1 << rows.start()) - 1) & ((1 << (rows.end() + 1)) - 1);
+        let mask = cols.fold(0, |mask, col| mask | (rows_mask << (col * VERTICAL_BLOCKS)));
+        MapBits(self.get_all_occupied().0 & mask).to_cells()
+    }
+
+    /// obtains the cells (row, col) of the blocks at the ends of the platforms, along with the
+    /// side of the end (left=-1.0 or right=+1.0). A block alone on its row is at both ends.
+    pub(crate) fn get_platform_ends(&self) -> Vec<(usize, usize, f32)> {
+        // the bits of the next column are `VERTICAL_BLOCKS` bits higher.
+        let occupied = self.get_all_occupied().0 as u128;
+        let left_ends = occupied & !(occupied << VERTICAL_BLOCKS);
+        let right_ends = occupied & !(occupied >> VERTICAL_BLOCKS);
+
+        let left_cells = MapBits(left_ends as i128).to_cells().into_iter().map(|(row, col)| (row, col, -1.0));
+        let right_cells = MapBits(right_ends as i128).to_cells().into_iter().map(|(row, col)| (row, col, 1.0));
+        left_cells.chain(right_cells).collect()
+    }
+
     /// returns the index of the first row containing a block
     /// below the point defined by row and col. if there is no block, then returns
     /// a number greater than VERTICAL_BLOCKS.
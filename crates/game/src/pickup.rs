@@ -0,0 +1,107 @@
+/// Items lying on the platforms of the map, which players pick up by walking into them.
+use glam::Vec2;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use strum::EnumCount;
+use crate::block::BlockRect;
+use crate::effect::EffectType;
+use crate::weaponscatalog::WeaponType;
+
+/// width and height of a pickup.
+pub const PICKUP_SIZE: f32 = 24.0;
+
+/// time between two pickups spawning on the platforms, in ms.
+pub const PICKUP_SPAWN_INTERVAL: u128 = 10000;
+
+/// most pickups lying on the map at once.
+pub const MAX_PICKUPS: usize = 3;
+
+/// What the player touching the pickup obtains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickupKind {
+    /// replaces the current weapon of the player.
+    Weapon(WeaponType),
+    /// gives a status effect to the player.
+    Effect(EffectType),
+}
+
+/// An item lying on the map.
+#[derive(Debug, Clone)]
+pub struct Pickup {
+    /// the top left corner of the pickup.
+    position: Vec2,
+    kind: PickupKind,
+}
+
+impl Pickup {
+    pub fn new(position: Vec2, kind: PickupKind) -> Self {
+        Self { position, kind }
+    }
+
+    pub fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn get_kind(&self) -> PickupKind {
+        self.kind
+    }
+
+    /// whether the pickup overlaps the rectangle from `min` (top left) to `max` (bottom right).
+    pub fn overlaps(&self, min: Vec2, max: Vec2) -> bool {
+        let bottomright = self.position + Vec2::splat(PICKUP_SIZE);
+        self.position.cmple(max).all() && min.cmple(bottomright).all()
+    }
+}
+
+/// Places random pickups on top of the platforms every `PICKUP_SPAWN_INTERVAL`.
+#[derive(Debug, Clone)]
+pub struct PickupSpawner {
+    rng: StdRng,
+    next_spawn_time: u128,
+}
+
+impl PickupSpawner {
+    /// creates a spawner. The same `seed` always spawns the same pickups at the same places.
+    pub fn new(seed: u64) -> Self {
+        let rng = StdRng::seed_from_u64(seed);
+        Self { rng, next_spawn_time: PICKUP_SPAWN_INTERVAL }
+    }
+
+    /// whether it is time to spawn the next pickup.
+    pub(crate) fn is_due(&self, curr_time: u128) -> bool {
+        curr_time >= self.next_spawn_time
+    }
+
+    /// spawns a pickup on top of one of the `blocks`, unless there are already `MAX_PICKUPS`
+    /// pickups on the map. The next pickup spawns `PICKUP_SPAWN_INTERVAL` later.
+    pub(crate) fn spawn(&mut self,
+                        curr_time: u128,
+                        blocks: &[BlockRect],
+                        pickups_count: usize) -> Option<Pickup> {
+
+        self.next_spawn_time = curr_time + PICKUP_SPAWN_INTERVAL;
+        if pickups_count >= MAX_PICKUPS || blocks.is_empty() {
+            return None;
+        }
+
+        let position = get_pickup_spot(&blocks[self.rng.gen_range(0..blocks.len())]);
+
+        let i = self.rng.gen_range(0..WeaponType::COUNT + EffectType::COUNT);
+        let kind = match WeaponType::from_repr(i) {
+            Some(weapontype) => PickupKind::Weapon(weapontype),
+            None => {
+                let effecttype = EffectType::from_repr(i - WeaponType::COUNT)
+                    .expect("Index should be within the effect types");
+                PickupKind::Effect(effecttype)
+            },
+        };
+
+        Some(Pickup::new(position, kind))
+    }
+}
+
+/// obtains the position of a pickup lying in the middle of the top of the `block`.
+pub(crate) fn get_pickup_spot(block: &BlockRect) -> Vec2 {
+    let x = block.x + (block.w - PICKUP_SIZE) / 2.0;
+    let y = block.y - PICKUP_SIZE;
+    Vec2::new(x, y)
+}
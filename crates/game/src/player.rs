@@ -1,16 +1,25 @@
-use std::{time::SystemTime, collections::HashMap};
-
+use crate::effect::{EffectType, StatusEffect, HEAVY_BODY_MULTIPLIER, SPEED_BOOST_MULTIPLIER};
+use crate::entity::{EntityId, EntityStore};
+use crate::event::GameEvent;
+use crate::fixed::{self, RealVec2};
 use crate::weapon::{Weapon, WeaponStatus, Bullet};
 use crate::weaponscatalog::WeaponType;
+use crate::characterscatalog::{CharacterClass, CLASS_JUMPS_COUNTS, CLASS_JUMP_VELOCITIES, CLASS_MASSES};
+use crate::characterscatalog::{CLASS_SIZES, CLASS_SPEED_CAPS, CLASS_WEAPONS};
 use glam::Vec2;
 use crate::GRAVITY_DEFAULT;
 use crate::JUMP_COOLDOWN;
-use crate::JUMP_ACCEL;
+use crate::{COYOTE_TICKS, JUMP_BUFFER_TICKS};
+use crate::JUMP_VELOCITY;
+use crate::{KNOCKBACK_SCALING, RECOIL_SCALING};
+use crate::{MELEE_COOLDOWN, MELEE_RANGE};
+use crate::{DASH_AIR_COOLDOWN, DASH_DURATION, DASH_GROUND_COOLDOWN, DASH_INVULNERABILITY, DASH_VELOCITY};
 use crate::ARENA_WIDTH;
 use crate::PLAYER_SPEED_CAP;
 use crate::PLAYER_MASS;
 use crate::PLAYER_HEIGHT;
 use crate::PLAYER_WIDTH;
+use crate::teleporter::TELEPORT_COOLDOWN;
 
 /// Since the display grid has increasing y for going lower on screen,
 /// the convention will be downward y direction is positive.
@@ -21,24 +30,62 @@ use crate::PLAYER_WIDTH;
 /// facing direction (e.g. when player gets shot from the front).
 #[derive(Debug)]
 pub struct Player {
-    /// the top left corner of the player.
-    pub position: Vec2,
-    pub velocity: Vec2,
-    pub acceleration: Vec2,
+    /// the top left corner of the player. The movement of the player is kept as `RealVec2`,
+    /// and is seen as `Vec2` outside of the movement through `get_position` and `get_velocity`.
+    pub(crate) position: RealVec2,
+    pub(crate) velocity: RealVec2,
+    pub(crate) acceleration: RealVec2,
     pub name: String,
     pub width: f32,
     pub height: f32,
     pub direction: f32,
+    /// unit vector the player is aiming at.
+    aim: Vec2,
     pub mass: f32,
     pub speed_cap: f32,
     pub jumps_count: u8,
     pub jumps_left: u8,
+    /// velocity gained by a jump (before accounting for the mass of the weapon).
+    pub jump_velocity: Vec2,
+    /// number of ticks a jump input is buffered for.
+    pub jump_buffer_ticks: u32,
+    /// number of ticks the ground jump is kept after leaving a block.
+    pub coyote_ticks: u32,
+    /// ticks left before the buffered jump input is dropped.
+    buffered_jump_ticks: u32,
+    /// ticks since the player last stood on a block.
+    ticks_in_air: u32,
     last_jump_time: u128,
+    last_melee_time: u128,
+    /// whether the player jumped during the current tick.
+    jumped: bool,
+    /// whether the player started a melee shove during the current tick.
+    meleed: bool,
+    /// whether the player started a dash during the current tick.
+    dashed: bool,
+    /// time at which the last dash started, if any.
+    last_dash_time: Option<u128>,
+    /// time at which the dash cooldown runs out.
+    dash_ready_at: u128,
+    /// whether the player is dashing, and is not slowed down to its speed cap yet.
+    dashing: bool,
+    /// time at which the teleport cooldown runs out.
+    teleport_ready_at: u128,
+    class: CharacterClass,
     default_weapontype: WeaponType,
     current_weapon: Weapon,
     team: u8,
+    /// damage taken (in percent), which makes the player fly farther when hit.
     damage_multiplier: f32,
-    lives: u8,
+    /// whether the player was knocked out, and is waiting to be respawned by the game mode.
+    knocked_out: bool,
+    last_hit_by: Option<EntityId>,
+    /// weapon thrown away during the last update, waiting to be added to the arena.
+    thrown_weapon: Option<Weapon>,
+    /// active status effects, at most one of each type.
+    effects: Vec<StatusEffect>,
+    /// whether the player was standing on a block during the previous update.
+    pub(crate) was_standing: bool,
 }
 
 impl Player {
@@ -62,9 +109,8 @@ impl Player {
     ///
     /// `drop_input` detects whether a valid drop input command was pushed (e.g. only when on block).
     pub fn update(&mut self, dt: f32, max_y: f32, drop_input: bool, direction: f32) {
-
-        // dx = vt + 1/2 at^2
-        let mut new_position = self.position + self.velocity * dt + 0.5 * self.acceleration * dt * dt;
+        let dt = fixed::real(dt);
+        let (mut new_position, _) = fixed::integrate(self.position, self.velocity, self.acceleration, dt);
 
         // make edits to player's new_position based on obstacles between the original and final
         // destinations.
@@ -76,18 +122,23 @@ impl Player {
         //
         // drop_input puts in a 1 pixel offset so players can "phase" through the block if a down
         // input is sent successfully.
-        let drop_height = drop_input as u8 as f32;
-        new_position.y = f32::min(max_y - self.height, new_position.y) + drop_height;
+        let drop_height = fixed::real(drop_input as u8 as f32);
+        new_position.y = (fixed::real(max_y) - fixed::real(self.height)).min(new_position.y) + drop_height;
 
-        self.velocity = (new_position - self.position) / dt;
+        self.velocity = fixed::get_velocity_between(self.position, new_position, dt);
 
-        self.update_position(new_position, direction);
+        self.move_to(new_position, direction);
 
         // resets the acceleration component for next function call to add forces.
-        self.acceleration = Vec2::ZERO;
+        self.acceleration = RealVec2::ZERO;
     }
 
     pub fn update_position(&mut self, new_position: Vec2, direction: f32) {
+        self.move_to(fixed::real_vec2(new_position), direction);
+    }
+
+    /// moves the player to `new_position`, like `update_position` without leaving the movement.
+    pub(crate) fn move_to(&mut self, new_position: RealVec2, direction: f32) {
         // sets the player's direction based on input left or right. if no input, then just keep
         // current direction facing.
         if direction != 0.0 {
@@ -96,80 +147,189 @@ impl Player {
         self.position = new_position;
 
         // updates the gun that the player is holding.
-        self.current_weapon.set_position(self.position);
-        self.current_weapon.set_direction(self.direction);
+        self.current_weapon.move_to(self.position);
+    }
+
+    /// obtains the top left corner of the player.
+    pub fn get_position(&self) -> Vec2 {
+        fixed::to_vec2(self.position)
+    }
+
+    pub fn get_velocity(&self) -> Vec2 {
+        fixed::to_vec2(self.velocity)
+    }
+
+    /// sets the velocity of the player (e.g. to set up a scenario).
+    pub fn set_velocity(&mut self, velocity: Vec2) {
+        self.velocity = fixed::real_vec2(velocity);
+    }
+
+    /// aims in one of eight directions. `horizontal` is the left/right input, and `vertical` is
+    /// the up/down input (up=-1.0, down=+1.0). Without any vertical input, or with a horizontal
+    /// input, the player aims towards the direction it is facing.
+    pub(crate) fn set_aim_input(&mut self, horizontal: f32, vertical: f32) {
+        let facing = if horizontal != 0.0 { horizontal } else { self.direction };
+        let x = if vertical != 0.0 && horizontal == 0.0 { 0.0 } else { facing };
+        self.set_aim(Vec2::new(x, vertical).normalize());
+    }
+
+    /// points the player's weapon at `aim`, which is a unit vector.
+    pub fn set_aim(&mut self, aim: Vec2) {
+        self.aim = aim;
+        self.current_weapon.set_aim(aim);
+    }
+
+    pub fn get_aim(&self) -> Vec2 {
+        self.aim
     }
 
 
+    /// whether the point is inside the player's rectangle.
+    pub fn contains(&self, point: Vec2) -> bool {
+        let position = self.get_position();
+        let bottomright = position + Vec2::new(self.width, self.height);
+        position.cmple(point).all() && point.cmple(bottomright).all()
+    }
+
     /// adds a force to the player. Returns a mutable reference to self, so
     /// more forces can be added with subsequent function calls.
-    pub(crate) fn add_force(&mut self, force: Vec2) -> &mut Player {
-        self.acceleration += force / self.get_total_mass();
+    pub(crate) fn add_force(&mut self, force: RealVec2) -> &mut Player {
+        self.acceleration += force / fixed::real(self.get_total_mass());
+        self
+    }
+
+    /// adds an impulse (an instant change in momentum) to the player, which changes the
+    /// velocity right away. Unlike a force, which acts over the length of a tick, the change in
+    /// velocity does not depend on the tick rate. Returns a mutable reference to self, so more
+    /// impulses and forces can be added with subsequent function calls.
+    pub fn add_impulse(&mut self, impulse: RealVec2) -> &mut Player {
+        self.velocity += impulse / fixed::real(self.get_total_mass());
         self
     }
 
-    pub(crate) fn add_jump_force(&mut self, standing_on_block: bool, jump_input: bool) -> &mut Player {
-        // removes a jump if not standing on block, if possible.
-        if !standing_on_block {
-            self.jumps_left = u8::min(self.jumps_count - 1, self.jumps_left);
+    /// adds the jump impulse if `jump_input` is pressed, or was pressed within the last
+    /// `jump_buffer_ticks` ticks. `curr_time` is the simulation time of the arena in
+    /// milliseconds, used for the jump cooldown. Must be called once per tick.
+    pub(crate) fn add_jump_impulse(&mut self, standing_on_block: bool, jump_input: bool, curr_time: u128) -> &mut Player {
+        // removes the ground jump once the player has been off the blocks for longer
+        // than the coyote time, if possible.
+        if standing_on_block {
+            self.ticks_in_air = 0;
+            self.jumps_left = self.get_jumps_count();
         } else {
-            self.jumps_left = self.jumps_count;
+            self.ticks_in_air = self.ticks_in_air.saturating_add(1);
+            if self.ticks_in_air > self.coyote_ticks {
+                self.jumps_left = u8::min(self.get_jumps_count() - 1, self.jumps_left);
+            }
+        }
+
+        // buffers the jump input, so a jump pressed too early still goes through.
+        if jump_input {
+            self.buffered_jump_ticks = self.jump_buffer_ticks.saturating_add(1);
         }
+        let wants_jump = self.buffered_jump_ticks > 0;
+        self.buffered_jump_ticks -= wants_jump as u32;
 
-        // let jump_force be a function of the number of jumps left, so
+        // let jump_impulse be a function of the number of jumps left, so
         // subsequent midair jumps are weaker compared to a ground jump.
         //
         // if jump was unsuccessful (cooldown active, or no more jumps left),
-        // then return the zero vector for the jump force. Automatically docks
+        // then no impulse is added. Automatically docks
         // one from the `jumps_left` variable if possible.
-        // adds the jump force if input is pressed.
-        let curr_time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Unable to get current time!")
-            .as_millis();
-
+        // adds the jump impulse if input is pressed.
         let still_has_jumps = self.jumps_left > 0;
-        let time_since_last_jump = curr_time - self.last_jump_time;
+        let time_since_last_jump = curr_time.saturating_sub(self.last_jump_time);
 
-        if jump_input && still_has_jumps && time_since_last_jump > JUMP_COOLDOWN {
+        self.jumped = wants_jump && still_has_jumps && time_since_last_jump > JUMP_COOLDOWN;
+        if self.jumped {
             // TODO: figure out a good function to use so double jumping results in the 
             // same final position regardless of when the player inputted the 2nd jump input.
             // let fraction: f32 = self.jumps_left as f32 / self.jumps_count as f32;
             self.last_jump_time = curr_time;
             self.jumps_left -= 1;
+            self.buffered_jump_ticks = 0;
 
             let multiplier: f32 = 1.0;
-            self.add_force(multiplier * self.mass * JUMP_ACCEL)
+            self.add_impulse(fixed::real_vec2(multiplier * self.mass * self.jump_velocity))
         } else {
             // don't do anything if unable to jump or no jump inputted.
             self
         }
     }
 
-    /// calculates and adds the recoil force to the player.
-    pub(crate) fn add_recoil_force(&mut self,
-                                   has_shoot_input: bool,
-                                   dt: f32,
-                                   next_id: &mut u16,
-                                   bullets: &mut HashMap<u16, Bullet>) -> &mut Player {
+    /// dashes towards `direction` (or towards the facing direction without any) if `dash_input`
+    /// is pressed and the dash cooldown is over. Dashing from the air has a longer cooldown than
+    /// dashing from a block.
+    pub(crate) fn add_dash_impulse(&mut self, standing_on_block: bool, dash_input: bool, direction: f32, curr_time: u128) -> &mut Player {
+        self.dashed = dash_input && curr_time >= self.dash_ready_at;
+        if !self.dashed {
+            return self;
+        }
+
+        let direction = if direction != 0.0 { direction } else { self.direction };
+        let cooldown = if standing_on_block { DASH_GROUND_COOLDOWN } else { DASH_AIR_COOLDOWN };
+        self.last_dash_time = Some(curr_time);
+        self.dash_ready_at = curr_time + cooldown;
+        self.dashing = true;
+        self.add_impulse(fixed::real_vec2(Vec2::new(direction * self.mass * DASH_VELOCITY, 0.0)))
+    }
+
+    /// ends the dash once it lasted `DASH_DURATION` ms, slowing the player back down to its
+    /// speed cap.
+    pub(crate) fn update_dash(&mut self, curr_time: u128) {
+        let over = self.last_dash_time.is_some_and(|time| curr_time >= time + DASH_DURATION);
+        if self.dashing && over {
+            let speed_cap = fixed::real(self.get_speed_cap());
+            self.velocity.x = self.velocity.x.clamp(-speed_cap, speed_cap);
+            self.dashing = false;
+        }
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        self.dashing
+    }
+
+    /// whether the player started a dash during the current tick.
+    pub(crate) fn has_dashed(&self) -> bool {
+        self.dashed
+    }
+
+    /// whether the player cannot be hit at time `curr_time`, right after starting a dash.
+    pub fn is_invulnerable(&self, curr_time: u128) -> bool {
+        self.last_dash_time.is_some_and(|time| curr_time < time + DASH_INVULNERABILITY)
+    }
+
+    /// calculates and adds the recoil impulse to the player, which is opposite to the momentum
+    /// of the fired bullet, scaled by `RECOIL_SCALING`.
+    ///
+    /// `owner` is the id of this player in the arena, and `curr_time` is the simulation time of
+    /// the arena in milliseconds. Fired bullets and emptied weapons are reported to `events`.
+    pub(crate) fn add_recoil_impulse(&mut self,
+                                     has_shoot_input: bool,
+                                     curr_time: u128,
+                                     owner: EntityId,
+                                     bullets: &mut EntityStore<Bullet>,
+                                     events: &mut Vec<GameEvent>) -> &mut Player {
 
         if has_shoot_input {
-            match self.attack() {
+            let weapontype = self.current_weapon.weapontype;
+            match self.attack(curr_time) {
                 WeaponStatus::FireSuccess => {
                     // on successful fire, add the newly created bullet to be
                     // managed by the arena.
-                    let bullet = self.create_new_bullet(*next_id);
-                    bullets.insert(*next_id, bullet);
-                    *next_id += 1;
+                    let bullet = self.create_new_bullet(owner);
+                    let bullettype = bullet.get_bullet_type();
+                    let id = bullets.insert(bullet);
+                    events.push(GameEvent::BulletFired { player: owner, bullet: id, bullettype });
 
-                    // also calculates the recoil from firing the bullet and adds
-                    // it to the total force.
-                    self.add_force(-self.get_bullet_momentum() / dt)
+                    // also pushes the player back from firing the bullet.
+                    self.add_impulse(-self.get_bullet_momentum() * fixed::real(RECOIL_SCALING))
                 },
 
                 WeaponStatus::Empty => {
                     // TODO: automatically discards weapon and calculates the recoil
-                    self.add_force(Vec2::ZERO)
+                    events.push(GameEvent::WeaponEmptied { player: owner, weapontype });
+                    self
                 },
 
                 _ => self
@@ -179,93 +339,353 @@ impl Player {
         }
     }
 
+    /// knocks the player back after being hit by a bullet fired by `attacker`. The bullet adds
+    /// `damage` to the player, and then transfers its `momentum` scaled up by the damage taken.
+    ///
+    /// Returns the impulse applied to the player, or None if the hit was absorbed by a shield
+    /// instead, which breaks the shield.
+    pub(crate) fn add_knockback(&mut self, momentum: RealVec2, damage: f32, attacker: EntityId) -> Option<Vec2> {
+        if self.has_effect(EffectType::Shield) {
+            self.effects.retain(|effect| effect.get_effect_type() != EffectType::Shield);
+            return None;
+        }
+
+        self.damage_multiplier += damage;
+        let impulse = momentum * fixed::real(1.0 + self.damage_multiplier * KNOCKBACK_SCALING);
+        self.add_impulse(impulse);
+        self.last_hit_by = Some(attacker);
+        Some(fixed::to_vec2(impulse))
+    }
+
+    /// obtains the damage taken by the player, in percent.
+    pub fn get_damage(&self) -> f32 {
+        self.damage_multiplier
+    }
+
+    /// sets the damage taken by the player (e.g. with the damage received from the server).
+    pub fn set_damage(&mut self, damage: f32) {
+        self.damage_multiplier = damage;
+    }
+
+    /// gives the player the effect, or stacks it if the effect is already active.
+    pub(crate) fn add_effect(&mut self, effecttype: EffectType, curr_time: u128) {
+        let active = self.effects.iter_mut().find(|effect| effect.get_effect_type() == effecttype);
+        match active {
+            Some(effect) => effect.stack(curr_time),
+            None => self.effects.push(effecttype.start(curr_time)),
+        }
+    }
+
+    /// removes the effects which ran out at `curr_time`. Returns the removed effects.
+    pub(crate) fn update_effects(&mut self, curr_time: u128) -> Vec<EffectType> {
+        let mut expired = Vec::new();
+        self.effects.retain(|effect| {
+            let active = effect.get_remaining(curr_time) > 0;
+            if !active {
+                expired.push(effect.get_effect_type());
+            }
+            active
+        });
+
+        expired
+    }
+
+    pub fn has_effect(&self, effecttype: EffectType) -> bool {
+        self.effects.iter().any(|effect| effect.get_effect_type() == effecttype)
+    }
+
+    pub fn get_effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    /// replaces the active effects (e.g. with the effects received from the server).
+    pub fn set_effects(&mut self, effects: Vec<StatusEffect>) {
+        self.effects = effects;
+    }
+
+    /// obtains the speed cap of the player, with the effects accounted for.
+    pub fn get_speed_cap(&self) -> f32 {
+        if self.has_effect(EffectType::SpeedBoost) {
+            self.speed_cap * SPEED_BOOST_MULTIPLIER
+        } else {
+            self.speed_cap
+        }
+    }
+
+    /// obtains the number of jumps of the player, with the effects accounted for.
+    pub fn get_jumps_count(&self) -> u8 {
+        self.jumps_count + self.has_effect(EffectType::ExtraJump) as u8
+    }
+
+    /// starts a melee shove if `melee_input` is pressed and the melee cooldown is over.
+    /// The arena shoves the players inside the melee hitbox afterwards.
+    pub(crate) fn melee(&mut self, melee_input: bool, curr_time: u128) {
+        let time_since_last_melee = curr_time.saturating_sub(self.last_melee_time);
+        self.meleed = melee_input && time_since_last_melee > MELEE_COOLDOWN;
+        if self.meleed {
+            self.last_melee_time = curr_time;
+        }
+    }
+
+    /// whether the player started a melee shove during the current tick.
+    pub(crate) fn has_meleed(&self) -> bool {
+        self.meleed
+    }
+
+    /// obtains the (top left, bottom right) corners of the melee hitbox, which is in front of
+    /// the player's `direction`.
+    pub fn get_melee_hitbox(&self) -> (Vec2, Vec2) {
+        let position = self.get_position();
+        let x = if self.direction > 0.0 {
+            position.x + self.width
+        } else {
+            position.x - MELEE_RANGE
+        };
+
+        let min = Vec2::new(x, position.y);
+        (min, min + Vec2::new(MELEE_RANGE, self.height))
+    }
+
+    /// whether the weapon of the player is still reloading.
+    pub(crate) fn is_reloading(&self, curr_time: u128) -> bool {
+        self.current_weapon.is_reloading(curr_time)
+    }
+
+    /// finishes the reload of the weapon once its time is up, and starts a new reload on a
+    /// `reload_input`.
+    pub(crate) fn reload(&mut self, reload_input: bool, curr_time: u128) {
+        self.current_weapon.finish_reload(curr_time);
+        if reload_input {
+            self.current_weapon.start_reload(curr_time);
+        }
+    }
+
+    /// whether the player jumped during the current tick.
+    pub(crate) fn has_jumped(&self) -> bool {
+        self.jumped
+    }
+
+    /// brings the player back to the spawn point after being knocked out of the arena.
+    pub(crate) fn respawn(&mut self, curr_time: u128) {
+        self.knocked_out = false;
+        self.position = fixed::real_vec2(get_spawn_position());
+        self.velocity = RealVec2::ZERO;
+        self.acceleration = RealVec2::ZERO;
+        self.last_hit_by = None;
+        self.damage_multiplier = 0.0;
+        self.effects.clear();
+        self.was_standing = false;
+        self.buffered_jump_ticks = 0;
+        self.last_dash_time = None;
+        self.dashing = false;
+        self.aim = Vec2::new(self.direction, 0.0);
+        self.current_weapon = Weapon::new(self.get_position(), self.default_weapontype, self.direction, curr_time);
+    }
+
+    /// whether the player is done with the cooldown of its last teleport.
+    pub(crate) fn can_teleport(&self, curr_time: u128) -> bool {
+        curr_time >= self.teleport_ready_at
+    }
+
+    /// moves the player to `position` through a teleporter, keeping its velocity.
+    pub(crate) fn teleport(&mut self, position: RealVec2, curr_time: u128) {
+        self.move_to(position, 0.0);
+        self.teleport_ready_at = curr_time + TELEPORT_COOLDOWN;
+    }
+
+    pub fn get_class(&self) -> CharacterClass {
+        self.class
+    }
+
+    /// turns the player into a character of the `class`, with the body, running and jumping of
+    /// the class. The player is given the weapon of the class, unless the current weapon was
+    /// already used: it is kept with its ammo, and the class weapon comes with the next respawn.
+    pub fn set_class(&mut self, class: CharacterClass, curr_time: u128) {
+        let i = class as usize;
+        let size = CLASS_SIZES[i];
+        self.class = class;
+        self.mass = CLASS_MASSES[i];
+        self.width = size.x;
+        self.height = size.y;
+        self.speed_cap = CLASS_SPEED_CAPS[i];
+        self.jumps_count = CLASS_JUMPS_COUNTS[i];
+        self.jump_velocity = CLASS_JUMP_VELOCITIES[i];
+        let untouched = self.current_weapon.get_weapon_type() == self.default_weapontype && self.current_weapon.is_unused();
+        self.default_weapontype = CLASS_WEAPONS[i];
+        if untouched {
+            self.equip_weapon(self.default_weapontype, curr_time);
+        }
+    }
+
+    pub fn is_knocked_out(&self) -> bool {
+        self.knocked_out
+    }
+
+    pub(crate) fn knock_out(&mut self) {
+        self.knocked_out = true;
+    }
+
+    pub fn get_team(&self) -> u8 {
+        self.team
+    }
+
+    /// puts the player on a team. Bullets fired afterwards belong to the team.
+    pub fn set_team(&mut self, team: u8) {
+        self.team = team;
+    }
+
+    /// obtains the id of the last player who hit this player, if any.
+    pub fn get_last_hit_by(&self) -> Option<EntityId> {
+        self.last_hit_by
+    }
+
     /// adds the weight of the player.
     pub(crate) fn add_weight_force(&mut self) -> &mut Player {
-        self.add_force(GRAVITY_DEFAULT * self.get_total_mass())
+        self.add_force(self.get_weight())
     }
 
     pub(crate) fn add_normal_force(&mut self, standing_on_block: bool) -> &mut Player {
         self.add_force(self.get_normal(standing_on_block))
     }
 
-    pub(crate) fn get_weight(&self) -> Vec2 {
-        GRAVITY_DEFAULT * self.get_total_mass()
+    pub(crate) fn get_weight(&self) -> RealVec2 {
+        fixed::real_vec2(GRAVITY_DEFAULT) * fixed::real(self.get_total_mass())
     }
 
-    pub(crate) fn get_normal(&self, standing_on_block: bool) -> Vec2 {
-        -self.get_weight() * standing_on_block as u8 as f32
+    pub(crate) fn get_normal(&self, standing_on_block: bool) -> RealVec2 {
+        -self.get_weight() * fixed::real(standing_on_block as u8 as f32)
     }
 
     /// obtains the total mass of the player (player + current weapon).
     pub(crate) fn get_total_mass(&self) -> f32 {
-        self.mass + self.current_weapon.get_mass()
+        let mass = if self.has_effect(EffectType::HeavyBody) {
+            self.mass * HEAVY_BODY_MULTIPLIER
+        } else {
+            self.mass
+        };
+
+        mass + self.current_weapon.get_mass()
     }
 
 
     /// attacks with the current weapon.
-    pub(crate) fn attack(&mut self) -> WeaponStatus {
-        let status_after_attack = self.current_weapon.attack();
+    pub(crate) fn attack(&mut self, curr_time: u128) -> WeaponStatus {
+        let status_after_attack = self.current_weapon.attack(curr_time);
+
+        // the fired bullet is given back while the player has infinite ammo.
+        if status_after_attack == WeaponStatus::FireSuccess && self.has_effect(EffectType::InfiniteAmmo) {
+            self.current_weapon.magazine += 1;
+        }
+
+        // an emptied magazine is reloaded right away, unless the weapon is thrown away instead.
+        if self.current_weapon.get_magazine() == 0 && !self.current_weapon.discards_when_empty() {
+            self.current_weapon.start_reload(curr_time);
+        }
 
         // if weapon is empty, discard on an attack command.
         if status_after_attack == WeaponStatus::Empty {
-            self.throw_current_weapon();
+            self.throw_current_weapon(curr_time);
         }
 
         status_after_attack
     }
 
-    pub(crate) fn create_new_bullet(&self, id: u16) -> Bullet {
-        let position_x = self.position.x + PLAYER_WIDTH / 2.0;
-        let position_y = self.position.y + PLAYER_HEIGHT / 2.0;
-        let position = Vec2::new(position_x, position_y);
-        let velocity = self.current_weapon.get_bullet_speed() * Vec2::X * self.direction;
+    pub(crate) fn create_new_bullet(&self, owner: EntityId) -> Bullet {
+        let position = self.position + fixed::real_vec2(Vec2::new(self.width, self.height) / 2.0);
+        let velocity = fixed::real_vec2(self.current_weapon.get_bullet_speed() * self.aim);
         let bullettype = self.current_weapon.get_bullet_type();
         let team = self.team;
 
-        Bullet::new(position, velocity, bullettype, team, id)
+        Bullet::from_movement(position, velocity, bullettype, team, owner)
     }
 
     /// throws the current weapon away and create a new weapon from the player's default.
-    pub(crate) fn throw_current_weapon(&mut self) {
+    pub(crate) fn throw_current_weapon(&mut self, curr_time: u128) {
+        let mut weapon = Weapon::new(self.get_position(), self.default_weapontype, self.direction, curr_time);
+        weapon.set_aim(self.aim);
+        let mut thrown = std::mem::replace(&mut self.current_weapon, weapon);
+
         // TODO: discard velocity should be different from player's velocity.
-        self.current_weapon.discard(self.velocity);
-        self.current_weapon = Weapon::new(self.position, self.default_weapontype, self.direction);
+        thrown.discard(self.velocity);
+        self.thrown_weapon = Some(thrown);
+    }
+
+    /// takes the weapon thrown away during the last update, if any.
+    pub(crate) fn take_thrown_weapon(&mut self) -> Option<Weapon> {
+        self.thrown_weapon.take()
     }
 
-    pub(crate) fn get_bullet_momentum(&self) -> Vec2 {
+    /// replaces the current weapon with a new weapon of type `weapontype`.
+    pub(crate) fn equip_weapon(&mut self, weapontype: WeaponType, curr_time: u128) {
+        self.current_weapon = Weapon::new(self.get_position(), weapontype, self.direction, curr_time);
+        self.current_weapon.set_aim(self.aim);
+    }
+
+    pub fn get_weapon(&self) -> &Weapon {
+        &self.current_weapon
+    }
+
+    /// whether the player overlaps the rectangle from `min` (top left) to `max` (bottom right).
+    pub fn overlaps(&self, min: Vec2, max: Vec2) -> bool {
+        let position = self.get_position();
+        let bottomright = position + Vec2::new(self.width, self.height);
+        position.cmple(max).all() && min.cmple(bottomright).all()
+    }
+
+    pub(crate) fn get_bullet_momentum(&self) -> RealVec2 {
         self.current_weapon.get_bullet_momentum()
     }
 }
 
+/// players spawn right above the middle of the arena.
+fn get_spawn_position() -> Vec2 {
+    let midmap = (ARENA_WIDTH - PLAYER_WIDTH) / 2.0;
+    Vec2::new(midmap, -PLAYER_HEIGHT)
+}
+
 impl Default for Player {
     fn default() -> Self {
-        let midmap = (ARENA_WIDTH - PLAYER_WIDTH) / 2.0;
-        let default_position = Vec2::new(midmap, -PLAYER_HEIGHT);
+        let default_position = get_spawn_position();
         let default_direction = 1.0;
         let default_weapontype = WeaponType::BasicPistol;
-        let current_weapon = Weapon::new(default_position, default_weapontype, default_direction);
-        let curr_time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Unable to get current time!")
-            .as_millis();
+        let current_weapon = Weapon::new(default_position, default_weapontype, default_direction, 0);
 
         Player {
-            position: default_position,
-            velocity: Vec2::ZERO,
-            acceleration: Vec2::ZERO,
+            position: fixed::real_vec2(default_position),
+            velocity: RealVec2::ZERO,
+            acceleration: RealVec2::ZERO,
             name: String::from("default"),
             speed_cap: PLAYER_SPEED_CAP,
             jumps_left: 0,
             jumps_count: 2,
-            last_jump_time: curr_time,
+            jump_velocity: JUMP_VELOCITY,
+            jump_buffer_ticks: JUMP_BUFFER_TICKS,
+            coyote_ticks: COYOTE_TICKS,
+            buffered_jump_ticks: 0,
+            ticks_in_air: 0,
+            last_jump_time: 0,
+            last_melee_time: 0,
+            jumped: false,
+            meleed: false,
+            dashed: false,
+            last_dash_time: None,
+            dash_ready_at: 0,
+            dashing: false,
+            teleport_ready_at: 0,
             width: PLAYER_WIDTH,
             height: PLAYER_HEIGHT,
             direction: 1.0,
+            aim: Vec2::X,
+            class: CharacterClass::Brawler,
             default_weapontype,
             current_weapon,
             team: 0,
             damage_multiplier: 0.0,
-            lives: 5,
+            knocked_out: false,
+            last_hit_by: None,
+            thrown_weapon: None,
+            effects: Vec::new(),
+            was_standing: false,
             mass: PLAYER_MASS,
         }
     }
@@ -0,0 +1,107 @@
+/// Results and geometry of the queries against the blocks and the players of the arena.
+///
+/// The queries themselves are methods of the `Arena`. The blocks are looked up through the bit
+/// grid of the `Map`, so only the occupied cells spanned by a query are tested, and the players
+/// through the grid of the arena, which is rebuilt on every update.
+use std::ops::RangeInclusive;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::block::BlockType;
+use crate::entity::EntityId;
+use crate::map::{HORIZONTAL_BLOCKS, VERTICAL_BLOCKS};
+use crate::{BLOCK_HEIGHT, BLOCK_WIDTH, ERROR_THRESHOLD, HORIZONTAL_PADDING, VERTICAL_BLOCK_SPACING, VERTICAL_PADDING};
+
+/// What a ray was stopped by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RayTarget {
+    /// the block at `row` and `col`, solid or not.
+    Block { row: usize, col: usize, blocktype: BlockType },
+    Player(EntityId),
+}
+
+/// First hit of a ray cast through the arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub target: RayTarget,
+    /// point where the ray entered the target.
+    pub point: Vec2,
+    /// distance from the origin of the ray to `point`.
+    pub distance: f32,
+}
+
+/// End of a platform, i.e. a top corner of a block without another block next to it on the
+/// same side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlatformEdge {
+    pub row: usize,
+    pub col: usize,
+    /// the top corner of the block at the end of the platform.
+    pub point: Vec2,
+    /// which end of the platform it is (left=-1.0 or right=+1.0).
+    pub side: f32,
+}
+
+/// obtains the top left and bottom right corners of the block at the cell (row, col).
+pub(crate) fn get_block_rect(row: usize, col: usize) -> (Vec2, Vec2) {
+    let min = Vec2::new(Arena::get_block_col_position(col), Arena::get_block_row_position(row));
+    (min, min + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT))
+}
+
+/// obtains the rows and the columns of the grid spanned by the rectangle from `min` (top left)
+/// to `max` (bottom right), or None if the rectangle is outside of the grid.
+pub(crate) fn get_cells_spanned(min: Vec2, max: Vec2) -> Option<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+    let to_col = |x: f32| ((x - HORIZONTAL_PADDING) / BLOCK_WIDTH).floor() as i32;
+    let to_row = |y: f32| ((y - VERTICAL_PADDING) / VERTICAL_BLOCK_SPACING).floor() as i32;
+    let (first_col, last_col) = (to_col(min.x).max(0), to_col(max.x).min(HORIZONTAL_BLOCKS as i32 - 1));
+    let (first_row, last_row) = (to_row(min.y).max(0), to_row(max.y).min(VERTICAL_BLOCKS as i32 - 1));
+    if first_col > last_col || first_row > last_row {
+        return None;
+    }
+
+    Some((first_row as usize..=last_row as usize, first_col as usize..=last_col as usize))
+}
+
+/// obtains the fraction of the way from `start` to `end` at which the segment enters the
+/// rectangle from `min` to `max`, which is 0.0 if the segment starts inside of it.
+pub(crate) fn get_segment_entry(start: Vec2, end: Vec2, min: Vec2, max: Vec2) -> Option<f32> {
+    if min.cmple(start).all() && start.cmple(max).all() {
+        Some(0.0)
+    } else {
+        sweep_segment(start, end, min, max)
+    }
+}
+
+/// obtains the fraction of the way from `start` to `end` at which the segment enters the
+/// rectangle from `min` to `max`. Returns None if the segment misses the rectangle, or starts
+/// inside of it.
+pub(crate) fn sweep_segment(start: Vec2, end: Vec2, min: Vec2, max: Vec2) -> Option<f32> {
+    if min.cmple(start).all() && start.cmple(max).all() {
+        return None;
+    }
+
+    let delta = end - start;
+    let (mut t_enter, mut t_exit) = (0.0_f32, 1.0_f32);
+    for axis in 0..2 {
+        let (start, delta, min, max) = (start.to_array()[axis], delta.to_array()[axis], min.to_array()[axis], max.to_array()[axis]);
+        if delta.abs() < ERROR_THRESHOLD {
+            if start < min || start > max {
+                return None;
+            }
+            continue;
+        }
+
+        let (t0, t1) = ((min - start) / delta, (max - start) / delta);
+        t_enter = t_enter.max(t0.min(t1));
+        t_exit = t_exit.min(t0.max(t1));
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    Some(t_enter)
+}
+
+/// whether the circle overlaps the rectangle from `min` to `max`.
+pub(crate) fn circle_overlaps_rect(center: Vec2, radius: f32, min: Vec2, max: Vec2) -> bool {
+    center.clamp(min, max).distance_squared(center) <= radius * radius
+}
@@ -0,0 +1,402 @@
+/// Game-mode scripts written in Rhai, for prototyping modes and weapons without recompiling.
+///
+/// A script registers its hooks by defining functions with the following names:
+///
+/// - `on_tick(arena)` is called after every update of the arena and of the game mode.
+/// - `on_event(arena, event)` is called before `on_tick` for every event of the update. The
+///   event is a map with its name under `kind` (e.g. "PlayerKilled"), and its fields.
+///
+/// `arena` is a `ScriptArena`, which lists the players and bullets of the arena as maps, gives
+/// the gravity and the spots on top of the blocks where pickups can lie, and queues the actions
+/// of the script: applying impulses to players, spawning pickups and setting scores. The
+/// actions are applied through the public methods of the `Arena` and the `GameMode` once the
+/// hooks return, so a script cannot do anything the rest of the game could not. Inside the
+/// hooks, `this` is a map kept between the calls, for the state of the script.
+///
+/// The engine has no access to files or modules, every call is limited in operations, and the
+/// pickups a script can spawn are limited to `MAX_SCRIPT_PICKUPS` in the arena, so a broken
+/// script cannot stall the server.
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use glam::Vec2;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, FLOAT, INT};
+use crate::arena::Arena;
+use crate::effect::EffectType;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::gamemode::{GameMode, Scorer};
+use crate::fixed;
+use crate::pickup::{self, Pickup, PickupKind};
+use crate::weaponscatalog::WeaponType;
+use crate::{ARENA_HEIGHT, ARENA_WIDTH, GRAVITY_DEFAULT};
+
+/// largest number of operations of a single call of a hook.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// largest number of nested function calls of a script.
+const MAX_CALL_LEVELS: usize = 32;
+
+/// largest number of items in a string, array or map created by a script.
+const MAX_CONTAINER_SIZE: usize = 4096;
+
+/// largest number of pickups in the arena for which a script can still spawn another one.
+pub const MAX_SCRIPT_PICKUPS: usize = 32;
+
+/// Action queued by a script, applied to the arena or the game mode after the hooks.
+#[derive(Debug, Clone)]
+enum ScriptAction {
+    Impulse(EntityId, Vec2),
+    SpawnPickup(Vec2, PickupKind),
+    SetScore(Scorer, u32),
+}
+
+/// What a script sees of the arena during a hook.
+#[derive(Debug, Clone)]
+pub struct ScriptArena {
+    /// simulation time of the arena in ms.
+    time: INT,
+    /// length of the last update in seconds.
+    dt: FLOAT,
+    players: Array,
+    bullets: Array,
+    /// positions of pickups lying on top of the blocks, as maps with `x` and `y`.
+    pickup_spots: Array,
+    /// number of pickups in the arena before the hook.
+    pickups_count: usize,
+    /// shared by the copies of the arena handed to the hooks.
+    actions: Rc<RefCell<Vec<ScriptAction>>>,
+}
+
+impl ScriptArena {
+    fn new(arena: &Arena, dt: f32) -> Self {
+        let players = arena.get_players().iter()
+            .map(|(id, player)| {
+                let mut map = Map::new();
+                map.insert("id".into(), (id.to_bits() as INT).into());
+                map.insert("name".into(), player.name.clone().into());
+                map.insert("class".into(), player.get_class().to_string().into());
+                let weapontype = player.get_weapon().get_weapon_type();
+                map.insert("weapon".into(), format!("{:?}", weapontype).into());
+                map.insert("x".into(), (player.get_position().x as FLOAT).into());
+                map.insert("y".into(), (player.get_position().y as FLOAT).into());
+                map.insert("vx".into(), (player.get_velocity().x as FLOAT).into());
+                map.insert("vy".into(), (player.get_velocity().y as FLOAT).into());
+                map.insert("width".into(), (player.width as FLOAT).into());
+                map.insert("height".into(), (player.height as FLOAT).into());
+                // the mass includes the weapon, so impulses can be scaled to a change in velocity.
+                map.insert("mass".into(), (player.get_total_mass() as FLOAT).into());
+                map.insert("damage".into(), (player.get_damage() as FLOAT).into());
+                map.insert("knocked_out".into(), player.is_knocked_out().into());
+                map.insert("team".into(), (player.get_team() as INT).into());
+                Dynamic::from_map(map)
+            })
+            .collect();
+
+        let bullets = arena.get_bullets().iter()
+            .map(|(id, bullet)| {
+                let (position, velocity) = (bullet.get_position(), bullet.get_velocity());
+                let mut map = Map::new();
+                map.insert("id".into(), (id.to_bits() as INT).into());
+                map.insert("owner".into(), (bullet.get_owner().to_bits() as INT).into());
+                map.insert("kind".into(), format!("{:?}", bullet.get_bullet_type()).into());
+                map.insert("x".into(), (position.x as FLOAT).into());
+                map.insert("y".into(), (position.y as FLOAT).into());
+                map.insert("vx".into(), (velocity.x as FLOAT).into());
+                map.insert("vy".into(), (velocity.y as FLOAT).into());
+                Dynamic::from_map(map)
+            })
+            .collect();
+
+        let pickup_spots = arena.get_blocks_iter()
+            .map(|block| {
+                let position = pickup::get_pickup_spot(&block);
+                let mut map = Map::new();
+                map.insert("x".into(), (position.x as FLOAT).into());
+                map.insert("y".into(), (position.y as FLOAT).into());
+                Dynamic::from_map(map)
+            })
+            .collect();
+
+        Self {
+            time: arena.get_time() as INT,
+            dt: dt as FLOAT,
+            players,
+            bullets,
+            pickup_spots,
+            pickups_count: arena.get_pickups().len(),
+            actions: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn push(&mut self, action: ScriptAction) {
+        self.actions.borrow_mut().push(action);
+    }
+
+    /// queues the spawn of a pickup, unless the arena would hold more than `MAX_SCRIPT_PICKUPS`.
+    fn spawn_pickup(&mut self,
+                    x: FLOAT,
+                    y: FLOAT,
+                    kind: PickupKind) -> Result<(), Box<EvalAltResult>> {
+
+        let queued = self.actions.borrow().iter()
+            .filter(|action| matches!(action, ScriptAction::SpawnPickup(..)))
+            .count();
+
+        if self.pickups_count + queued >= MAX_SCRIPT_PICKUPS {
+            let message = format!("Too many pickups, at most {} in the arena", MAX_SCRIPT_PICKUPS);
+            return Err(message.into());
+        }
+        self.push(ScriptAction::SpawnPickup(Vec2::new(x as f32, y as f32), kind));
+        Ok(())
+    }
+
+    /// pushes the player with an impulse (an instant change in momentum).
+    fn apply_impulse(&mut self, id: INT, x: FLOAT, y: FLOAT) -> Result<(), Box<EvalAltResult>> {
+        let id = to_entity_id(id)?;
+        self.push(ScriptAction::Impulse(id, Vec2::new(x as f32, y as f32)));
+        Ok(())
+    }
+
+    /// spawns a pickup of the weapon named `name` with its top left corner at (x, y).
+    fn spawn_weapon(&mut self, x: FLOAT, y: FLOAT, name: &str) -> Result<(), Box<EvalAltResult>> {
+        let weapontype = (0..).map_while(WeaponType::from_repr)
+            .find(|weapontype| format!("{:?}", weapontype) == name)
+            .ok_or_else(|| format!("Unknown weapon {}", name))?;
+        self.spawn_pickup(x, y, PickupKind::Weapon(weapontype))
+    }
+
+    /// spawns a pickup of the effect named `name` with its top left corner at (x, y).
+    fn spawn_effect(&mut self, x: FLOAT, y: FLOAT, name: &str) -> Result<(), Box<EvalAltResult>> {
+        let effecttype = (0..).map_while(EffectType::from_repr)
+            .find(|effecttype| effecttype.to_string() == name)
+            .ok_or_else(|| format!("Unknown effect {}", name))?;
+        self.spawn_pickup(x, y, PickupKind::Effect(effecttype))
+    }
+
+    fn set_score(&mut self, id: INT, score: INT) -> Result<(), Box<EvalAltResult>> {
+        let id = to_entity_id(id)?;
+        let score = u32::try_from(score).map_err(|_| format!("Invalid score {}", score))?;
+        self.push(ScriptAction::SetScore(Scorer::Player(id), score));
+        Ok(())
+    }
+
+    fn set_team_score(&mut self, team: INT, score: INT) -> Result<(), Box<EvalAltResult>> {
+        let team = u8::try_from(team).map_err(|_| format!("Invalid team {}", team))?;
+        let score = u32::try_from(score).map_err(|_| format!("Invalid score {}", score))?;
+        self.push(ScriptAction::SetScore(Scorer::Team(team), score));
+        Ok(())
+    }
+}
+
+/// A compiled game-mode script along with its state.
+#[derive(Debug)]
+pub struct ModeScript {
+    engine: Engine,
+    ast: AST,
+    /// the `this` of the hooks.
+    state: Dynamic,
+    has_on_tick: bool,
+    has_on_event: bool,
+}
+
+impl ModeScript {
+    /// compiles the script and runs its top level statements.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let engine = ModeScript::create_engine();
+        let ast = engine.compile(source).map_err(|e| e.to_string())?;
+        engine.run_ast(&ast).map_err(|e| e.to_string())?;
+
+        let has_hook = |name: &str| ast.iter_functions().any(|f| f.name == name);
+        let (has_on_tick, has_on_event) = (has_hook("on_tick"), has_hook("on_event"));
+        Ok(Self { engine, ast, state: Dynamic::from_map(Map::new()), has_on_tick, has_on_event })
+    }
+
+    /// loads the script from a file, e.g. one of the scripts in `resources/scripts`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        ModeScript::new(&source)
+    }
+
+    /// calls the hooks of the script after the `arena` and the `mode` were updated, with
+    /// `events` being the events of the update and `dt` its length in seconds. The actions of
+    /// the script are applied even if a hook fails.
+    pub fn update(&mut self,
+                  arena: &mut Arena,
+                  mode: &mut GameMode,
+                  events: &[GameEvent],
+                  dt: f32) -> Result<(), String> {
+
+        let script_arena = ScriptArena::new(arena, dt);
+        let result = self.call_hooks(&script_arena, events);
+
+        for action in script_arena.actions.take() {
+            match action {
+                ScriptAction::Impulse(id, impulse) => {
+                    if let Some(player) = arena.get_mut_player(id) {
+                        player.add_impulse(fixed::real_vec2(impulse));
+                    }
+                },
+
+                ScriptAction::SpawnPickup(position, kind) => {
+                    arena.add_pickup(Pickup::new(position, kind));
+                },
+
+                ScriptAction::SetScore(scorer, score) => mode.set_score(scorer, score),
+            }
+        }
+
+        result
+    }
+
+    fn call_hooks(&mut self, arena: &ScriptArena, events: &[GameEvent]) -> Result<(), String> {
+        if self.has_on_event {
+            for event in events {
+                let args = (arena.clone(), Dynamic::from_map(event_to_map(event)));
+                self.call_hook("on_event", args)?;
+            }
+        }
+
+        if self.has_on_tick {
+            self.call_hook("on_tick", (arena.clone(),))?;
+        }
+        Ok(())
+    }
+
+    fn call_hook(&mut self, name: &str, args: impl rhai::FuncArgs) -> Result<(), String> {
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, name, args)
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", name, e))
+    }
+
+    /// creates an engine without access to files or modules, with the limits of the scripts,
+    /// and with the API of the `ScriptArena`.
+    fn create_engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.disable_symbol("eval");
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        engine.set_max_string_size(MAX_CONTAINER_SIZE);
+        engine.set_max_array_size(MAX_CONTAINER_SIZE);
+        engine.set_max_map_size(MAX_CONTAINER_SIZE);
+
+        engine.register_type_with_name::<ScriptArena>("Arena")
+            .register_get("time", |arena: &mut ScriptArena| arena.time)
+            .register_get("dt", |arena: &mut ScriptArena| arena.dt)
+            .register_get("width", |_: &mut ScriptArena| ARENA_WIDTH as FLOAT)
+            .register_get("height", |_: &mut ScriptArena| ARENA_HEIGHT as FLOAT)
+            .register_get("gravity", |_: &mut ScriptArena| GRAVITY_DEFAULT.y as FLOAT)
+            .register_get("players", |arena: &mut ScriptArena| arena.players.clone())
+            .register_get("bullets", |arena: &mut ScriptArena| arena.bullets.clone())
+            .register_get("pickup_spots", |arena: &mut ScriptArena| arena.pickup_spots.clone())
+            .register_get("pickups_count", |arena: &mut ScriptArena| arena.pickups_count as INT)
+            .register_get("max_pickups", |_: &mut ScriptArena| MAX_SCRIPT_PICKUPS as INT)
+            .register_fn("apply_impulse", ScriptArena::apply_impulse)
+            .register_fn("spawn_weapon", ScriptArena::spawn_weapon)
+            .register_fn("spawn_effect", ScriptArena::spawn_effect)
+            .register_fn("set_score", ScriptArena::set_score)
+            .register_fn("set_team_score", ScriptArena::set_team_score);
+        engine
+    }
+}
+
+/// converts an id given by a script back to an `EntityId`.
+fn to_entity_id(id: INT) -> Result<EntityId, Box<EvalAltResult>> {
+    let bits = u32::try_from(id).map_err(|_| format!("Invalid id {}", id))?;
+    Ok(EntityId::from_bits(bits))
+}
+
+/// converts an event to a map with its name under `kind`, and its fields. Ids are given as
+/// integers, vectors as arrays of two floats, and types as their names.
+fn event_to_map(event: &GameEvent) -> Map {
+    let id = |id: &EntityId| Dynamic::from(id.to_bits() as INT);
+    let float = |value: &f32| Dynamic::from(*value as FLOAT);
+    let vector = |vector: &Vec2| Dynamic::from_array(vec![float(&vector.x), float(&vector.y)]);
+    let name = |value: &dyn std::fmt::Debug| Dynamic::from(format!("{:?}", value));
+    let cell = |(row, col): &(usize, usize)| {
+        Dynamic::from_array(vec![(*row as INT).into(), (*col as INT).into()])
+    };
+
+    let (kind, fields): (&str, Vec<(&str, Dynamic)>) = match event {
+        GameEvent::BulletFired { player, bullet, bullettype } => ("BulletFired", vec![
+            ("player", id(player)),
+            ("bullet", id(bullet)),
+            ("bullettype", name(bullettype)),
+        ]),
+        GameEvent::Jumped { player, midair } => {
+            ("Jumped", vec![("player", id(player)), ("midair", (*midair).into())])
+        },
+        GameEvent::Dashed { player, midair } => {
+            ("Dashed", vec![("player", id(player)), ("midair", (*midair).into())])
+        },
+        GameEvent::Landed { player } => ("Landed", vec![("player", id(player))]),
+        GameEvent::WeaponEmptied { player, weapontype } => {
+            ("WeaponEmptied", vec![("player", id(player)), ("weapontype", name(weapontype))])
+        },
+        GameEvent::PlayerHit { attacker, victim, bullettype, damage, momentum } => {
+            ("PlayerHit", vec![
+                ("attacker", id(attacker)),
+                ("victim", id(victim)),
+                ("bullettype", name(bullettype)),
+                ("damage", float(damage)),
+                ("momentum", vector(momentum)),
+            ])
+        },
+        GameEvent::PlayerShoved { attacker, victim, damage, momentum } => ("PlayerShoved", vec![
+            ("attacker", id(attacker)),
+            ("victim", id(victim)),
+            ("damage", float(damage)),
+            ("momentum", vector(momentum)),
+        ]),
+        GameEvent::PlayerKilled { victim, killer } => ("PlayerKilled", vec![
+            ("victim", id(victim)),
+            ("killer", killer.as_ref().map_or(Dynamic::UNIT, id)),
+        ]),
+        GameEvent::WeaponPickedUp { player, pickup, weapontype } => ("WeaponPickedUp", vec![
+            ("player", id(player)),
+            ("pickup", id(pickup)),
+            ("weapontype", name(weapontype)),
+        ]),
+        GameEvent::EffectPickedUp { player, pickup, effecttype } => ("EffectPickedUp", vec![
+            ("player", id(player)),
+            ("pickup", id(pickup)),
+            ("effecttype", name(effecttype)),
+        ]),
+        GameEvent::EffectExpired { player, effecttype } => {
+            ("EffectExpired", vec![("player", id(player)), ("effecttype", name(effecttype))])
+        },
+        GameEvent::PlayerStomped { attacker, victim } => {
+            ("PlayerStomped", vec![("attacker", id(attacker)), ("victim", id(victim))])
+        },
+        GameEvent::HazardTouched { player, hazardtype, damage } => ("HazardTouched", vec![
+            ("player", id(player)),
+            ("hazardtype", name(hazardtype)),
+            ("damage", float(damage)),
+        ]),
+        GameEvent::BlockChanged { row, col, blocktype } => ("BlockChanged", vec![
+            ("row", (*row as INT).into()),
+            ("col", (*col as INT).into()),
+            ("blocktype", blocktype.as_ref().map_or(Dynamic::UNIT, |blocktype| name(blocktype))),
+        ]),
+        GameEvent::PlayerTeleported { player, from, to } => ("PlayerTeleported", vec![
+            ("player", id(player)),
+            ("from", cell(from)),
+            ("to", cell(to)),
+        ]),
+        GameEvent::ShieldBroken { attacker, victim } => {
+            ("ShieldBroken", vec![("attacker", id(attacker)), ("victim", id(victim))])
+        },
+    };
+
+    let mut map = Map::new();
+    map.insert("kind".into(), kind.into());
+    for (field, value) in fields {
+        map.insert(field.into(), value);
+    }
+    map
+}
@@ -0,0 +1,157 @@
+/// Statistics of every player over a whole match.
+///
+/// The statistics are recorded at the end of every `Arena::update` from the events of the
+/// update, plus the distance traveled by the players. Players who leave the arena keep their
+/// statistics until the end of the match, when they are exported as a JSON document.
+use std::collections::BTreeMap;
+use glam::Vec2;
+use serde::{Serialize, Deserialize};
+use crate::entity::{EntityId, EntityStore};
+use crate::event::GameEvent;
+use crate::player::Player;
+
+/// Statistics of a single player.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    /// id of the player, as packed by `EntityId::to_bits`.
+    pub id: u32,
+    pub name: String,
+    pub shots_fired: u32,
+    /// bullets which hit another player. Bullets absorbed by a shield do not count.
+    pub hits: u32,
+    /// fraction of the shots fired which hit another player.
+    pub accuracy: f32,
+    /// other players knocked out of the arena by this player.
+    pub knockouts: u32,
+    /// times this player was knocked out of the arena, including self-destructs.
+    pub deaths: u32,
+    /// times this player fell out of the arena without being hit by anyone.
+    pub self_destructs: u32,
+    /// damage (in percent) added to other players by bullets and shoves.
+    pub damage_dealt: f32,
+    /// damage (in percent) taken from bullets and shoves.
+    pub damage_taken: f32,
+    /// distance traveled in pixels, not counting respawns.
+    pub distance_traveled: f32,
+}
+
+/// JSON document of the statistics.
+#[derive(Debug, Serialize, Deserialize)]
+struct StatsDocument {
+    /// length of the match in ms.
+    duration: u128,
+    players: Vec<PlayerStats>,
+}
+
+/// Statistics of all the players who played in the match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchStats {
+    players: BTreeMap<EntityId, PlayerStats>,
+    /// position of each player at the end of the previous update.
+    last_positions: BTreeMap<EntityId, Vec2>,
+    /// simulation time in ms when the match started.
+    start_time: u128,
+    /// simulation time in ms of the last update.
+    last_time: u128,
+}
+
+impl MatchStats {
+    /// starts recording a match at `start_time` ms of simulation time.
+    pub fn new(start_time: u128) -> Self {
+        Self { start_time, last_time: start_time, ..Default::default() }
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&PlayerStats> {
+        self.players.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &PlayerStats)> + '_ {
+        self.players.iter().map(|(id, stats)| (*id, stats))
+    }
+
+    /// obtains the length of the match in ms.
+    pub fn get_duration(&self) -> u128 {
+        self.last_time - self.start_time
+    }
+
+    /// records the `events` of an update which ended at `curr_time`.
+    pub(crate) fn record(&mut self, events: &[GameEvent], players: &EntityStore<Player>, curr_time: u128) {
+        self.last_time = curr_time;
+        for (id, player) in players.iter() {
+            self.entry(id).name.clone_from(&player.name);
+        }
+
+        for event in events {
+            match *event {
+                GameEvent::BulletFired { player, .. } => self.entry(player).shots_fired += 1,
+
+                GameEvent::PlayerHit { attacker, victim, damage, .. } => {
+                    let stats = self.entry(attacker);
+                    stats.hits += 1;
+                    stats.damage_dealt += damage;
+                    self.entry(victim).damage_taken += damage;
+                },
+
+                GameEvent::PlayerShoved { attacker, victim, damage, .. } => {
+                    self.entry(attacker).damage_dealt += damage;
+                    self.entry(victim).damage_taken += damage;
+                },
+
+                GameEvent::HazardTouched { player, damage, .. } => self.entry(player).damage_taken += damage,
+
+                GameEvent::PlayerKilled { victim, killer } => {
+                    match killer {
+                        Some(killer) => self.entry(killer).knockouts += 1,
+                        None => self.entry(victim).self_destructs += 1,
+                    }
+                    self.entry(victim).deaths += 1;
+                },
+
+                _ => {},
+            }
+        }
+
+        for (id, player) in players.iter() {
+            let stats = self.entry(id);
+            if stats.shots_fired > 0 {
+                stats.accuracy = (stats.hits as f32 / stats.shots_fired as f32).min(1.0);
+            }
+
+            // knocked out players are respawned later by the game mode, so the distance starts
+            // over from wherever they come back.
+            if player.is_knocked_out() {
+                self.last_positions.remove(&id);
+                continue;
+            }
+
+            if let Some(last_position) = self.last_positions.insert(id, player.get_position()) {
+                self.entry(id).distance_traveled += player.get_position().distance(last_position);
+            }
+        }
+    }
+
+    /// exports the statistics as a JSON document.
+    pub fn to_json(&self) -> String {
+        let document = StatsDocument {
+            duration: self.get_duration(),
+            players: self.players.values().cloned().collect(),
+        };
+
+        serde_json::to_string_pretty(&document).expect("Statistics should serialize")
+    }
+
+    /// reads the statistics from a JSON document created by `to_json`.
+    pub fn from_json(json: &str) -> Result<MatchStats, String> {
+        let document: StatsDocument = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let players = document.players
+            .into_iter()
+            .map(|stats| (EntityId::from_bits(stats.id), stats))
+            .collect();
+
+        Ok(MatchStats { players, last_positions: BTreeMap::new(), start_time: 0, last_time: document.duration })
+    }
+
+    fn entry(&mut self, id: EntityId) -> &mut PlayerStats {
+        self.players.entry(id).or_insert_with(|| PlayerStats { id: id.to_bits(), ..Default::default() })
+    }
+}
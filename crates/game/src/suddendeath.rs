@@ -0,0 +1,138 @@
+/// Sudden death, which starts when the time limit of a match runs out with several players
+/// still alive.
+///
+/// The outermost columns and the bottom rows of the map collapse one at a time, following the
+/// `CollapseSchedule` of the map, until only its center is left. The blocks about to collapse
+/// are announced a few seconds before they fall, so the players can get away from them.
+use serde::{Serialize, Deserialize};
+use crate::arena::Arena;
+use crate::map::{HORIZONTAL_BLOCKS, VERTICAL_BLOCKS};
+
+/// Error message for an invalid schedule.
+const INVALID_SCHEDULE: &str = "Invalid collapse schedule";
+
+/// How the map collapses during sudden death.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollapseSchedule {
+    /// time (in ms) before the first collapse, and between two collapses.
+    interval: u128,
+    /// time (in ms) the blocks about to collapse are announced before collapsing.
+    warning: u128,
+    /// number of columns left at the end.
+    center_cols: usize,
+    /// number of rows left at the end, counting from the top of the map.
+    center_rows: usize,
+}
+
+impl Default for CollapseSchedule {
+    fn default() -> Self {
+        Self { interval: 10000, warning: 3000, center_cols: 4, center_rows: 3 }
+    }
+}
+
+impl CollapseSchedule {
+    /// creates a schedule. Fails if the center is empty or larger than the map, or if the
+    /// warning is longer than the interval.
+    pub fn new(interval: u128, warning: u128, center_cols: usize, center_rows: usize) -> Result<Self, &'static str> {
+        let valid_center = (1..=HORIZONTAL_BLOCKS).contains(&center_cols) && (1..=VERTICAL_BLOCKS).contains(&center_rows);
+        if !valid_center || warning > interval {
+            return Err(INVALID_SCHEDULE);
+        }
+
+        Ok(Self { interval, warning, center_cols, center_rows })
+    }
+
+    pub fn get_interval(&self) -> u128 {
+        self.interval
+    }
+
+    pub fn get_warning(&self) -> u128 {
+        self.warning
+    }
+
+    pub fn get_center_cols(&self) -> usize {
+        self.center_cols
+    }
+
+    pub fn get_center_rows(&self) -> usize {
+        self.center_rows
+    }
+}
+
+/// Progress of the collapse of the map.
+#[derive(Debug, Clone)]
+pub(crate) struct SuddenDeath {
+    schedule: CollapseSchedule,
+    /// the columns from `left` to `right` and the rows down to `bottom` are still standing.
+    left: usize,
+    right: usize,
+    bottom: usize,
+    next_collapse: u128,
+    /// cells (row, col) of the blocks announced to collapse.
+    warned: Vec<(usize, usize)>,
+}
+
+impl SuddenDeath {
+    pub(crate) fn new(schedule: CollapseSchedule, curr_time: u128) -> Self {
+        Self {
+            schedule,
+            left: 0,
+            right: HORIZONTAL_BLOCKS - 1,
+            bottom: VERTICAL_BLOCKS - 1,
+            next_collapse: curr_time + schedule.interval,
+            warned: Vec::new(),
+        }
+    }
+
+    pub(crate) fn get_warned_cells(&self) -> &[(usize, usize)] {
+        &self.warned
+    }
+
+    /// obtains the columns and rows left after the next collapse: each side loses a column and
+    /// the bottom loses a row, until they reach the center.
+    fn get_next_bounds(&self) -> (usize, usize, usize) {
+        let (mut left, mut right, mut bottom) = (self.left, self.right, self.bottom);
+        let cols = right + 1 - left;
+        if cols > self.schedule.center_cols {
+            left += 1;
+        }
+        if cols > self.schedule.center_cols + 1 {
+            right -= 1;
+        }
+        if bottom + 1 > self.schedule.center_rows {
+            bottom -= 1;
+        }
+
+        (left, right, bottom)
+    }
+
+    /// whether the map collapsed down to its center.
+    pub(crate) fn is_done(&self) -> bool {
+        self.get_next_bounds() == (self.left, self.right, self.bottom)
+    }
+
+    /// announces the next collapse once its warning is due, and collapses the blocks when
+    /// its time comes.
+    pub(crate) fn update(&mut self, arena: &mut Arena, curr_time: u128) {
+        let (left, right, bottom) = self.get_next_bounds();
+        let mut cells = Vec::new();
+        for col in self.left..=self.right {
+            for row in 0..=self.bottom {
+                if col < left || col > right || row > bottom {
+                    cells.push((row, col));
+                }
+            }
+        }
+
+        if cells.is_empty() {
+            self.warned.clear();
+        } else if curr_time >= self.next_collapse {
+            arena.collapse_blocks(&cells);
+            (self.left, self.right, self.bottom) = (left, right, bottom);
+            self.next_collapse += self.schedule.interval;
+            self.warned.clear();
+        } else if curr_time + self.schedule.warning >= self.next_collapse {
+            self.warned = cells.into_iter().filter(|(row, col)| arena.get_block(*row, *col).is_some()).collect();
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/// Teleporters which are part of the map.
+///
+/// Teleporters come in pairs standing on top of two cells of the map grid. A player or bullet
+/// entering one of them comes out of its partner with the same velocity, and cannot teleport
+/// again for `TELEPORT_COOLDOWN` ms, so it does not bounce back and forth between the two.
+///
+/// Entering a teleporter is found by sweeping the movement of the object during the update
+/// against the teleporter (see `query::sweep_segment`), so fast objects cannot skip past it.
+use glam::Vec2;
+use serde::{Serialize, Deserialize};
+use crate::arena::Arena;
+use crate::BLOCK_WIDTH;
+
+/// width of a teleporter.
+pub const TELEPORTER_WIDTH: f32 = 48.0;
+
+/// height of a teleporter standing on top of a cell.
+pub const TELEPORTER_HEIGHT: f32 = 64.0;
+
+/// time (in ms) after teleporting before the same player or bullet can teleport again.
+pub const TELEPORT_COOLDOWN: u128 = 500;
+
+/// Two linked teleporters standing on top of the cells `first` and `second`, as (row, col).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeleporterPair {
+    first: (usize, usize),
+    second: (usize, usize),
+}
+
+impl TeleporterPair {
+    pub fn new(first: (usize, usize), second: (usize, usize)) -> Self {
+        Self { first, second }
+    }
+
+    pub fn get_first(&self) -> (usize, usize) {
+        self.first
+    }
+
+    pub fn get_second(&self) -> (usize, usize) {
+        self.second
+    }
+
+    /// obtains the cell of the teleporter linked to the one on `cell`, if it belongs to the pair.
+    pub fn get_partner(&self, cell: (usize, usize)) -> Option<(usize, usize)> {
+        if cell == self.first {
+            Some(self.second)
+        } else if cell == self.second {
+            Some(self.first)
+        } else {
+            None
+        }
+    }
+}
+
+/// obtains the top left and bottom right corners of the teleporter standing on top of the cell
+/// (row, col), centered on the cell.
+pub fn get_teleporter_rect(cell: (usize, usize)) -> (Vec2, Vec2) {
+    let (row, col) = cell;
+    let bottom = Arena::get_block_row_position(row);
+    let left = Arena::get_block_col_position(col) + (BLOCK_WIDTH - TELEPORTER_WIDTH) / 2.0;
+    (Vec2::new(left, bottom - TELEPORTER_HEIGHT), Vec2::new(left + TELEPORTER_WIDTH, bottom))
+}
@@ -1,4 +1,23 @@
 mod test_map;
 mod test_blocks;
 mod test_input;
+mod test_jump;
 mod test_arena;
+mod test_bot;
+mod test_broadphase;
+mod test_entity;
+mod test_effect;
+mod test_gamemode;
+mod test_stats;
+mod test_weapon;
+mod test_environment;
+mod test_impulse;
+mod test_bodies;
+mod test_hazard;
+mod test_suddendeath;
+mod test_teleporter;
+mod test_characters;
+mod test_dash;
+mod test_query;
+mod test_fixed;
+mod test_script;
@@ -1,5 +1,15 @@
+use std::collections::HashMap;
 use crate::arena::Arena;
+use crate::entity::EntityId;
 use glam::Vec2;
+use crate::event::GameEvent;
+use crate::fixed;
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::weapon::Bullet;
+use crate::pickup::{Pickup, PickupKind};
+use crate::weaponscatalog::{BulletType, WeaponType};
+use crate::{KNOCKBACK_SCALING, MELEE_DAMAGE, PLAYER_HEIGHT, PLAYER_WIDTH};
 
 #[test]
 fn test_approximate_position() {
@@ -17,3 +27,220 @@ fn test_approximate_position() {
         assert!(diff.length() < 1.0);
     }
 }
+
+#[test]
+fn test_bullet_hits_player() {
+    let mut arena = Arena::default();
+    let attacker = arena.add_player(Player::new("attacker"));
+    let victim = arena.add_player(Player::new("target"));
+    let player = arena.get_mut_player(victim).unwrap();
+    player.update_position(Vec2::new(500.0, 500.0), 0.0);
+    let target = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+
+    // bullet fired by the attacker, just before reaching the target.
+    let velocity = Vec2::new(600.0, 0.0);
+    let bullet = Bullet::new(target - Vec2::new(5.0, 0.0), velocity, BulletType::Pistol, 0, attacker);
+    arena.add_bullet(bullet);
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    let hit = events.iter().any(|event| {
+        matches!(event, GameEvent::PlayerHit { attacker: a, victim: v, .. } if *a == attacker && *v == victim)
+    });
+
+    assert!(hit);
+    assert!(arena.get_bullets().is_empty());
+    assert_eq!(arena.get_player(victim).unwrap().get_last_hit_by(), Some(attacker));
+}
+
+#[test]
+fn test_fast_bullet_cannot_skip_player() {
+    let mut arena = Arena::default();
+    let attacker = arena.add_player(Player::new("attacker"));
+    let victim = arena.add_player(Player::new("target"));
+    let player = arena.get_mut_player(victim).unwrap();
+    player.update_position(Vec2::new(500.0, 500.0), 0.0);
+    let start = player.get_position() + Vec2::new(-2.0, player.height / 2.0);
+
+    // a sniper bullet moves farther than the width of the player in one tick, from right
+    // before the player to right after it.
+    let velocity = Vec2::new(2200.0, 0.0);
+    assert!(velocity.x / 60.0 > PLAYER_WIDTH + 2.0);
+    arena.add_bullet(Bullet::new(start, velocity, BulletType::Sniper, 0, attacker));
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+
+    assert!(events.iter().any(|event| matches!(event, GameEvent::PlayerHit { victim: v, .. } if *v == victim)));
+    assert!(arena.get_bullets().is_empty());
+}
+
+#[test]
+fn test_knocked_out_player_respawns() {
+    let mut arena = Arena::default();
+    let killer = arena.add_player(Player::new("killer"));
+    let victim = arena.add_player(Player::new("target"));
+    let player = arena.get_mut_player(victim).unwrap();
+    player.add_knockback(fixed::real_vec2(Vec2::ZERO), 10.0, killer);
+    player.update_position(Vec2::new(-100.0, 500.0), 0.0);
+
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    assert!(events.contains(&GameEvent::PlayerKilled { victim, killer: Some(killer) }));
+    assert!(arena.get_player(victim).unwrap().is_knocked_out());
+
+    // the knockout is only reported once.
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerKilled { .. })));
+
+    arena.respawn_player(victim);
+    let player = arena.get_player(victim).unwrap();
+    assert!(!player.is_knocked_out());
+    assert!(!Arena::is_out_of_bounds(player.get_position()));
+    assert_eq!(player.get_last_hit_by(), None);
+    assert_eq!(player.get_damage(), 0.0);
+}
+
+#[test]
+fn test_damage_scales_knockback() {
+    let mut player = Player::new("target");
+    let attacker = EntityId::new(0, 0);
+    let momentum = Vec2::new(1000.0, 0.0);
+
+    let first = player.add_knockback(fixed::real_vec2(momentum), 50.0, attacker).unwrap();
+    let second = player.add_knockback(fixed::real_vec2(momentum), 50.0, attacker).unwrap();
+
+    assert_eq!(player.get_damage(), 100.0);
+    assert!(second.x > first.x);
+    assert_eq!(second, momentum * (1.0 + 100.0 * KNOCKBACK_SCALING));
+}
+
+#[test]
+fn test_jump_and_land_events() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("jumper"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(500.0, Arena::get_block_row_position(3) - player.height), 0.0);
+
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    assert!(events.contains(&GameEvent::Landed { player: id }));
+
+    // waits for the jump cooldown.
+    for _ in 0..30 {
+        arena.update(1.0 / 60.0, &HashMap::new());
+    }
+
+    let mut jump = InputMask::new();
+    jump.add_mask(Input::Up);
+    let mut inputs = HashMap::new();
+    inputs.insert(id, jump);
+
+    let events = arena.update(1.0 / 60.0, &inputs);
+    assert!(events.contains(&GameEvent::Jumped { player: id, midair: false }));
+    assert!(!events.contains(&GameEvent::Landed { player: id }));
+}
+
+#[test]
+fn test_removed_player_id_is_stale() {
+    let mut arena = Arena::default();
+    let old = arena.add_player(Player::new("old"));
+    arena.remove_player(old);
+    let new = arena.add_player(Player::new("new"));
+
+    assert_eq!(old.get_index(), new.get_index());
+    assert!(arena.get_player(old).is_none());
+    assert_eq!(arena.get_player(new).unwrap().name, "new");
+    assert_eq!(EntityId::from_bits(new.to_bits()), new);
+}
+
+#[test]
+fn test_pickup_equips_weapon() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("picker"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(500.0, Arena::get_block_row_position(3) - player.height), 0.0);
+    let position = player.get_position();
+    let pickup = arena.add_pickup(Pickup::new(position, PickupKind::Weapon(WeaponType::BasicPistol)));
+
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    let weapontype = WeaponType::BasicPistol;
+    assert!(events.contains(&GameEvent::WeaponPickedUp { player: id, pickup, weapontype }));
+    assert!(arena.get_pickups().is_empty());
+    assert_eq!(arena.get_player(id).unwrap().get_weapon().get_weapon_type(), weapontype);
+}
+
+#[test]
+fn test_melee_shoves_player_in_front() {
+    let mut arena = Arena::default();
+    let attacker = arena.add_player(Player::new("attacker"));
+    let victim = arena.add_player(Player::new("victim"));
+    let y = Arena::get_block_row_position(3) - PLAYER_HEIGHT;
+    arena.get_mut_player(attacker).unwrap().update_position(Vec2::new(500.0, y), 0.0);
+    arena.get_mut_player(victim).unwrap().update_position(Vec2::new(500.0 + PLAYER_WIDTH + 10.0, y), 0.0);
+
+    // waits for the melee cooldown.
+    for _ in 0..30 {
+        arena.update(1.0 / 60.0, &HashMap::new());
+    }
+
+    let mut melee = InputMask::new();
+    melee.add_mask(Input::Melee);
+    let mut inputs = HashMap::new();
+    inputs.insert(attacker, melee);
+
+    let events = arena.update(1.0 / 60.0, &inputs);
+    let shoved = events.iter().any(|event| {
+        matches!(event, GameEvent::PlayerShoved { attacker: a, victim: v, .. } if *a == attacker && *v == victim)
+    });
+
+    assert!(shoved);
+    assert!(arena.get_player(victim).unwrap().get_velocity().x > 0.0);
+    assert_eq!(arena.get_player(victim).unwrap().get_damage(), MELEE_DAMAGE);
+
+    // the shove is on cooldown right after.
+    let events = arena.update(1.0 / 60.0, &inputs);
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerShoved { .. })));
+}
+
+#[test]
+fn test_eight_way_aim() {
+    let mut player = Player::new("aimer");
+    player.direction = -1.0;
+
+    player.set_aim_input(0.0, 0.0);
+    assert_eq!(player.get_aim(), Vec2::new(-1.0, 0.0));
+
+    player.set_aim_input(0.0, -1.0);
+    assert_eq!(player.get_aim(), Vec2::new(0.0, -1.0));
+
+    player.set_aim_input(1.0, 1.0);
+    assert_eq!(player.get_aim(), Vec2::new(1.0, 1.0).normalize());
+}
+
+#[test]
+fn test_shoot_upwards() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("shooter"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(500.0, Arena::get_block_row_position(3) - player.height), 0.0);
+
+    // waits for the weapon to reload.
+    for _ in 0..70 {
+        arena.update(1.0 / 60.0, &HashMap::new());
+    }
+
+    let mut shoot_up = InputMask::new();
+    shoot_up.add_mask(Input::Shoot);
+    shoot_up.add_mask(Input::Up);
+    let mut inputs = HashMap::new();
+    inputs.insert(id, shoot_up);
+
+    let events = arena.update(1.0 / 60.0, &inputs);
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::Jumped { .. })));
+
+    let bullet = events.iter()
+        .find_map(|event| match event {
+            GameEvent::BulletFired { bullet, .. } => Some(*bullet),
+            _ => None,
+        })
+        .expect("Bullet should be fired");
+
+    let velocity = arena.get_bullets().get(bullet).unwrap().get_velocity();
+    assert_eq!(velocity.x, 0.0);
+    assert!(velocity.y < 0.0);
+}
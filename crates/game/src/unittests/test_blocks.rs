@@ -1,7 +1,207 @@
-use crate::block::{get_block_friction, BlockType};
+use std::collections::HashMap;
+use glam::Vec2;
+use strum::EnumCount;
+use crate::arena::Arena;
+use crate::block::{get_block_friction, get_block_health, get_block_regrow_time, is_block_solid, BlockType};
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::input::{Input, InputMask};
+use crate::map::{Map, VERTICAL_BLOCKS};
+use crate::player::Player;
+use crate::weapon::Bullet;
+use crate::weaponscatalog::BulletType;
+use crate::{BLOCK_HEIGHT, BLOCK_WIDTH};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// row 3 is filled with grass from columns 4 to 11, and `ceiling` is placed on row 2 of
+/// column 6 and in front of the wall at row 1 of column 8.
+fn create_test_arena(ceiling: BlockType) -> Arena {
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    for col in 4..=11 {
+        data[BlockType::GrassBlock as usize] |= 1 << (col * VERTICAL_BLOCKS + 3);
+    }
+    data[ceiling as usize] |= 1 << (6 * VERTICAL_BLOCKS + 2);
+    data[ceiling as usize] |= 1 << (8 * VERTICAL_BLOCKS + 1);
+    Arena::new(Map::new(data.into()).unwrap())
+}
+
+/// places a player standing on row 3 in the middle of column `col`.
+fn add_standing_player(arena: &mut Arena, col: usize) -> EntityId {
+    let id = arena.add_player(Player::new("standing"));
+    let position = arena.get_block_position_at(3, col);
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(position + Vec2::new((BLOCK_WIDTH - player.width) / 2.0, -player.height), 0.0);
+    player.set_velocity(Vec2::ZERO);
+    id
+}
+
+fn input_of(input: Input) -> InputMask {
+    let mut mask = InputMask::new();
+    mask.add_mask(input);
+    mask
+}
+
+/// highest point (smallest y) reached by the top of a player jumping from column 6.
+fn jump_height(ceiling: BlockType) -> f32 {
+    let mut arena = create_test_arena(ceiling);
+    let id = add_standing_player(&mut arena, 6);
+    for _ in 0..30 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let jump = HashMap::from([(id, input_of(Input::Up))]);
+    arena.update(DT, &jump);
+
+    let mut highest = f32::MAX;
+    for _ in 0..60 {
+        arena.update(DT, &HashMap::new());
+        highest = highest.min(arena.get_player(id).unwrap().get_position().y);
+    }
+    highest
+}
 
 #[test]
 fn test_get_block_friction() {
     assert_eq!(get_block_friction(BlockType::GrassBlock), 0.8);
     assert_eq!(get_block_friction(BlockType::IceBlock), 0.2);
+    assert_eq!(get_block_friction(BlockType::StoneBlock), 0.6);
+    assert_eq!(get_block_friction(BlockType::CrateBlock), 0.7);
+}
+
+#[test]
+fn test_is_block_solid() {
+    assert!(!is_block_solid(BlockType::GrassBlock));
+    assert!(!is_block_solid(BlockType::IceBlock));
+    assert!(is_block_solid(BlockType::StoneBlock));
+    assert!(is_block_solid(BlockType::CrateBlock));
+}
+
+#[test]
+fn test_ceiling_stops_jump() {
+    let ceiling_bottom = Arena::get_block_row_position(2) + BLOCK_HEIGHT;
+
+    // jumps through a one-way platform, but bumps into the bottom of a solid block.
+    assert!(jump_height(BlockType::GrassBlock) < ceiling_bottom);
+    assert!((jump_height(BlockType::StoneBlock) - ceiling_bottom).abs() < 1e-3);
+}
+
+#[test]
+fn test_wall_stops_horizontal_motion() {
+    let mut arena = create_test_arena(BlockType::StoneBlock);
+    let id = arena.add_player(Player::new("flying"));
+    let wall = arena.get_block_position_at(1, 8);
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(wall + Vec2::new(-player.width - 10.0, 0.0), 0.0);
+    player.set_velocity(Vec2::new(600.0, 0.0));
+
+    for _ in 0..10 {
+        arena.update(DT, &HashMap::new());
+        let player = arena.get_player(id).unwrap();
+        assert!(player.get_position().x + player.width <= wall.x + 1e-3);
+    }
+    assert_eq!(arena.get_player(id).unwrap().get_velocity().x, 0.0);
+}
+
+#[test]
+fn test_cannot_drop_through_solid_block() {
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    data[BlockType::StoneBlock as usize] |= 1 << (6 * VERTICAL_BLOCKS + 3);
+    data[BlockType::GrassBlock as usize] |= 1 << (7 * VERTICAL_BLOCKS + 3);
+    let mut arena = Arena::new(Map::new(data.into()).unwrap());
+    let on_stone = add_standing_player(&mut arena, 6);
+    let on_grass = add_standing_player(&mut arena, 7);
+
+    let down = input_of(Input::Down);
+    let inputs = HashMap::from([(on_stone, down), (on_grass, down)]);
+    for _ in 0..30 {
+        arena.update(DT, &inputs);
+    }
+
+    let top = Arena::get_block_row_position(3);
+    let stone_player = arena.get_player(on_stone).unwrap();
+    let grass_player = arena.get_player(on_grass).unwrap();
+    assert_eq!(stone_player.get_position().y + stone_player.height, top);
+    assert!(grass_player.get_position().y + grass_player.height > top);
+}
+
+/// a single crate at row 3 of column 6.
+fn create_crate_arena() -> Arena {
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    data[BlockType::CrateBlock as usize] |= 1 << (6 * VERTICAL_BLOCKS + 3);
+    Arena::new(Map::new(data.into()).unwrap())
+}
+
+#[test]
+fn test_bullets_break_crate() {
+    let mut arena = create_crate_arena();
+    let owner = arena.add_player(Player::new("shooter"));
+    let health = arena.get_block_health(3, 6).unwrap();
+    let target = arena.get_block_position_at(3, 6) + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT) / 2.0;
+
+    let mut broken = false;
+    for _ in 0..20 {
+        let bullet = Bullet::new(target - Vec2::new(5.0, 0.0), Vec2::new(600.0, 0.0), BulletType::Sniper, 0, owner);
+        arena.add_bullet(bullet);
+        let events = arena.update(DT, &HashMap::new());
+        assert!(arena.get_bullets().is_empty());
+
+        broken = events.contains(&GameEvent::BlockChanged { row: 3, col: 6, blocktype: None });
+        if broken {
+            break;
+        }
+        assert!(arena.get_block_health(3, 6).unwrap() < health);
+    }
+
+    assert!(broken);
+    assert_eq!(arena.get_block(3, 6), None);
+    assert_eq!(arena.get_block_health(3, 6), None);
+    assert_eq!(arena.get_blocks_iter().count(), 0);
+}
+
+#[test]
+fn test_fast_bullet_cannot_skip_crate() {
+    let mut arena = create_crate_arena();
+    let owner = arena.add_player(Player::new("shooter"));
+    let health = arena.get_block_health(3, 6).unwrap();
+    let above = arena.get_block_position_at(3, 6) + Vec2::new(BLOCK_WIDTH / 2.0, -2.0);
+
+    // the bullet moves from right above the crate to right below it in one tick.
+    let velocity = Vec2::new(0.0, 2200.0);
+    assert!(velocity.y * DT > BLOCK_HEIGHT + 2.0);
+    arena.add_bullet(Bullet::new(above, velocity, BulletType::Sniper, 0, owner));
+    arena.update(DT, &HashMap::new());
+
+    assert!(arena.get_bullets().is_empty());
+    assert!(arena.get_block_health(3, 6).unwrap() < health);
+}
+
+#[test]
+fn test_broken_crate_grows_back() {
+    let mut arena = create_crate_arena();
+    let center = arena.get_block_position_at(3, 6) + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT) / 2.0;
+    arena.add_explosion(center, 100.0, 0.0, 1000.0);
+    assert_eq!(arena.get_block(3, 6), None);
+
+    // the explosion is reported by the next update.
+    let events = arena.update(DT, &HashMap::new());
+    assert!(events.contains(&GameEvent::BlockChanged { row: 3, col: 6, blocktype: None }));
+
+    // a player standing where the crate was keeps it from growing back.
+    let id = arena.add_player(Player::new("in the way"));
+    let regrow_ticks = (get_block_regrow_time(BlockType::CrateBlock).unwrap() as f32 / 1000.0 / DT) as usize;
+    for _ in 0..regrow_ticks + 10 {
+        let player = arena.get_mut_player(id).unwrap();
+        player.update_position(center, 0.0);
+        player.set_velocity(Vec2::ZERO);
+        arena.update(DT, &HashMap::new());
+    }
+    assert_eq!(arena.get_block(3, 6), None);
+
+    arena.remove_player(id);
+    let events = arena.update(DT, &HashMap::new());
+    let regrown = Some(BlockType::CrateBlock);
+    assert!(events.contains(&GameEvent::BlockChanged { row: 3, col: 6, blocktype: regrown }));
+    assert_eq!(arena.get_block(3, 6), regrown);
+    assert_eq!(arena.get_block_health(3, 6), get_block_health(BlockType::CrateBlock));
 }
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use crate::arena::Arena;
+use crate::event::GameEvent;
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::{PLAYER_HEIGHT, PLAYER_WIDTH};
+use glam::Vec2;
+
+const DT: f32 = 1.0 / 60.0;
+
+#[test]
+fn test_players_block_each_other() {
+    let mut arena = Arena::default();
+    let first = arena.add_player(Player::new("first"));
+    let second = arena.add_player(Player::new("second"));
+
+    // both players spawn at the same place, and are pushed apart after landing.
+    for _ in 0..120 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let x = |arena: &Arena, id| arena.get_player(id).unwrap().get_position().x;
+    let (runner, blocker) = if x(&arena, first) < x(&arena, second) { (first, second) } else { (second, first) };
+    assert!(x(&arena, blocker) - x(&arena, runner) >= PLAYER_WIDTH - 1.0);
+
+    // the runner keeps running into the blocker, but never gets past it.
+    let mut right = InputMask::new();
+    right.add_mask(Input::Right);
+    let inputs = HashMap::from([(runner, right)]);
+    for _ in 0..60 {
+        arena.update(DT, &inputs);
+        assert!(x(&arena, blocker) - x(&arena, runner) > PLAYER_WIDTH / 2.0);
+    }
+}
+
+#[test]
+fn test_collision_exchanges_momentum() {
+    let mut arena = Arena::default();
+    let light = arena.add_player(Player::new("light"));
+    let heavy = arena.add_player(Player::new("heavy"));
+
+    // the players collide high above the blocks.
+    let position = Vec2::new(500.0, -1000.0);
+    let player = arena.get_mut_player(light).unwrap();
+    player.update_position(position, 0.0);
+    player.set_velocity(Vec2::new(300.0, 0.0));
+    let light_mass = player.get_total_mass();
+
+    let player = arena.get_mut_player(heavy).unwrap();
+    player.update_position(position + Vec2::new(PLAYER_WIDTH - 4.0, 0.0), 0.0);
+    player.mass *= 3.0;
+    let heavy_mass = player.get_total_mass();
+
+    arena.set_body_push_strength(1.0);
+    arena.update(DT, &HashMap::new());
+
+    let light_player = arena.get_player(light).unwrap();
+    let heavy_player = arena.get_player(heavy).unwrap();
+    let momentum = light_mass * light_player.get_velocity().x + heavy_mass * heavy_player.get_velocity().x;
+    assert!((momentum - light_mass * 300.0).abs() < 1e-2 * light_mass * 300.0);
+    assert!((light_player.get_velocity().x - heavy_player.get_velocity().x).abs() < 1e-3);
+
+    // the heavier player was pushed back less than the lighter one.
+    assert!(heavy_player.get_position().x - light_player.get_position().x >= PLAYER_WIDTH - 1e-3);
+}
+
+#[test]
+fn test_stomp_bounces_off_head() {
+    let mut arena = Arena::default();
+    let stomper = arena.add_player(Player::new("stomper"));
+    let victim = arena.add_player(Player::new("victim"));
+
+    let position = Vec2::new(500.0, -1000.0);
+    arena.get_mut_player(victim).unwrap().update_position(position, 0.0);
+    let player = arena.get_mut_player(stomper).unwrap();
+    player.update_position(position - Vec2::new(4.0, PLAYER_HEIGHT - 2.0), 0.0);
+    player.set_velocity(Vec2::new(0.0, 300.0));
+
+    let events = arena.update(DT, &HashMap::new());
+    assert!(events.contains(&GameEvent::PlayerStomped { attacker: stomper, victim }));
+
+    let stomper_player = arena.get_player(stomper).unwrap();
+    let victim_player = arena.get_player(victim).unwrap();
+    assert!(stomper_player.get_velocity().y < 0.0);
+    assert!(victim_player.get_velocity().y > 0.0);
+    assert!(stomper_player.get_position().y + PLAYER_HEIGHT <= victim_player.get_position().y + 1e-3);
+}
@@ -0,0 +1,84 @@
+use glam::Vec2;
+use strum::EnumCount;
+use crate::arena::Arena;
+use crate::block::BlockType;
+use crate::entity::EntityId;
+use crate::bot::{Bot, Difficulty, REACTION_TICKS};
+use crate::input::Input;
+use crate::map::{Map, VERTICAL_BLOCKS};
+use crate::player::Player;
+use crate::PLAYER_WIDTH;
+
+/// row 3 is filled from columns 4 to 11 and column 15 has a single block.
+fn create_test_arena() -> Arena {
+    let row = 3;
+    let mut grassbits: i128 = 0;
+    for col in (4..=11).chain(15..=15) {
+        grassbits |= 1 << (col * VERTICAL_BLOCKS + row);
+    }
+
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    data[BlockType::GrassBlock as usize] = grassbits;
+    Arena::new(Map::new(data.into()).unwrap())
+}
+
+/// places a player standing on row 3 at x position `x`. Returns the id of the player.
+fn add_standing_player(arena: &mut Arena, x: f32) -> EntityId {
+    let id = arena.add_player(Player::new("standing"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(x, Arena::get_block_row_position(3) - player.height), 0.0);
+    player.set_velocity(Vec2::ZERO);
+    id
+}
+
+#[test]
+fn test_bot_without_player() {
+    let arena = create_test_arena();
+    let mut bot = Bot::new(EntityId::new(0, 0), Difficulty::Normal, 0);
+    let input: u32 = bot.think(&arena).into();
+
+    assert_eq!(input, 0);
+}
+
+#[test]
+fn test_bot_shoots_level_enemy() {
+    let mut arena = create_test_arena();
+    let id = add_standing_player(&mut arena, Arena::get_block_col_position(5));
+    add_standing_player(&mut arena, Arena::get_block_col_position(6));
+
+    let mut bot = Bot::new(id, Difficulty::Hard, 0);
+    let reaction = REACTION_TICKS[Difficulty::Hard as usize];
+    for _ in 0..reaction {
+        assert!(!bot.think(&arena).has_mask(Input::Shoot));
+    }
+
+    assert!(bot.think(&arena).has_mask(Input::Shoot));
+}
+
+#[test]
+fn test_bot_does_not_walk_off_edge() {
+    let mut arena = create_test_arena();
+    let edge = Arena::get_block_col_position(12) - PLAYER_WIDTH;
+    let id = add_standing_player(&mut arena, edge);
+    add_standing_player(&mut arena, Arena::get_block_col_position(15));
+
+    let mut bot = Bot::new(id, Difficulty::Normal, 0);
+    for _ in 0..10 {
+        let input = bot.think(&arena);
+        assert!(!input.has_mask(Input::Right));
+    }
+}
+
+#[test]
+fn test_bot_recovers_towards_stage() {
+    let mut arena = create_test_arena();
+    let id = add_standing_player(&mut arena, Arena::get_block_col_position(1));
+    arena.get_mut_player(id).unwrap().set_velocity(Vec2::new(-100.0, 200.0));
+
+    let mut bot = Bot::new(id, Difficulty::Easy, 0);
+    let input = bot.think(&arena);
+
+    assert!(input.has_mask(Input::Right));
+    assert!(input.has_mask(Input::Up));
+    assert!(!input.has_mask(Input::Left));
+}
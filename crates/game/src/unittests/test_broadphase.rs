@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::broadphase::Broadphase;
+use crate::player::Player;
+use crate::{BLOCK_WIDTH, VERTICAL_BLOCK_SPACING};
+
+#[test]
+fn test_query_point() {
+    let mut grid: Broadphase<u16> = Broadphase::new();
+    grid.insert_point(1, Vec2::new(10.0, 10.0));
+    grid.insert_point(2, Vec2::new(BLOCK_WIDTH + 10.0, 10.0));
+
+    let found: Vec<u16> = grid.query_point(Vec2::new(50.0, 50.0)).collect();
+    assert_eq!(found, vec![1]);
+}
+
+#[test]
+fn test_query_rect_spanning_cells() {
+    let mut grid: Broadphase<u8> = Broadphase::new();
+    let min = Vec2::new(BLOCK_WIDTH - 10.0, VERTICAL_BLOCK_SPACING - 10.0);
+    grid.insert(7, min, min + Vec2::new(20.0, 20.0));
+
+    // the rectangle overlaps 4 cells, so it is found from each of them.
+    assert_eq!(grid.query_point(Vec2::new(0.0, 0.0)).count(), 1);
+    assert_eq!(grid.query_point(Vec2::new(BLOCK_WIDTH, VERTICAL_BLOCK_SPACING)).count(), 1);
+    assert_eq!(grid.query(Vec2::ZERO, 2.0 * min).count(), 4);
+}
+
+#[test]
+fn test_query_outside_arena_is_clamped() {
+    let mut grid: Broadphase<u16> = Broadphase::new();
+    grid.insert_point(3, Vec2::new(-500.0, -500.0));
+
+    let found: Vec<u16> = grid.query_point(Vec2::new(-1.0, -1.0)).collect();
+    assert_eq!(found, vec![3]);
+
+    grid.clear();
+    assert_eq!(grid.query_point(Vec2::new(-1.0, -1.0)).count(), 0);
+}
+
+#[test]
+fn test_arena_query_players() {
+    let mut arena = Arena::default();
+    let a = arena.add_player(Player::new("a"));
+    let b = arena.add_player(Player::new("b"));
+    arena.get_mut_player(a).unwrap().update_position(Vec2::new(500.0, 0.0), 0.0);
+    arena.get_mut_player(b).unwrap().update_position(Vec2::new(1500.0, 0.0), 0.0);
+    arena.update(1.0 / 60.0, &HashMap::new());
+
+    let position = arena.get_player(a).unwrap().get_position();
+    let found = arena.query_players(position - Vec2::splat(50.0), position + Vec2::splat(50.0));
+    assert_eq!(found, vec![a]);
+}
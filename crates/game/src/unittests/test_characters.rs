@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::characterscatalog::{CharacterClass, CLASS_JUMPS_COUNTS, CLASS_SIZES, CLASS_WEAPONS};
+use crate::entity::EntityId;
+use crate::fixed;
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::weaponscatalog::MAGAZINE_SIZES;
+
+const DT: f32 = 1.0 / 60.0;
+
+fn add_player_of_class(arena: &mut Arena, class: CharacterClass) -> EntityId {
+    let mut player = Player::new(&class.to_string());
+    player.set_class(class, arena.get_time());
+    arena.add_player(player)
+}
+
+#[test]
+fn test_default_class() {
+    let player = Player::new("default");
+    let mut brawler = Player::new("brawler");
+    brawler.set_class(CharacterClass::Brawler, 0);
+
+    assert_eq!(player.get_class(), CharacterClass::Brawler);
+    assert_eq!((player.mass, player.speed_cap, player.jumps_count), (brawler.mass, brawler.speed_cap, brawler.jumps_count));
+    assert_eq!((player.width, player.height), (brawler.width, brawler.height));
+    assert_eq!(player.get_weapon().get_weapon_type(), brawler.get_weapon().get_weapon_type());
+}
+
+#[test]
+fn test_class_stats_applied() {
+    let mut player = Player::new("scout");
+    player.set_class(CharacterClass::Scout, 0);
+    let i = CharacterClass::Scout as usize;
+    assert_eq!(player.jumps_count, CLASS_JUMPS_COUNTS[i]);
+    assert_eq!(Vec2::new(player.width, player.height), CLASS_SIZES[i]);
+
+    player.set_class(CharacterClass::Tank, 0);
+    assert_eq!(player.get_weapon().get_weapon_type(), CLASS_WEAPONS[CharacterClass::Tank as usize]);
+}
+
+#[test]
+fn test_scout_triple_jumps() {
+    let mut arena = Arena::default();
+    let scout = add_player_of_class(&mut arena, CharacterClass::Scout);
+    let brawler = add_player_of_class(&mut arena, CharacterClass::Brawler);
+    for _ in 0..120 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let mut jump = InputMask::new();
+    jump.add_mask(Input::Up);
+    let inputs = HashMap::from([(scout, jump), (brawler, jump)]);
+    let mut jumps = HashMap::from([(scout, 0), (brawler, 0)]);
+    for _ in 0..120 {
+        for id in [scout, brawler] {
+            let before = arena.get_player(id).unwrap().jumps_left;
+            arena.update(DT, &HashMap::from([(id, inputs[&id])]));
+            let after = arena.get_player(id).unwrap().jumps_left;
+            if after < before {
+                *jumps.get_mut(&id).unwrap() += 1;
+            }
+        }
+    }
+
+    assert!(jumps[&scout] >= 3);
+    assert!(jumps[&scout] > jumps[&brawler]);
+}
+
+#[test]
+fn test_tank_resists_knockback() {
+    let mut arena = Arena::default();
+    let tank = add_player_of_class(&mut arena, CharacterClass::Tank);
+    let scout = add_player_of_class(&mut arena, CharacterClass::Scout);
+    let attacker = add_player_of_class(&mut arena, CharacterClass::Brawler);
+
+    let momentum = Vec2::new(10000.0, 0.0);
+    for id in [tank, scout] {
+        let player = arena.get_mut_player(id).unwrap();
+        player.set_velocity(Vec2::ZERO);
+        player.add_knockback(fixed::real_vec2(momentum), 0.0, attacker);
+    }
+
+    let tank_speed = arena.get_player(tank).unwrap().get_velocity().x;
+    let scout_speed = arena.get_player(scout).unwrap().get_velocity().x;
+    assert!(tank_speed > 0.0);
+    assert!(scout_speed > 2.0 * tank_speed);
+}
+
+#[test]
+fn test_switching_class_keeps_used_weapon() {
+    let mut arena = Arena::default();
+    let id = add_player_of_class(&mut arena, CharacterClass::Brawler);
+    for _ in 0..120 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let mut shoot = InputMask::new();
+    shoot.add_mask(Input::Shoot);
+    arena.update(DT, &HashMap::from([(id, shoot)]));
+    let magazine = arena.get_player(id).unwrap().get_weapon().get_magazine();
+    assert!(magazine < MAGAZINE_SIZES[CLASS_WEAPONS[CharacterClass::Brawler as usize] as usize]);
+
+    let curr_time = arena.get_time();
+    let player = arena.get_mut_player(id).unwrap();
+    player.set_class(CharacterClass::Tank, curr_time);
+    player.set_class(CharacterClass::Brawler, curr_time);
+    let weapon = player.get_weapon();
+    assert_eq!(weapon.get_weapon_type(), CLASS_WEAPONS[CharacterClass::Brawler as usize]);
+    assert_eq!(weapon.get_magazine(), magazine);
+}
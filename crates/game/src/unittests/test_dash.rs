@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::weapon::Bullet;
+use crate::weaponscatalog::BulletType;
+use crate::{DASH_AIR_COOLDOWN, DASH_DURATION, DASH_GROUND_COOLDOWN, DASH_INVULNERABILITY};
+
+const DT: f32 = 1.0 / 60.0;
+
+/// adds a player and waits two seconds, so it lands on a block.
+fn add_settled_player(arena: &mut Arena) -> EntityId {
+    let id = arena.add_player(Player::new("dasher"));
+    for _ in 0..120 {
+        arena.update(DT, &HashMap::new());
+    }
+    id
+}
+
+fn input_of(input: Input) -> InputMask {
+    let mut mask = InputMask::new();
+    mask.add_mask(input);
+    mask
+}
+
+/// lifts the player high above the blocks, where there is no friction.
+fn lift(player: &mut Player) {
+    player.update_position(Vec2::new(player.get_position().x, -1000.0), 0.0);
+}
+
+/// updates the arena with a dash input for the player `id`.
+fn dash(arena: &mut Arena, id: EntityId) -> Vec<GameEvent> {
+    arena.update(DT, &HashMap::from([(id, input_of(Input::Dash))]))
+}
+
+/// updates the arena without any input for `ms` milliseconds.
+fn wait(arena: &mut Arena, ms: u128) {
+    let ticks = (ms as f32 / 1000.0 / DT).ceil() as usize;
+    for _ in 0..ticks {
+        arena.update(DT, &HashMap::new());
+    }
+}
+
+#[test]
+fn test_dash_towards_facing_direction() {
+    let mut arena = Arena::default();
+    let id = add_settled_player(&mut arena);
+    let speed_cap = arena.get_player(id).unwrap().get_speed_cap();
+
+    let events = dash(&mut arena, id);
+    assert!(events.contains(&GameEvent::Dashed { player: id, midair: false }));
+    let player = arena.get_player(id).unwrap();
+    assert!(player.is_dashing());
+    assert!(player.get_velocity().x > speed_cap);
+    assert_eq!(player.get_velocity().y, 0.0);
+
+    // an input direction takes over the facing direction.
+    wait(&mut arena, DASH_GROUND_COOLDOWN);
+    let mut mask = input_of(Input::Dash);
+    mask.add_mask(Input::Left);
+    arena.update(DT, &HashMap::from([(id, mask)]));
+    assert!(arena.get_player(id).unwrap().get_velocity().x < -speed_cap);
+}
+
+#[test]
+fn test_dash_slows_down_to_speed_cap() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("dasher"));
+
+    // dashes from high above the blocks, so there is no friction.
+    let player = arena.get_mut_player(id).unwrap();
+    lift(player);
+    let speed_cap = player.get_speed_cap();
+    dash(&mut arena, id);
+
+    wait(&mut arena, DASH_DURATION);
+    let player = arena.get_player(id).unwrap();
+    assert!(!player.is_dashing());
+    assert!((player.get_velocity().x - speed_cap).abs() < 0.1);
+}
+
+#[test]
+fn test_dash_cooldown() {
+    let mut arena = Arena::default();
+    let id = add_settled_player(&mut arena);
+    assert!(dash(&mut arena, id).contains(&GameEvent::Dashed { player: id, midair: false }));
+
+    // a dash started on a block can be used again after the ground cooldown.
+    let events = dash(&mut arena, id);
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::Dashed { .. })));
+    wait(&mut arena, DASH_GROUND_COOLDOWN);
+    let events = dash(&mut arena, id);
+    assert!(events.contains(&GameEvent::Dashed { player: id, midair: false }));
+
+    // a dash started in the air takes longer to come back.
+    wait(&mut arena, DASH_GROUND_COOLDOWN);
+    lift(arena.get_mut_player(id).unwrap());
+    let events = dash(&mut arena, id);
+    assert!(events.contains(&GameEvent::Dashed { player: id, midair: true }));
+    wait(&mut arena, DASH_GROUND_COOLDOWN);
+    let events = dash(&mut arena, id);
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::Dashed { .. })));
+    wait(&mut arena, DASH_AIR_COOLDOWN - DASH_GROUND_COOLDOWN);
+    let events = dash(&mut arena, id);
+    assert!(events.contains(&GameEvent::Dashed { player: id, midair: true }));
+}
+
+#[test]
+fn test_dash_invulnerability() {
+    let mut arena = Arena::default();
+    let shooter = arena.add_player(Player::new("shooter"));
+    let id = add_settled_player(&mut arena);
+    lift(arena.get_mut_player(shooter).unwrap());
+
+    // bullets fly through the player right after it dashed.
+    dash(&mut arena, id);
+    let curr_time = arena.get_time();
+    let player = arena.get_player(id).unwrap();
+    assert!(player.is_invulnerable(curr_time));
+    let center = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+    arena.add_bullet(Bullet::new(center, Vec2::ZERO, BulletType::Sniper, 0, shooter));
+    let events = arena.update(DT, &HashMap::new());
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerHit { .. })));
+    assert_eq!(arena.get_bullets().len(), 1);
+
+    // explosions do not push the player either.
+    let player = arena.get_player(id).unwrap();
+    let center = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+    assert!(arena.add_explosion(center, 100.0, 1000.0, 0.0).is_empty());
+
+    wait(&mut arena, DASH_INVULNERABILITY);
+    assert!(!arena.get_player(id).unwrap().is_invulnerable(arena.get_time()));
+    let player = arena.get_player(id).unwrap();
+    let center = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+    assert_eq!(arena.add_explosion(center, 100.0, 1000.0, 0.0), vec![id]);
+}
+
+#[test]
+fn test_dash_reported_for_its_tick_only() {
+    let mut player = Player::new("short ticks");
+    player.add_dash_impulse(true, true, 1.0, DASH_GROUND_COOLDOWN);
+    assert!(player.has_dashed());
+
+    // a tick shorter than a millisecond happens at the same time, but is not a dash.
+    player.add_dash_impulse(true, false, 0.0, DASH_GROUND_COOLDOWN);
+    assert!(!player.has_dashed());
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::effect::{EffectType, EFFECT_DURATIONS, MAX_EFFECT_DURATION, SPEED_BOOST_MULTIPLIER};
+use crate::event::GameEvent;
+use crate::pickup::{Pickup, PickupKind, PickupSpawner, PICKUP_SPAWN_INTERVAL};
+use crate::player::Player;
+use crate::weapon::Bullet;
+use crate::weaponscatalog::BulletType;
+
+#[test]
+fn test_effect_stacking() {
+    let mut player = Player::new("stacker");
+    let shield = EFFECT_DURATIONS[EffectType::Shield as usize];
+    let speed = EFFECT_DURATIONS[EffectType::SpeedBoost as usize];
+
+    // shields refresh their duration.
+    player.add_effect(EffectType::Shield, 0);
+    player.add_effect(EffectType::Shield, 1000);
+    assert_eq!(player.get_effects()[0].get_expires_at(), 1000 + shield);
+
+    // speed boosts extend their duration, up to the maximum.
+    player.add_effect(EffectType::SpeedBoost, 0);
+    player.add_effect(EffectType::SpeedBoost, 1000);
+    assert_eq!(player.get_effects()[1].get_expires_at(), 2 * speed);
+
+    for _ in 0..10 {
+        player.add_effect(EffectType::SpeedBoost, 1000);
+    }
+    assert_eq!(player.get_effects()[1].get_expires_at(), 1000 + MAX_EFFECT_DURATION);
+    assert_eq!(player.get_effects().len(), 2);
+}
+
+#[test]
+fn test_effect_modifies_player() {
+    let mut player = Player::new("boosted");
+    let speed_cap = player.get_speed_cap();
+    let jumps = player.get_jumps_count();
+    let mass = player.get_total_mass();
+
+    player.add_effect(EffectType::SpeedBoost, 0);
+    player.add_effect(EffectType::ExtraJump, 0);
+    player.add_effect(EffectType::HeavyBody, 0);
+    assert_eq!(player.get_speed_cap(), speed_cap * SPEED_BOOST_MULTIPLIER);
+    assert_eq!(player.get_jumps_count(), jumps + 1);
+    assert!(player.get_total_mass() > mass);
+
+    let duration = EFFECT_DURATIONS[EffectType::ExtraJump as usize];
+    let expired = player.update_effects(duration);
+    assert!(expired.contains(&EffectType::ExtraJump));
+    assert_eq!(player.get_jumps_count(), jumps);
+}
+
+#[test]
+fn test_shield_absorbs_hit() {
+    let mut arena = Arena::default();
+    let attacker = arena.add_player(Player::new("attacker"));
+    let victim = arena.add_player(Player::new("shielded"));
+    let player = arena.get_mut_player(victim).unwrap();
+    player.update_position(Vec2::new(500.0, 500.0), 0.0);
+    player.add_effect(EffectType::Shield, 0);
+    let target = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+
+    let velocity = Vec2::new(600.0, 0.0);
+    let bullet = Bullet::new(target - Vec2::new(5.0, 0.0), velocity, BulletType::Pistol, 0, attacker);
+    arena.add_bullet(bullet);
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+
+    assert!(events.contains(&GameEvent::ShieldBroken { attacker, victim }));
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerHit { .. })));
+
+    let player = arena.get_player(victim).unwrap();
+    assert!(!player.has_effect(EffectType::Shield));
+    assert_eq!(player.get_last_hit_by(), None);
+}
+
+#[test]
+fn test_effect_pickup() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("picker"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(500.0, Arena::get_block_row_position(3) - player.height), 0.0);
+    let position = player.get_position();
+    let effecttype = EffectType::InfiniteAmmo;
+    let pickup = arena.add_pickup(Pickup::new(position, PickupKind::Effect(effecttype)));
+
+    let events = arena.update(1.0 / 60.0, &HashMap::new());
+    assert!(events.contains(&GameEvent::EffectPickedUp { player: id, pickup, effecttype }));
+    assert!(arena.get_player(id).unwrap().has_effect(effecttype));
+}
+
+#[test]
+fn test_pickups_spawn_only_with_spawner() {
+    let ticks = (PICKUP_SPAWN_INTERVAL / 1000 + 1) * 60;
+    let mut arena = Arena::default();
+    for _ in 0..ticks {
+        arena.update(1.0 / 60.0, &HashMap::new());
+    }
+    assert!(arena.get_pickups().is_empty());
+
+    let mut arena = Arena::default();
+    arena.set_pickup_spawner(Some(PickupSpawner::new(0)));
+    for _ in 0..ticks {
+        arena.update(1.0 / 60.0, &HashMap::new());
+    }
+    assert_eq!(arena.get_pickups().len(), 1);
+}
@@ -0,0 +1,70 @@
+use crate::entity::{EntityId, EntityStore};
+
+#[test]
+fn test_insert_and_remove() {
+    let mut store = EntityStore::new();
+    let a = store.insert("a");
+    let b = store.insert("b");
+
+    assert_eq!(store.len(), 2);
+    assert_eq!(store.get(a), Some(&"a"));
+    assert_eq!(store.remove(a), Some("a"));
+    assert_eq!(store.remove(a), None);
+    assert_eq!(store.len(), 1);
+    assert!(store.contains(b));
+}
+
+#[test]
+fn test_stale_id_after_reuse() {
+    let mut store = EntityStore::new();
+    let old = store.insert(1);
+    store.remove(old);
+    let new = store.insert(2);
+
+    assert_eq!(old.get_index(), new.get_index());
+    assert_ne!(old.get_generation(), new.get_generation());
+    assert_eq!(store.get(old), None);
+    assert_eq!(store.get(new), Some(&2));
+}
+
+#[test]
+fn test_slot_retired_before_generation_wraps() {
+    let mut store = EntityStore::new();
+    let first = store.insert(0);
+    store.remove(first);
+    for _ in 1..=u16::MAX {
+        let id = store.insert(1);
+        store.remove(id);
+    }
+
+    // the slot ran out of generations, so a new slot is used instead.
+    let last = store.insert(2);
+    assert_eq!(last.get_generation(), 0);
+    assert_ne!(last.get_index(), first.get_index());
+
+    let next = store.insert(3);
+    assert_ne!(next.get_index(), first.get_index());
+    assert_eq!(store.get(first), None);
+}
+
+#[test]
+fn test_insert_at_mirrors_ids() {
+    let mut store = EntityStore::new();
+    let id = EntityId::new(3, 7);
+    store.insert_at(id, "mirrored");
+
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.get(id), Some(&"mirrored"));
+    assert_eq!(store.get(EntityId::new(3, 6)), None);
+
+    // slots skipped by `insert_at` are reused by `insert`.
+    let other = store.insert("other");
+    assert!(other.get_index() < 3);
+    assert_eq!(store.ids().collect::<Vec<_>>(), vec![other, id]);
+}
+
+#[test]
+fn test_bits_round_trip() {
+    let id = EntityId::new(513, 42);
+    assert_eq!(EntityId::from_bits(id.to_bits()), id);
+}
@@ -0,0 +1,78 @@
+use glam::Vec2;
+use crate::environment::{Environment, EnvironmentConfig, EnvironmentPool};
+use crate::fixed;
+use crate::input::InputMask;
+use crate::ARENA_HEIGHT;
+
+#[test]
+fn test_reset_observations() {
+    let mut environment = Environment::new(EnvironmentConfig::default());
+    let observations = environment.reset(0);
+
+    assert_eq!(observations.len(), 2);
+    for observation in observations {
+        assert_eq!(observation.len(), environment.observation_len());
+    }
+}
+
+#[test]
+fn test_reset_is_deterministic() {
+    let mut environment = Environment::new(EnvironmentConfig::default());
+    let first = environment.reset(42);
+    let second = environment.reset(42);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_step_survival_reward() {
+    let config = EnvironmentConfig::default();
+    let survival = config.rewards.survival;
+    let mut environment = Environment::new(config);
+    environment.reset(0);
+
+    let step = environment.step(&[InputMask::new(), InputMask::new()]);
+    assert!(!step.done);
+    assert_eq!(step.rewards, vec![survival, survival]);
+}
+
+#[test]
+fn test_step_ring_out_ends_episode() {
+    let config = EnvironmentConfig::default();
+    let rewards = config.rewards;
+    let mut environment = Environment::new(config);
+    environment.reset(0);
+
+    // throws player 1 out of the arena after player 0 hit it.
+    let killer = environment.get_entity_id(0).unwrap();
+    let victim = environment.get_entity_id(1).unwrap();
+    let player = environment.get_mut_arena().get_mut_player(victim).unwrap();
+    player.add_knockback(fixed::real_vec2(Vec2::ZERO), 10.0, killer);
+    player.update_position(Vec2::new(0.0, 2.0 * ARENA_HEIGHT), 0.0);
+
+    let step = environment.step(&[InputMask::new(), InputMask::new()]);
+    assert!(step.done);
+    assert_eq!(step.rewards[0], rewards.survival + rewards.ring_out);
+    assert_eq!(step.rewards[1], rewards.death);
+    assert!(!environment.is_alive(1));
+    assert!(step.observations[1].iter().all(|x| *x == 0.0));
+}
+
+#[test]
+fn test_pool_matches_single_environment() {
+    let config = EnvironmentConfig::default();
+    let mut environment = Environment::new(config.clone());
+    let mut pool = EnvironmentPool::new(config, 4, 2);
+
+    environment.reset(7);
+    pool.reset(7);
+
+    let actions = vec![vec![InputMask::new(); 2]; 4];
+    for _ in 0..10 {
+        let expected = environment.step(&actions[0]);
+        let steps = pool.step(&actions);
+
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0].observations, expected.observations);
+    }
+}
@@ -0,0 +1,94 @@
+use glam::Vec2;
+use crate::fixed::{self, Fixed, FixedVec2};
+
+#[cfg(feature = "fixed-point")]
+const DT: f32 = 1.0 / 60.0;
+
+/// inputs of the player `index` at `tick`, which run, jump, shoot and dash around.
+#[cfg(feature = "fixed-point")]
+fn scripted_input(index: usize, tick: usize) -> crate::input::InputMask {
+    use crate::input::{Input, InputMask};
+
+    const SCRIPT: [u32; 6] = [
+        1 << Input::Right as u32,
+        1 << Input::Right as u32 | 1 << Input::Up as u32,
+        1 << Input::Shoot as u32,
+        1 << Input::Left as u32 | 1 << Input::Dash as u32,
+        1 << Input::Melee as u32 | 1 << Input::Left as u32,
+        1 << Input::Up as u32 | 1 << Input::Shoot as u32,
+    ];
+
+    InputMask::from(SCRIPT[(tick / 15 + index * 2) % SCRIPT.len()])
+}
+
+/// runs an arena of three players following the script for `ticks` ticks, and obtains the
+/// hash of the final state.
+#[cfg(feature = "fixed-point")]
+fn run_hash(ticks: usize) -> u64 {
+    use std::collections::HashMap;
+    use crate::arena::Arena;
+    use crate::entity::EntityId;
+    use crate::input::InputMask;
+    use crate::player::Player;
+
+    let mut arena = Arena::default();
+    let ids: Vec<EntityId> = (0..3).map(|i| arena.add_player(Player::new(&format!("player {}", i)))).collect();
+
+    for tick in 0..ticks {
+        let inputs: HashMap<EntityId, InputMask> = ids.iter()
+            .enumerate()
+            .map(|(i, id)| (*id, scripted_input(i, tick)))
+            .collect();
+        arena.update(DT, &inputs);
+    }
+    arena.get_state_hash()
+}
+
+#[test]
+fn test_fixed_arithmetic() {
+    let (a, b) = (Fixed::from_f32(1.5), Fixed::from_f32(-2.25));
+    assert_eq!(a + b, Fixed::from_f32(-0.75));
+    assert_eq!(a - b, Fixed::from_f32(3.75));
+    assert_eq!(a * b, Fixed::from_f32(-3.375));
+    assert_eq!(b / a, Fixed::from_f32(-1.5));
+    assert_eq!(-a, Fixed::from_f32(-1.5));
+    assert_eq!(Fixed::ONE.to_bits(), 1 << 16);
+    assert_eq!((a * b).to_f32(), -3.375);
+
+    // values are rounded to the closest multiple of 1/65536, even far across the arena.
+    assert_eq!(Fixed::from_f32(0.4 / 65536.0), Fixed::ZERO);
+    assert_eq!(Fixed::from_f32(0.6 / 65536.0), Fixed::from_bits(1));
+    assert_eq!(Fixed::from_f32(3000.0 + 1.0 / 4096.0).to_bits(), 3000 * 65536 + 16);
+
+    let vector = FixedVec2::from(Vec2::new(3.0, -0.5));
+    assert_eq!(Vec2::from(vector * Fixed::from_f32(2.0)), Vec2::new(6.0, -1.0));
+    assert_eq!(Vec2::from(vector - vector), Vec2::ZERO);
+    assert_eq!(FixedVec2::from(Vec2::new(3.0, -4.0)).length(), Fixed::from_f32(5.0));
+}
+
+/// hash of the arena after 600 ticks of `scripted_input`.
+#[cfg(feature = "fixed-point")]
+const GOLDEN_HASH: u64 = 16692484475235232675;
+
+/// the state after the script is pinned, so a change of the movement (or a build which computes
+/// it differently) shows up as a different hash.
+#[cfg(feature = "fixed-point")]
+#[test]
+fn test_scripted_run_matches_golden_hash() {
+    assert_eq!(run_hash(600), GOLDEN_HASH);
+}
+
+#[test]
+fn test_integrate() {
+    let (position, velocity) = fixed::integrate(
+        fixed::real_vec2(Vec2::new(10.0, 20.0)),
+        fixed::real_vec2(Vec2::new(60.0, 0.0)),
+        fixed::real_vec2(Vec2::new(0.0, 120.0)),
+        fixed::real(0.5),
+    );
+    assert_eq!(fixed::to_vec2(position), Vec2::new(40.0, 35.0));
+    assert_eq!(fixed::to_vec2(velocity), Vec2::new(60.0, 60.0));
+
+    let velocity = fixed::get_velocity_between(fixed::real_vec2(Vec2::ZERO), position, fixed::real(0.5));
+    assert_eq!(fixed::to_vec2(velocity), Vec2::new(80.0, 70.0));
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::gamemode::{GameMode, GameModeType, Scorer, FLAG_SIZE, HILL_SCORE_LIMIT};
+use crate::player::Player;
+use crate::weaponscatalog::WeaponType;
+use crate::PLAYER_HEIGHT;
+use glam::Vec2;
+use strum::EnumCount;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// updates the arena and then the mode, like the server does on every tick.
+fn tick(arena: &mut Arena, mode: &mut GameMode) -> Vec<GameEvent> {
+    let events = arena.update(DT, &HashMap::new());
+    mode.update(arena, &events);
+    events
+}
+
+fn get_score(mode: &GameMode, scorer: Scorer) -> u32 {
+    mode.get_scores()
+        .into_iter()
+        .find(|(s, _)| *s == scorer)
+        .map(|(_, score)| score)
+        .expect("Scorer should have a score")
+}
+
+#[test]
+fn test_king_of_the_hill() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::KingOfTheHill, &arena);
+    let hill = mode.get_objectives()[0];
+    let king = arena.add_player(Player::new("king"));
+    let other = arena.add_player(Player::new("other"));
+    arena.get_mut_player(king).unwrap().update_position(hill + Vec2::new(10.0, 0.0), 0.0);
+
+    // a second of standing alone on the hill.
+    for _ in 0..60 {
+        tick(&mut arena, &mut mode);
+    }
+
+    let score = get_score(&mode, Scorer::Player(king));
+    assert!((900..=1100).contains(&score));
+    assert_eq!(get_score(&mode, Scorer::Player(other)), 0);
+
+    // nobody scores while the hill is contested.
+    arena.get_mut_player(other).unwrap().update_position(hill + Vec2::new(60.0, 0.0), 0.0);
+    for _ in 0..60 {
+        tick(&mut arena, &mut mode);
+    }
+    assert_eq!(get_score(&mode, Scorer::Player(king)), score);
+
+    arena.remove_player(other);
+    while mode.get_winner().is_none() {
+        tick(&mut arena, &mut mode);
+    }
+
+    assert_eq!(mode.get_winner(), Some(Scorer::Player(king)));
+    assert!(get_score(&mode, Scorer::Player(king)) >= HILL_SCORE_LIMIT);
+}
+
+#[test]
+fn test_gun_game_advances_on_kills() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::GunGame, &arena);
+    let killer = arena.add_player(Player::new("killer"));
+    let victim = arena.add_player(Player::new("victim"));
+    tick(&mut arena, &mut mode);
+
+    let kill = [GameEvent::PlayerKilled { victim, killer: Some(killer) }];
+    mode.update(&mut arena, &kill);
+    assert_eq!(get_score(&mode, Scorer::Player(killer)), 1);
+    assert_eq!(get_score(&mode, Scorer::Player(victim)), 0);
+
+    let weapontype = arena.get_player(killer).unwrap().get_weapon().get_weapon_type();
+    assert_eq!(weapontype, WeaponType::from_repr(1).unwrap());
+
+    // a kill with the last weapon of the catalog wins.
+    for _ in 1..WeaponType::COUNT {
+        assert_eq!(mode.get_winner(), None);
+        mode.update(&mut arena, &kill);
+    }
+    assert_eq!(mode.get_winner(), Some(Scorer::Player(killer)));
+}
+
+#[test]
+fn test_capture_the_flag() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::CaptureTheFlag, &arena);
+    let red = arena.add_player(Player::new("red"));
+    let blue = arena.add_player(Player::new("blue"));
+    tick(&mut arena, &mut mode);
+
+    assert_eq!(mode.get_team(red), Some(0));
+    assert_eq!(mode.get_team(blue), Some(1));
+    assert_eq!(arena.get_player(blue).unwrap().get_team(), 1);
+
+    // players standing right on top of the block of a flag.
+    let flags = mode.get_objectives();
+    let standing_at = |flag: Vec2| flag + Vec2::new(0.0, FLAG_SIZE - PLAYER_HEIGHT);
+
+    // the red player takes the blue flag and brings it back to the red flag.
+    arena.get_mut_player(red).unwrap().update_position(standing_at(flags[1]), 0.0);
+    tick(&mut arena, &mut mode);
+    assert_ne!(mode.get_objectives()[1], flags[1]);
+
+    arena.get_mut_player(red).unwrap().update_position(standing_at(flags[0]), 0.0);
+    tick(&mut arena, &mut mode);
+    assert_eq!(get_score(&mode, Scorer::Team(0)), 1);
+    assert_eq!(mode.get_objectives(), flags);
+
+    // the flag goes back home when its carrier is knocked out.
+    arena.get_mut_player(blue).unwrap().update_position(standing_at(flags[0]), 0.0);
+    tick(&mut arena, &mut mode);
+    assert_ne!(mode.get_objectives()[0], flags[0]);
+
+    let knockout = [GameEvent::PlayerKilled { victim: blue, killer: None }];
+    arena.get_mut_player(blue).unwrap().update_position(Vec2::new(500.0, 0.0), 0.0);
+    mode.update(&mut arena, &knockout);
+    assert_eq!(mode.get_objectives()[0], flags[0]);
+    assert_eq!(get_score(&mode, Scorer::Team(1)), 0);
+}
+
+#[test]
+fn test_free_for_all_has_no_winner() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    let killer = arena.add_player(Player::new("killer"));
+    tick(&mut arena, &mut mode);
+
+    let kill = [GameEvent::PlayerKilled { victim: EntityId::new(7, 0), killer: Some(killer) }];
+    for _ in 0..100 {
+        mode.update(&mut arena, &kill);
+    }
+
+    assert_eq!(get_score(&mode, Scorer::Player(killer)), 100);
+    assert_eq!(mode.get_winner(), None);
+    assert!(mode.get_objectives().is_empty());
+}
+
+#[test]
+fn test_mode_respawns_knocked_out_players() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    let id = arena.add_player(Player::new("faller"));
+    arena.get_mut_player(id).unwrap().update_position(Vec2::new(-100.0, 500.0), 0.0);
+
+    let events = tick(&mut arena, &mut mode);
+    assert!(events.contains(&GameEvent::PlayerKilled { victim: id, killer: None }));
+
+    let player = arena.get_player(id).unwrap();
+    assert!(!player.is_knocked_out());
+    assert!(!Arena::is_out_of_bounds(player.get_position()));
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use strum::EnumCount;
+use crate::arena::Arena;
+use crate::block::BlockType;
+use crate::entity::EntityId;
+use crate::event::GameEvent;
+use crate::hazard::{Hazard, HazardType, WindZone, LAVA_DAMAGE};
+use crate::map::{Map, VERTICAL_BLOCKS};
+use crate::player::Player;
+use crate::weapon::Bullet;
+use crate::weaponscatalog::BulletType;
+use crate::BLOCK_WIDTH;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// row 3 is filled with grass from columns 4 to 11, with spikes on column 6 and lava on
+/// column 8.
+fn create_test_map() -> Map {
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    for col in 4..=11 {
+        data[BlockType::GrassBlock as usize] |= 1 << (col * VERTICAL_BLOCKS + 3);
+    }
+
+    let mut map = Map::new(data.into()).unwrap();
+    map.add_hazard(Hazard::new(3, 6, HazardType::Spikes)).unwrap();
+    map.add_hazard(Hazard::new(3, 8, HazardType::Lava)).unwrap();
+    map
+}
+
+/// places a player standing on row 3 in the middle of column `col`.
+fn add_standing_player(arena: &mut Arena, col: usize) -> EntityId {
+    let id = arena.add_player(Player::new("standing"));
+    let position = arena.get_block_position_at(3, col);
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(position + Vec2::new((BLOCK_WIDTH - player.width) / 2.0, -player.height), 0.0);
+    player.set_velocity(Vec2::ZERO);
+    id
+}
+
+#[test]
+fn test_hazard_outside_of_map_rejected() {
+    let mut map = create_test_map();
+    assert!(map.add_hazard(Hazard::new(VERTICAL_BLOCKS, 0, HazardType::Lava)).is_err());
+
+    // replaces the spikes.
+    map.add_hazard(Hazard::new(3, 6, HazardType::Lava)).unwrap();
+    assert_eq!(map.get_hazards().len(), 2);
+}
+
+#[test]
+fn test_spikes_knock_out() {
+    let mut arena = Arena::new(create_test_map());
+    let id = add_standing_player(&mut arena, 6);
+
+    let events = arena.update(DT, &HashMap::new());
+    assert!(events.contains(&GameEvent::PlayerKilled { victim: id, killer: None }));
+    assert!(arena.get_player(id).unwrap().is_knocked_out());
+
+    // the player next to the spikes is safe.
+    let mut arena = Arena::new(create_test_map());
+    let id = add_standing_player(&mut arena, 5);
+    let events = arena.update(DT, &HashMap::new());
+    assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerKilled { .. })));
+    assert!(!arena.get_player(id).unwrap().is_knocked_out());
+}
+
+#[test]
+fn test_lava_damages_and_bounces() {
+    let mut arena = Arena::new(create_test_map());
+    let id = add_standing_player(&mut arena, 8);
+
+    let events = arena.update(DT, &HashMap::new());
+    let burned = GameEvent::HazardTouched { player: id, hazardtype: HazardType::Lava, damage: LAVA_DAMAGE };
+    assert!(events.contains(&burned));
+
+    // the player is only burned once while bouncing out of the lava.
+    for _ in 0..10 {
+        let events = arena.update(DT, &HashMap::new());
+        assert!(!events.contains(&burned));
+    }
+
+    let player = arena.get_player(id).unwrap();
+    assert_eq!(player.get_damage(), LAVA_DAMAGE);
+    assert!(player.get_position().y + player.height < Arena::get_block_row_position(3) - 20.0);
+}
+
+#[test]
+fn test_wind_pushes_players_and_bullets() {
+    let mut map = create_test_map();
+    let force = Vec2::new(20000.0, 0.0);
+    map.add_wind_zone(WindZone::new(Vec2::new(0.0, -2000.0), Vec2::new(800.0, 150.0), force));
+    let mut arena = Arena::new(map);
+
+    let inside = arena.add_player(Player::new("inside"));
+    let outside = arena.add_player(Player::new("outside"));
+    arena.get_mut_player(inside).unwrap().update_position(Vec2::new(400.0, -1000.0), 0.0);
+    arena.get_mut_player(outside).unwrap().update_position(Vec2::new(1200.0, -1000.0), 0.0);
+    let bullet = arena.add_bullet(Bullet::new(Vec2::new(400.0, 100.0), Vec2::ZERO, BulletType::Pistol, 0, outside));
+
+    for _ in 0..10 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    assert!(arena.get_player(inside).unwrap().get_velocity().x > 0.0);
+    assert_eq!(arena.get_player(outside).unwrap().get_velocity().x, 0.0);
+    assert!(arena.get_bullets().get(bullet).unwrap().get_position().x > 400.0);
+}
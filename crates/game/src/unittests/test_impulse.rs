@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use crate::arena::Arena;
+use crate::entity::EntityId;
+use crate::fixed;
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::{ARENA_WIDTH, PLAYER_HEIGHT, PLAYER_WIDTH, RECOIL_SCALING};
+use glam::Vec2;
+
+/// tick rates of the compared simulations, in Hz.
+const TICK_RATES: [u32; 3] = [30, 60, 120];
+
+/// simulation time after the impulse when the trajectories are compared, in s.
+const ELAPSED: f32 = 0.3;
+
+/// largest allowed difference between the trajectories, in pixels.
+const TOLERANCE: f32 = 8.0;
+
+/// waits two seconds, so the player lands on a block and the weapon is ready to fire.
+fn settle(arena: &mut Arena, dt: f32) {
+    let ticks = (2.0 / dt).round() as u32;
+    for _ in 0..ticks {
+        arena.update(dt, &HashMap::new());
+    }
+}
+
+/// presses `input` for a single tick at `hz`, then returns how far the player moved
+/// `ELAPSED` seconds after the input.
+fn displacement_after(arena: &mut Arena, id: EntityId, input: Input, hz: u32) -> Vec2 {
+    let dt = 1.0 / hz as f32;
+    let start = arena.get_player(id).unwrap().get_position();
+
+    let mut mask = InputMask::new();
+    mask.add_mask(input);
+    arena.update(dt, &HashMap::from([(id, mask)]));
+
+    let ticks = (ELAPSED * hz as f32).round() as u32;
+    for _ in 1..ticks {
+        arena.update(dt, &HashMap::new());
+    }
+
+    arena.get_player(id).unwrap().get_position() - start
+}
+
+fn assert_within_tolerance(displacements: &[Vec2]) {
+    for displacement in displacements {
+        let difference = (*displacement - displacements[0]).length();
+        assert!(difference < TOLERANCE, "{:?} differs by {}", displacements, difference);
+    }
+}
+
+#[test]
+fn test_jump_independent_of_tick_rate() {
+    let displacements: Vec<Vec2> = TICK_RATES.iter().map(|&hz| {
+        let mut arena = Arena::default();
+        let id = arena.add_player(Player::new("jumper"));
+        settle(&mut arena, 1.0 / hz as f32);
+        displacement_after(&mut arena, id, Input::Up, hz)
+    }).collect();
+
+    // the player left the ground.
+    assert!(displacements[0].y < -50.0);
+    assert_within_tolerance(&displacements);
+}
+
+#[test]
+fn test_recoil_independent_of_tick_rate() {
+    let displacements: Vec<Vec2> = TICK_RATES.iter().map(|&hz| {
+        let mut arena = Arena::default();
+        let id = arena.add_player(Player::new("shooter"));
+        settle(&mut arena, 1.0 / hz as f32);
+
+        // fires from high above the blocks, so nothing stops the player.
+        let player = arena.get_mut_player(id).unwrap();
+        player.update_position(Vec2::new((ARENA_WIDTH - PLAYER_WIDTH) / 2.0, -1000.0), 0.0);
+        player.set_velocity(Vec2::ZERO);
+        displacement_after(&mut arena, id, Input::Shoot, hz)
+    }).collect();
+
+    // the player faces right, so the recoil pushes it to the left, at a share of the momentum
+    // of the bullet.
+    let player = Player::new("shooter");
+    let recoil_velocity = RECOIL_SCALING * fixed::to_vec2(player.get_bullet_momentum()).x / player.get_total_mass();
+    let expected = -recoil_velocity * ELAPSED;
+    assert!(displacements[0].x < -5.0);
+    assert!((displacements[0].x - expected).abs() < 1.0, "{} != {}", displacements[0].x, expected);
+    assert_within_tolerance(&displacements);
+}
+
+#[test]
+fn test_explosion_pushes_players_away() {
+    let mut arena = Arena::default();
+    let left = arena.add_player(Player::new("left"));
+    let right = arena.add_player(Player::new("right"));
+    let far = arena.add_player(Player::new("far"));
+    let center = Vec2::new(500.0, 500.0);
+    let offset = Vec2::new(PLAYER_WIDTH, PLAYER_HEIGHT) / 2.0;
+    arena.get_mut_player(left).unwrap().update_position(center - Vec2::new(50.0, 0.0) - offset, 0.0);
+    arena.get_mut_player(right).unwrap().update_position(center + Vec2::new(100.0, 0.0) - offset, 0.0);
+    arena.get_mut_player(far).unwrap().update_position(center + Vec2::new(300.0, 0.0) - offset, 0.0);
+
+    let pushed = arena.add_explosion(center, 200.0, 1000.0, 0.0);
+    let mut expected = vec![left, right];
+    expected.sort_unstable();
+    assert_eq!(pushed, expected);
+
+    // the impulse falls off with the distance to the center.
+    let left_velocity = arena.get_player(left).unwrap().get_velocity();
+    let right_velocity = arena.get_player(right).unwrap().get_velocity();
+    assert!(left_velocity.x < 0.0 && right_velocity.x > 0.0);
+    assert!(left_velocity.x.abs() > right_velocity.x.abs());
+    assert_eq!(left_velocity.y, 0.0);
+    assert_eq!(arena.get_player(far).unwrap().get_velocity(), Vec2::ZERO);
+}
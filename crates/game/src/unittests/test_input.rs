@@ -51,3 +51,16 @@ fn test_inputmask_double_remove() {
 
     assert!(!inputmask.has_mask(Input::Up));
 }
+
+#[test]
+fn test_inputmask_reload() {
+    let mut inputmask = InputMask::new();
+    inputmask.add_mask(Input::Reload);
+    inputmask.add_mask(Input::Left);
+
+    let data: u32 = inputmask.into();
+    let inputmask = InputMask::from(data);
+    assert!(inputmask.has_mask(Input::Reload));
+    assert!(inputmask.has_mask(Input::Left));
+    assert!(!inputmask.has_mask(Input::Melee));
+}
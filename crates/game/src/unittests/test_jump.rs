@@ -0,0 +1,97 @@
+use crate::player::Player;
+use crate::JUMP_COOLDOWN;
+
+/// simulation time in ms of the given tick, starting after the jump cooldown.
+fn tick_time(tick: u128) -> u128 {
+    JUMP_COOLDOWN + 1 + tick * 16
+}
+
+#[test]
+fn test_jump_buffered_before_landing() {
+    let mut player = Player::new("buffer");
+    player.jumps_left = 0;
+
+    // jump pressed in the air without any jumps left.
+    player.add_jump_impulse(false, true, tick_time(0));
+    assert!(!player.has_jumped());
+
+    for tick in 1..player.jump_buffer_ticks as u128 {
+        player.add_jump_impulse(false, false, tick_time(tick));
+    }
+
+    // lands within the buffer window, so the jump goes through.
+    let landing = tick_time(player.jump_buffer_ticks as u128);
+    player.add_jump_impulse(true, false, landing);
+    assert!(player.has_jumped());
+}
+
+#[test]
+fn test_jump_buffer_expires() {
+    let mut player = Player::new("buffer");
+    player.jumps_left = 0;
+
+    player.add_jump_impulse(false, true, tick_time(0));
+    for tick in 1..=player.jump_buffer_ticks as u128 {
+        player.add_jump_impulse(false, false, tick_time(tick));
+    }
+
+    let landing = tick_time(player.jump_buffer_ticks as u128 + 1);
+    player.add_jump_impulse(true, false, landing);
+    assert!(!player.has_jumped());
+}
+
+#[test]
+fn test_no_buffer_only_jumps_on_input() {
+    let mut player = Player::new("no buffer");
+    player.jump_buffer_ticks = 0;
+    player.jumps_left = 0;
+
+    player.add_jump_impulse(false, true, tick_time(0));
+    player.add_jump_impulse(true, false, tick_time(1));
+    assert!(!player.has_jumped());
+}
+
+#[test]
+fn test_coyote_time_keeps_ground_jump() {
+    let mut player = Player::new("coyote");
+    let jumps = player.jumps_count;
+    player.add_jump_impulse(true, false, tick_time(0));
+
+    // walks off the edge, and jumps at the end of the coyote time.
+    let coyote = player.coyote_ticks as u128;
+    for tick in 1..coyote {
+        player.add_jump_impulse(false, false, tick_time(tick));
+    }
+
+    player.add_jump_impulse(false, true, tick_time(coyote));
+    assert!(player.has_jumped());
+    assert_eq!(player.jumps_left, jumps - 1);
+}
+
+#[test]
+fn test_ground_jump_lost_after_coyote_time() {
+    let mut player = Player::new("coyote");
+    let jumps = player.jumps_count;
+    player.add_jump_impulse(true, false, tick_time(0));
+
+    let coyote = player.coyote_ticks as u128;
+    for tick in 1..=coyote {
+        player.add_jump_impulse(false, false, tick_time(tick));
+    }
+
+    // only the mid-air jump is left.
+    player.add_jump_impulse(false, true, tick_time(coyote + 1));
+    assert!(player.has_jumped());
+    assert_eq!(player.jumps_left, jumps - 2);
+}
+
+#[test]
+fn test_jump_reported_for_its_tick_only() {
+    let mut player = Player::new("short ticks");
+    player.add_jump_impulse(true, true, tick_time(0));
+    assert!(player.has_jumped());
+
+    // a tick shorter than a millisecond happens at the same time, but is not a jump.
+    player.add_jump_impulse(false, false, tick_time(0));
+    assert!(!player.has_jumped());
+}
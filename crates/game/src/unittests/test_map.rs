@@ -85,6 +85,60 @@ mod tests {
         assert_eq!(map_from_file.to_string(), result.to_string());
     }
 
+    #[test]
+    fn test_serialize_deserialize_map_with_objects() {
+        use crate::hazard::{Hazard, HazardType};
+        use crate::teleporter::TeleporterPair;
+
+        let f = "test_map_with_objects";
+        let mut map = Map::default();
+        map.add_hazard(Hazard::new(1, 2, HazardType::Lava)).unwrap();
+        map.add_teleporter_pair(TeleporterPair::new((0, 0), (3, 15))).unwrap();
+        map.write_to_file(f).unwrap();
+
+        let map_from_file = Map::read_from_file(f).unwrap();
+        std::fs::remove_file(f).unwrap();
+
+        assert_eq!(map_from_file.to_string(), map.to_string());
+        assert_eq!(map_from_file.get_hazards().len(), 1);
+        assert_eq!(map_from_file.get_teleporter_pairs(), map.get_teleporter_pairs());
+    }
+
+    #[test]
+    fn test_deserialize_legacy_map() {
+        let f = "test_legacy_map";
+        let legacy: [i128; 2] = [0b0101, 0b1010];
+        std::fs::write(f, bincode::serialize(&legacy).unwrap()).unwrap();
+
+        let map_from_file = Map::read_from_file(f).unwrap();
+        std::fs::remove_file(f).unwrap();
+
+        let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+        data[..2].copy_from_slice(&legacy);
+        assert_eq!(map_from_file.to_string(), Map::new(data.into()).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_deserialize_hazard_outside_map_fail() {
+        use crate::hazard::{Hazard, HazardType};
+
+        let f = "test_hazard_outside_map";
+        let mut map = Map::default();
+        map.add_hazard(Hazard::new(1, 2, HazardType::Spikes)).unwrap();
+        map.write_to_file(f).unwrap();
+
+        // moves the hazard below the map: its row comes after the version, the blocks and the
+        // length of the hazards.
+        let mut bytes = std::fs::read(f).unwrap();
+        let row = 1 + BlockType::COUNT * std::mem::size_of::<i128>() + std::mem::size_of::<u64>();
+        bytes[row] = VERTICAL_BLOCKS as u8;
+        std::fs::write(f, bytes).unwrap();
+
+        let result = Map::read_from_file(f);
+        std::fs::remove_file(f).unwrap();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_deserialize_fail() {
         let f = "nonexistant_file";
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use strum::EnumCount;
+use crate::arena::Arena;
+use crate::block::BlockType;
+use crate::map::{Map, VERTICAL_BLOCKS};
+use crate::player::Player;
+use crate::query::{PlatformEdge, RayTarget};
+use crate::{BLOCK_HEIGHT, BLOCK_WIDTH, PLAYER_HEIGHT, PLAYER_WIDTH};
+
+/// a platform of grass on row 3 from columns 4 to 6, and a single stone block at row 1 of
+/// column 8.
+fn create_test_arena() -> Arena {
+    let mut data: [i128; BlockType::COUNT] = [0; BlockType::COUNT];
+    for col in 4..=6 {
+        data[BlockType::GrassBlock as usize] |= 1 << (col * VERTICAL_BLOCKS + 3);
+    }
+    data[BlockType::StoneBlock as usize] |= 1 << (8 * VERTICAL_BLOCKS + 1);
+    Arena::new(Map::new(data.into()).unwrap())
+}
+
+fn block_center(arena: &Arena, row: usize, col: usize) -> Vec2 {
+    arena.get_block_position_at(row, col) + Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT) / 2.0
+}
+
+#[test]
+fn test_raycast_hits_first_block() {
+    let arena = create_test_arena();
+    let target = block_center(&arena, 3, 5);
+    let origin = target - Vec2::new(0.0, 200.0);
+
+    let hit = arena.raycast(origin, Vec2::new(0.0, 1.0), 500.0, None).unwrap();
+    assert_eq!(hit.target, RayTarget::Block { row: 3, col: 5, blocktype: BlockType::GrassBlock });
+    assert!((hit.point.y - arena.get_block_position_at(3, 5).y).abs() < 1e-3);
+    assert!((hit.distance - (200.0 - BLOCK_HEIGHT / 2.0)).abs() < 1e-3);
+
+    // too short, or pointing away from the block.
+    assert_eq!(arena.raycast(origin, Vec2::new(0.0, 1.0), 100.0, None), None);
+    assert_eq!(arena.raycast(origin, Vec2::new(0.0, -1.0), 500.0, None), None);
+    assert_eq!(arena.raycast(origin, Vec2::ZERO, 500.0, None), None);
+}
+
+#[test]
+fn test_raycast_hits_players() {
+    let mut arena = create_test_arena();
+    let near = arena.add_player(Player::new("near"));
+    let far = arena.add_player(Player::new("far"));
+    let origin = Vec2::new(100.0, 100.0);
+    arena.get_mut_player(near).unwrap().update_position(origin + Vec2::new(100.0, -PLAYER_HEIGHT / 2.0), 0.0);
+    arena.get_mut_player(far).unwrap().update_position(origin + Vec2::new(300.0, -PLAYER_HEIGHT / 2.0), 0.0);
+
+    // the players are only found once the grid is rebuilt by an update.
+    assert_eq!(arena.raycast(origin, Vec2::X, 1000.0, None), None);
+    for id in [near, far] {
+        arena.get_mut_player(id).unwrap().set_velocity(Vec2::ZERO);
+    }
+    arena.update(0.0, &HashMap::new());
+
+    let hit = arena.raycast(origin, Vec2::X, 1000.0, None).unwrap();
+    assert_eq!(hit.target, RayTarget::Player(near));
+    assert!((hit.distance - 100.0).abs() < 1e-3);
+
+    let hit = arena.raycast(origin, Vec2::X, 1000.0, Some(near)).unwrap();
+    assert_eq!(hit.target, RayTarget::Player(far));
+}
+
+#[test]
+fn test_line_of_sight() {
+    let arena = create_test_arena();
+    let above = block_center(&arena, 3, 5) - Vec2::new(0.0, 80.0);
+    let below = block_center(&arena, 3, 5) + Vec2::new(0.0, 50.0);
+    let beside = above + Vec2::new(200.0, 0.0);
+
+    assert!(arena.has_line_of_sight(above, beside));
+    assert!(arena.has_line_of_sight(beside, above));
+    assert!(!arena.has_line_of_sight(above, below));
+    assert!(!arena.has_line_of_sight(below, above));
+
+    // the stone block stands between the two sides of column 8.
+    let stone = block_center(&arena, 1, 8);
+    assert!(!arena.has_line_of_sight(stone - Vec2::new(200.0, 0.0), stone + Vec2::new(200.0, 0.0)));
+}
+
+#[test]
+fn test_overlap_queries() {
+    let mut arena = create_test_arena();
+    let center = block_center(&arena, 3, 5);
+    assert_eq!(arena.query_blocks(center - Vec2::splat(10.0), center + Vec2::splat(10.0)), vec![(3, 5)]);
+    assert_eq!(arena.query_blocks(center - Vec2::new(BLOCK_WIDTH, 0.0), center), vec![(3, 4), (3, 5)]);
+    assert!(arena.query_blocks(Vec2::ZERO, Vec2::splat(100.0)).is_empty());
+
+    // the circle reaches the sides of the neighbouring blocks, but not their corners.
+    assert_eq!(arena.query_blocks_in_circle(center, BLOCK_WIDTH / 2.0 + 1.0), vec![(3, 4), (3, 5), (3, 6)]);
+    let corner = arena.get_block_position_at(3, 6) + Vec2::new(BLOCK_WIDTH + 5.0, -5.0);
+    assert!(arena.query_blocks_in_circle(corner, 6.0).is_empty());
+    assert_eq!(arena.query_blocks_in_circle(corner, 8.0), vec![(3, 6)]);
+
+    let id = arena.add_player(Player::new("corner"));
+    let player = arena.get_mut_player(id).unwrap();
+    player.update_position(Vec2::new(100.0, 100.0), 0.0);
+    player.set_velocity(Vec2::ZERO);
+    arena.update(0.0, &HashMap::new());
+    let bottomright = Vec2::new(100.0 + PLAYER_WIDTH, 100.0 + PLAYER_HEIGHT);
+    assert!(arena.query_players_in_circle(bottomright + Vec2::new(5.0, 5.0), 6.0).is_empty());
+    assert_eq!(arena.query_players_in_circle(bottomright + Vec2::new(5.0, 5.0), 8.0), vec![id]);
+}
+
+#[test]
+fn test_nearest_platform_edge() {
+    let arena = create_test_arena();
+    let left = arena.get_block_position_at(3, 4);
+    let right = arena.get_block_position_at(3, 6) + Vec2::new(BLOCK_WIDTH, 0.0);
+
+    let edge = arena.get_nearest_platform_edge(left + Vec2::new(10.0, -20.0)).unwrap();
+    assert_eq!(edge, PlatformEdge { row: 3, col: 4, point: left, side: -1.0 });
+    let edge = arena.get_nearest_platform_edge(right + Vec2::new(-10.0, -20.0)).unwrap();
+    assert_eq!(edge, PlatformEdge { row: 3, col: 6, point: right, side: 1.0 });
+
+    // a block alone on its row is a platform with two ends.
+    let stone = arena.get_block_position_at(1, 8);
+    let edge = arena.get_nearest_platform_edge(stone + Vec2::new(BLOCK_WIDTH - 1.0, 0.0)).unwrap();
+    assert_eq!((edge.row, edge.col, edge.side), (1, 8, 1.0));
+
+    let empty = Arena::new(Map::new([0; BlockType::COUNT].into()).unwrap());
+    assert_eq!(empty.get_nearest_platform_edge(left), None);
+}
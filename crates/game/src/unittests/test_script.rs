@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::path::Path;
+use glam::Vec2;
+use crate::arena::Arena;
+use crate::block::BlockRect;
+use crate::event::GameEvent;
+use crate::gamemode::{GameMode, GameModeType, Scorer};
+use crate::pickup::{PickupKind, PICKUP_SIZE};
+use crate::player::Player;
+use crate::script::{ModeScript, MAX_SCRIPT_PICKUPS};
+use crate::effect::EffectType;
+use crate::GRAVITY_DEFAULT;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// path of a script shipped in the resources.
+fn shipped_script(name: &str) -> String {
+    format!("{}/../../resources/scripts/{}.rhai", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn test_script_actions() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("scripted"));
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.update(&mut arena, &[]);
+    let mut script = ModeScript::new(r#"
+        fn on_tick(arena) {
+            this.ticks = (this.ticks ?? 0) + 1;
+            for player in arena.players {
+                arena.apply_impulse(player.id, 0.0, -1000.0);
+                arena.set_score(player.id, this.ticks * 10);
+            }
+            if this.ticks == 2 {
+                arena.spawn_effect(100.0, 200.0, "Shield");
+            }
+        }
+    "#).unwrap();
+
+    for ticks in 1..=2 {
+        arena.get_mut_player(id).unwrap().set_velocity(Vec2::ZERO);
+        script.update(&mut arena, &mut mode, &[], DT).unwrap();
+        assert!(arena.get_player(id).unwrap().get_velocity().y < 0.0);
+        assert_eq!(mode.get_scores(), vec![(Scorer::Player(id), ticks * 10)]);
+    }
+
+    let pickups: Vec<_> = arena.get_pickups().iter().map(|(_, pickup)| (pickup.get_position(), pickup.get_kind())).collect();
+    assert_eq!(pickups, vec![(Vec2::new(100.0, 200.0), PickupKind::Effect(EffectType::Shield))]);
+}
+
+#[test]
+fn test_script_sandbox() {
+    // no access to modules or eval.
+    assert!(ModeScript::new(r#"import "arena" as a;"#).is_err());
+    assert!(ModeScript::new(r#"eval("1 + 1");"#).is_err());
+
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+
+    // endless hooks are stopped.
+    let mut script = ModeScript::new("fn on_tick(arena) { loop { } }").unwrap();
+    assert!(script.update(&mut arena, &mut mode, &[], DT).is_err());
+
+    // unknown names and invalid ids are errors.
+    let mut script = ModeScript::new(r#"fn on_tick(arena) { arena.spawn_weapon(0.0, 0.0, "Railgun"); }"#).unwrap();
+    assert!(script.update(&mut arena, &mut mode, &[], DT).is_err());
+    let mut script = ModeScript::new("fn on_tick(arena) { arena.apply_impulse(-1, 0.0, 0.0); }").unwrap();
+    assert!(script.update(&mut arena, &mut mode, &[], DT).is_err());
+    assert_eq!(arena.get_pickups().len(), 0);
+}
+
+#[test]
+fn test_script_pickups_are_capped() {
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    let mut script = ModeScript::new(r#"
+        fn on_tick(arena) {
+            for i in 0..300 {
+                arena.spawn_effect(100.0, 200.0, "Shield");
+            }
+        }
+    "#).unwrap();
+
+    // the spawns past the limit are errors, and the ones before it are kept.
+    assert!(script.update(&mut arena, &mut mode, &[], DT).is_err());
+    assert_eq!(arena.get_pickups().len(), MAX_SCRIPT_PICKUPS);
+    assert!(script.update(&mut arena, &mut mode, &[], DT).is_err());
+    assert_eq!(arena.get_pickups().len(), MAX_SCRIPT_PICKUPS);
+}
+
+#[test]
+fn test_bounty_script() {
+    let mut arena = Arena::default();
+    let hunter = arena.add_player(Player::new("hunter"));
+    let streaker = arena.add_player(Player::new("streaker"));
+    let victim = arena.add_player(Player::new("victim"));
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.update(&mut arena, &[]);
+    let mut script = ModeScript::from_file(Path::new(&shipped_script("bounty"))).unwrap();
+
+    // two knockouts in a row put a bounty of two points on the streaker.
+    for _ in 0..2 {
+        let events = [GameEvent::PlayerKilled { victim, killer: Some(streaker) }];
+        script.update(&mut arena, &mut mode, &events, DT).unwrap();
+    }
+    let events = [GameEvent::Jumped { player: hunter, midair: false }, GameEvent::PlayerKilled { victim: streaker, killer: Some(hunter) }];
+    script.update(&mut arena, &mut mode, &events, DT).unwrap();
+
+    let expected = vec![(Scorer::Player(hunter), 3), (Scorer::Player(streaker), 2), (Scorer::Player(victim), 0)];
+    assert_eq!(mode.get_scores(), expected);
+}
+
+#[test]
+fn test_moon_script() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("astronaut"));
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    let mut script = ModeScript::from_file(Path::new(&shipped_script("moon"))).unwrap();
+
+    // half of the gravity of a tick is taken back.
+    arena.get_mut_player(id).unwrap().set_velocity(Vec2::ZERO);
+    script.update(&mut arena, &mut mode, &[], DT).unwrap();
+    let velocity = arena.get_player(id).unwrap().get_velocity();
+    assert!((velocity.y + 0.5 * GRAVITY_DEFAULT.y * DT).abs() < 1e-3);
+    assert_eq!(arena.get_pickups().len(), 0);
+
+    // a sniper rifle drops on top of a block after 15 seconds, with nobody around to take it.
+    arena.remove_player(id);
+    for _ in 0..15 * 60 {
+        let events = arena.update(DT, &HashMap::new());
+        script.update(&mut arena, &mut mode, &events, DT).unwrap();
+    }
+    let rifle = arena.get_pickups().iter()
+        .map(|(_, pickup)| pickup)
+        .find(|pickup| pickup.get_kind() == PickupKind::Weapon(crate::weaponscatalog::WeaponType::SniperRifle))
+        .expect("The rifle should have dropped");
+
+    let (min, max) = (rifle.get_position(), rifle.get_position() + Vec2::splat(PICKUP_SIZE));
+    let overlaps = |b: &BlockRect| min.x < b.x + b.w && b.x < max.x && min.y < b.y + b.h && b.y < max.y;
+    assert!(!arena.get_blocks_iter().any(|block| overlaps(&block)));
+    assert!(arena.get_blocks_iter().any(|block| (block.y - max.y).abs() < 1e-3));
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use crate::arena::Arena;
+use crate::gamemode::{GameMode, GameModeType};
+use crate::input::{Input, InputMask};
+use crate::player::Player;
+use crate::stats::MatchStats;
+use crate::weapon::Bullet;
+use crate::weaponscatalog::{BulletType, BULLET_DAMAGES};
+use glam::Vec2;
+
+const DT: f32 = 1.0 / 60.0;
+
+#[test]
+fn test_stats_record_hits_and_knockouts() {
+    let mut arena = Arena::default();
+    let attacker = arena.add_player(Player::new("attacker"));
+    let victim = arena.add_player(Player::new("victim"));
+    let faller = arena.add_player(Player::new("faller"));
+
+    let player = arena.get_mut_player(victim).unwrap();
+    player.update_position(Vec2::new(500.0, 500.0), 0.0);
+    let target = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+    let bullet = Bullet::new(target - Vec2::new(5.0, 0.0), Vec2::new(600.0, 0.0), BulletType::Pistol, 0, attacker);
+    arena.add_bullet(bullet);
+    arena.update(DT, &HashMap::new());
+
+    // the victim is knocked out after the hit, while the faller falls on its own.
+    arena.get_mut_player(victim).unwrap().update_position(Vec2::new(-100.0, 500.0), 0.0);
+    arena.get_mut_player(faller).unwrap().update_position(Vec2::new(-100.0, 500.0), 0.0);
+    arena.update(DT, &HashMap::new());
+
+    let damage = BULLET_DAMAGES[BulletType::Pistol as usize];
+    let stats = arena.get_stats();
+    let attacker_stats = stats.get(attacker).unwrap();
+    assert_eq!(attacker_stats.hits, 1);
+    assert_eq!(attacker_stats.knockouts, 1);
+    assert_eq!(attacker_stats.damage_dealt, damage);
+    assert_eq!(attacker_stats.deaths, 0);
+
+    let victim_stats = stats.get(victim).unwrap();
+    assert_eq!(victim_stats.name, "victim");
+    assert_eq!(victim_stats.damage_taken, damage);
+    assert_eq!(victim_stats.deaths, 1);
+    assert_eq!(victim_stats.self_destructs, 0);
+
+    let faller_stats = stats.get(faller).unwrap();
+    assert_eq!(faller_stats.deaths, 1);
+    assert_eq!(faller_stats.self_destructs, 1);
+}
+
+#[test]
+fn test_stats_record_shots_and_distance() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("shooter"));
+    let mut shoot = InputMask::new();
+    shoot.add_mask(Input::Shoot);
+    let inputs = HashMap::from([(id, shoot)]);
+
+    // waits for the weapon to reload.
+    for _ in 0..70 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let start = arena.get_player(id).unwrap().get_position();
+    let before = arena.get_stats().get(id).unwrap().distance_traveled;
+    arena.update(DT, &inputs);
+
+    let stats = arena.get_stats().get(id).unwrap();
+    assert_eq!(stats.shots_fired, 1);
+    assert_eq!(stats.accuracy, 0.0);
+
+    // the player spawns above the arena and falls straight down.
+    let end = arena.get_player(id).unwrap().get_position();
+    assert!(before > 0.0);
+    assert!((stats.distance_traveled - before - end.distance(start)).abs() < 1e-3);
+}
+
+#[test]
+fn test_stats_distance_skips_respawns() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("faller"));
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    for _ in 0..120 {
+        let events = arena.update(DT, &HashMap::new());
+        mode.update(&mut arena, &events);
+    }
+
+    // the player is knocked out off the side of the arena, and the mode respawns it.
+    arena.get_mut_player(id).unwrap().update_position(Vec2::new(-100.0, 500.0), 0.0);
+    let before = arena.get_stats().get(id).unwrap().distance_traveled;
+    for _ in 0..2 {
+        let events = arena.update(DT, &HashMap::new());
+        mode.update(&mut arena, &events);
+    }
+
+    // only the fall after the respawn counts, not the way from the knockout to the spawn.
+    assert_eq!(arena.get_stats().get(id).unwrap().deaths, 1);
+    let distance = arena.get_stats().get(id).unwrap().distance_traveled - before;
+    assert!(distance < 10.0, "{}", distance);
+}
+
+#[test]
+fn test_stats_json_round_trip() {
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("player"));
+    for _ in 0..30 {
+        arena.update(DT, &HashMap::new());
+    }
+
+    let json = arena.get_stats().to_json();
+    assert!(json.contains("\"distance_traveled\""));
+
+    let stats = MatchStats::from_json(&json).unwrap();
+    assert_eq!(stats.get(id), arena.get_stats().get(id));
+    assert_eq!(stats.get_duration(), arena.get_stats().get_duration());
+
+    arena.reset_stats();
+    assert_eq!(arena.get_stats().get(id), None);
+    assert_eq!(arena.get_stats().get_duration(), 0);
+}
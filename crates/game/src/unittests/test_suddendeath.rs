@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use crate::arena::Arena;
+use crate::event::GameEvent;
+use crate::gamemode::{GameMode, GameModeType, MATCH_TIME_LIMIT};
+use crate::map::{Map, HORIZONTAL_BLOCKS, VERTICAL_BLOCKS};
+use crate::player::Player;
+use crate::suddendeath::CollapseSchedule;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// updates the arena and then the mode, like the server does on every tick.
+fn tick(arena: &mut Arena, mode: &mut GameMode) -> Vec<GameEvent> {
+    let events = arena.update(DT, &HashMap::new());
+    mode.update(arena, &events);
+    events
+}
+
+/// runs the match for `seconds` of simulated time.
+fn run(arena: &mut Arena, mode: &mut GameMode, seconds: f32) {
+    for _ in 0..(seconds / DT).round() as usize {
+        tick(arena, mode);
+    }
+}
+
+/// a full default map collapsing every second, down to 4 columns and 3 rows.
+fn create_collapsing_arena() -> Arena {
+    let mut map = Map::default();
+    map.set_collapse_schedule(CollapseSchedule::new(1000, 500, 4, 3).unwrap());
+    Arena::new(map)
+}
+
+#[test]
+fn test_invalid_schedule_rejected() {
+    assert!(CollapseSchedule::new(1000, 2000, 4, 3).is_err());
+    assert!(CollapseSchedule::new(1000, 500, 0, 3).is_err());
+    assert!(CollapseSchedule::new(1000, 500, 4, VERTICAL_BLOCKS + 1).is_err());
+    assert!(CollapseSchedule::new(1000, 1000, HORIZONTAL_BLOCKS, 1).is_ok());
+}
+
+#[test]
+fn test_free_for_all_has_no_time_limit() {
+    let arena = Arena::default();
+    assert_eq!(GameMode::new(GameModeType::FreeForAll, &arena).get_time_limit(), None);
+    assert_eq!(GameMode::new(GameModeType::KingOfTheHill, &arena).get_time_limit(), Some(MATCH_TIME_LIMIT));
+}
+
+#[test]
+fn test_sudden_death_needs_several_players_alive() {
+    let mut arena = create_collapsing_arena();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.set_time_limit(Some(1000));
+    arena.add_player(Player::new("alone"));
+
+    run(&mut arena, &mut mode, 0.5);
+    assert!(!mode.is_over(arena.get_time()));
+    run(&mut arena, &mut mode, 1.5);
+    assert!(!mode.is_sudden_death());
+    assert!(mode.is_over(arena.get_time()));
+
+    arena.add_player(Player::new("challenger"));
+    tick(&mut arena, &mut mode);
+    assert!(mode.is_sudden_death());
+    assert!(!mode.is_over(arena.get_time()));
+
+    // no time limit, no sudden death.
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.set_time_limit(None);
+    run(&mut arena, &mut mode, 2.0);
+    assert!(!mode.is_sudden_death());
+}
+
+#[test]
+fn test_map_collapses_to_center() {
+    let mut arena = create_collapsing_arena();
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.set_time_limit(Some(0));
+    arena.add_player(Player::new("first"));
+    arena.add_player(Player::new("second"));
+    tick(&mut arena, &mut mode);
+    assert!(mode.is_sudden_death());
+
+    // the outermost columns and the bottom row are announced half a second before collapsing.
+    run(&mut arena, &mut mode, 0.4);
+    assert!(mode.get_collapsing_cells().is_empty());
+    run(&mut arena, &mut mode, 0.2);
+    let collapsing = mode.get_collapsing_cells();
+    assert_eq!(collapsing.len(), 2 * VERTICAL_BLOCKS + HORIZONTAL_BLOCKS - 2);
+    assert!(collapsing.contains(&(0, 0)) && collapsing.contains(&(3, HORIZONTAL_BLOCKS - 1)));
+    assert!(collapsing.contains(&(VERTICAL_BLOCKS - 1, 7)));
+
+    let mut events = Vec::new();
+    for _ in 0..30 {
+        events.extend(tick(&mut arena, &mut mode));
+    }
+    for (row, col) in collapsing {
+        assert_eq!(arena.get_block(row, col), None);
+        assert!(events.contains(&GameEvent::BlockChanged { row, col, blocktype: None }));
+    }
+
+    // only the center is left in the end.
+    assert!(!mode.is_over(arena.get_time()));
+    run(&mut arena, &mut mode, 10.0);
+    assert!(mode.is_over(arena.get_time()));
+    assert!(mode.get_collapsing_cells().is_empty());
+    assert_eq!(arena.get_blocks_iter().count(), 4 * 3);
+    for col in 6..10 {
+        for row in 0..3 {
+            assert!(arena.get_block(row, col).is_some());
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use strum::EnumCount;
+use crate::arena::Arena;
+use crate::block::BlockType;
+use crate::event::GameEvent;
+use crate::map::{Map, HORIZONTAL_BLOCKS};
+use crate::player::Player;
+use crate::query;
+use crate::teleporter::{self, TeleporterPair, TELEPORT_COOLDOWN};
+use crate::weapon::Bullet;
+use crate::weaponscatalog::BulletType;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// an empty map with teleporters on row 3 of columns 2 and 12.
+fn create_test_arena() -> Arena {
+    let mut map = Map::new([0; BlockType::COUNT].into()).unwrap();
+    map.add_teleporter_pair(TeleporterPair::new((3, 2), (3, 12))).unwrap();
+    Arena::new(map)
+}
+
+fn get_center(cell: (usize, usize)) -> Vec2 {
+    let (min, max) = teleporter::get_teleporter_rect(cell);
+    (min + max) / 2.0
+}
+
+#[test]
+fn test_invalid_pairs_rejected() {
+    let mut map = create_test_arena().get_map().clone();
+    assert!(map.add_teleporter_pair(TeleporterPair::new((1, 1), (1, 1))).is_err());
+    assert!(map.add_teleporter_pair(TeleporterPair::new((1, 1), (1, HORIZONTAL_BLOCKS))).is_err());
+    assert!(map.add_teleporter_pair(TeleporterPair::new((1, 1), (3, 12))).is_err());
+    assert!(map.add_teleporter_pair(TeleporterPair::new((1, 1), (2, 12))).is_ok());
+}
+
+#[test]
+fn test_sweep_segment() {
+    let (min, max) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 10.0));
+    let t = query::sweep_segment(Vec2::new(-10.0, 5.0), Vec2::new(30.0, 5.0), min, max).unwrap();
+    assert!((t - 0.25).abs() < 1e-6);
+
+    // misses, stops short, or starts inside.
+    assert_eq!(query::sweep_segment(Vec2::new(-10.0, 20.0), Vec2::new(30.0, 20.0), min, max), None);
+    assert_eq!(query::sweep_segment(Vec2::new(-10.0, 5.0), Vec2::new(-1.0, 5.0), min, max), None);
+    assert_eq!(query::sweep_segment(Vec2::new(5.0, 5.0), Vec2::new(30.0, 5.0), min, max), None);
+}
+
+#[test]
+fn test_player_comes_out_of_partner() {
+    let mut arena = create_test_arena();
+    let id = arena.add_player(Player::new("traveller"));
+    let entrance = get_center((3, 2));
+    let player = arena.get_mut_player(id).unwrap();
+    let half_size = Vec2::new(player.width, player.height) / 2.0;
+    player.update_position(entrance - half_size - Vec2::new(200.0, 0.0), 0.0);
+    player.set_velocity(Vec2::new(600.0, 0.0));
+
+    let mut teleported = None;
+    for _ in 0..30 {
+        let events = arena.update(DT, &HashMap::new());
+        let velocity = arena.get_player(id).unwrap().get_velocity();
+        if events.contains(&GameEvent::PlayerTeleported { player: id, from: (3, 2), to: (3, 12) }) {
+            teleported = Some(velocity);
+            break;
+        }
+    }
+
+    // the player keeps moving to the right, out of the partner teleporter.
+    let velocity = teleported.expect("Player should teleport");
+    assert!(velocity.x > 500.0);
+    let player = arena.get_player(id).unwrap();
+    assert!((player.get_position() + half_size - get_center((3, 12))).length() < 1e-3);
+
+    // the player does not teleport again while walking out of the partner.
+    for _ in 0..(TELEPORT_COOLDOWN as f32 / 1000.0 / DT) as usize {
+        let events = arena.update(DT, &HashMap::new());
+        assert!(!events.iter().any(|event| matches!(event, GameEvent::PlayerTeleported { .. })));
+    }
+}
+
+#[test]
+fn test_fast_bullet_cannot_skip_teleporter() {
+    let mut arena = create_test_arena();
+    let owner = arena.add_player(Player::new("shooter"));
+
+    // the bullet moves 200 pixels per tick, and is never inside of the teleporter at the end of a
+    // tick.
+    let entrance = get_center((3, 2));
+    let start = entrance - Vec2::new(100.0, 0.0);
+    let bullet = arena.add_bullet(Bullet::new(start, Vec2::new(200.0 / DT, 0.0), BulletType::Sniper, 0, owner));
+    arena.update(DT, &HashMap::new());
+
+    let bullet = arena.get_bullets().get(bullet).expect("Bullet should be in the arena");
+    assert_eq!(bullet.get_position(), get_center((3, 12)));
+    assert_eq!(bullet.get_velocity(), Vec2::new(200.0 / DT, 0.0));
+}
@@ -0,0 +1,103 @@
+use glam::Vec2;
+use crate::player::Player;
+use crate::weapon::{Weapon, WeaponStatus};
+use crate::weaponscatalog::{WeaponType, ATTACK_TIMES, MAGAZINE_SIZES, RELOAD_TIMES, RESERVE_AMMO};
+
+/// time in ms after which a new weapon of the type is ready to fire.
+fn ready_time(weapontype: WeaponType) -> u128 {
+    RELOAD_TIMES[weapontype as usize] + 1
+}
+
+/// fires the weapon until its magazine is empty. Returns the time of the last shot.
+fn empty_magazine(weapon: &mut Weapon, mut curr_time: u128) -> u128 {
+    let attack_time = ATTACK_TIMES[weapon.get_weapon_type() as usize] + 1;
+    while weapon.get_magazine() > 0 {
+        assert_eq!(weapon.attack(curr_time), WeaponStatus::FireSuccess);
+        curr_time += attack_time;
+    }
+    curr_time - attack_time
+}
+
+#[test]
+fn test_manual_reload() {
+    let weapontype = WeaponType::BasicPistol;
+    let mut weapon = Weapon::new(Vec2::ZERO, weapontype, 1.0, 0);
+    let curr_time = ready_time(weapontype);
+
+    // a full magazine cannot be reloaded.
+    assert!(!weapon.start_reload(curr_time));
+
+    assert_eq!(weapon.attack(curr_time), WeaponStatus::FireSuccess);
+    assert!(weapon.start_reload(curr_time));
+    assert!(weapon.is_reloading(curr_time));
+    assert_eq!(weapon.get_reload_progress(curr_time), 0.0);
+    assert_eq!(weapon.attack(curr_time + 1), WeaponStatus::Cooldown);
+
+    let half = curr_time + RELOAD_TIMES[weapontype as usize] / 2;
+    assert!((weapon.get_reload_progress(half) - 0.5).abs() < 0.01);
+
+    let done = curr_time + ready_time(weapontype);
+    weapon.finish_reload(done);
+    assert_eq!(weapon.get_reload_progress(done), 1.0);
+    assert_eq!(weapon.get_magazine(), MAGAZINE_SIZES[weapontype as usize]);
+    assert_eq!(weapon.get_reserve(), RESERVE_AMMO[weapontype as usize] - 1);
+}
+
+#[test]
+fn test_auto_reload_when_magazine_empties() {
+    let weapontype = WeaponType::BasicPistol;
+    let mut player = Player::new("shooter");
+    let mut curr_time = ready_time(weapontype);
+
+    for _ in 0..MAGAZINE_SIZES[weapontype as usize] {
+        assert_eq!(player.attack(curr_time), WeaponStatus::FireSuccess);
+        curr_time += ATTACK_TIMES[weapontype as usize] + 1;
+    }
+
+    assert!(player.is_reloading(curr_time));
+    assert_eq!(player.attack(curr_time), WeaponStatus::Cooldown);
+
+    curr_time += RELOAD_TIMES[weapontype as usize];
+    player.reload(false, curr_time);
+    assert_eq!(player.get_weapon().get_magazine(), MAGAZINE_SIZES[weapontype as usize]);
+    assert_eq!(player.attack(curr_time), WeaponStatus::FireSuccess);
+}
+
+#[test]
+fn test_weapon_discarded_when_empty() {
+    let weapontype = WeaponType::SniperRifle;
+    let mut player = Player::new("sniper");
+    player.equip_weapon(weapontype, 0);
+    let mut curr_time = ready_time(weapontype);
+
+    for _ in 0..MAGAZINE_SIZES[weapontype as usize] {
+        assert_eq!(player.attack(curr_time), WeaponStatus::FireSuccess);
+        curr_time += ATTACK_TIMES[weapontype as usize] + 1;
+    }
+
+    // the sniper rifle still has bullets in reserve, but is thrown away anyway.
+    assert!(!player.is_reloading(curr_time));
+    assert_eq!(player.attack(curr_time), WeaponStatus::Empty);
+    let thrown = player.take_thrown_weapon().unwrap();
+    assert_eq!(thrown.get_weapon_type(), weapontype);
+    assert_eq!(thrown.get_reserve(), RESERVE_AMMO[weapontype as usize]);
+    assert_ne!(player.get_weapon().get_weapon_type(), weapontype);
+}
+
+#[test]
+fn test_weapon_discarded_without_reserve() {
+    let weapontype = WeaponType::BasicPistol;
+    let mut weapon = Weapon::new(Vec2::ZERO, weapontype, 1.0, 0);
+    let mut curr_time = ready_time(weapontype);
+
+    while weapon.get_reserve() > 0 || weapon.get_magazine() > 0 {
+        curr_time = empty_magazine(&mut weapon, curr_time) + ATTACK_TIMES[weapontype as usize] + 1;
+        if weapon.get_reserve() > 0 {
+            assert_eq!(weapon.attack(curr_time), WeaponStatus::Cooldown);
+            curr_time += ready_time(weapontype);
+            weapon.finish_reload(curr_time);
+        }
+    }
+
+    assert_eq!(weapon.attack(curr_time), WeaponStatus::Empty);
+}
@@ -1,8 +1,11 @@
-use crate::weaponscatalog::{ATTACK_TIMES, BULLET_TYPES, DEFAULT_BULLET_MASSES, BulletType, BULLET_SPEEDS, RELOAD_TIMES, WeaponType};
+use crate::weaponscatalog::{ATTACK_TIMES, BULLET_DAMAGES, BULLET_TYPES, DEFAULT_BULLET_MASSES, BulletType, BULLET_SPEEDS, RELOAD_TIMES, WeaponType};
 use core::fmt::Debug;
-use std::time::SystemTime;
 use glam::Vec2;
-use crate::weaponscatalog::{DEFAULT_BULLET_COUNTS, DEFAULT_MASSES};
+use crate::entity::EntityId;
+use crate::fixed::{self, RealVec2};
+use crate::GRAVITY_DEFAULT;
+use crate::teleporter::TELEPORT_COOLDOWN;
+use crate::weaponscatalog::{DEFAULT_MASSES, DISCARD_WHEN_EMPTY, MAGAZINE_SIZES, RESERVE_AMMO};
 use WeaponStatus::*;
 
 /// contains the various implementations for all the weapons and bullets
@@ -13,16 +16,23 @@ use WeaponStatus::*;
 /// not having trait fields.
 #[derive(Debug)]
 pub struct Bullet {
-    position: Vec2,
-    velocity: Vec2,
+    position: RealVec2,
+    velocity: RealVec2,
     bullettype: BulletType,
     team: u8,
-    id: u16,
+    owner: EntityId,
+    /// time at which the teleport cooldown runs out.
+    teleport_ready_at: u128,
 }
 
 impl Bullet {
-    pub fn new(position: Vec2, velocity: Vec2, bullettype: BulletType, team: u8, id: u16) -> Self {
-        Self { position, velocity, bullettype, team, id }
+    pub fn new(position: Vec2, velocity: Vec2, bullettype: BulletType, team: u8, owner: EntityId) -> Self {
+        Self::from_movement(fixed::real_vec2(position), fixed::real_vec2(velocity), bullettype, team, owner)
+    }
+
+    /// creates a bullet from a position and velocity that are already `RealVec2`.
+    pub(crate) fn from_movement(position: RealVec2, velocity: RealVec2, bullettype: BulletType, team: u8, owner: EntityId) -> Self {
+        Self { position, velocity, bullettype, team, owner, teleport_ready_at: 0 }
     }
 
     pub fn get_mass(&self) -> f32 {
@@ -33,39 +43,81 @@ impl Bullet {
         self.team
     }
 
-    pub fn get_id(&self) -> u16 {
-        self.id
+    /// id of the player who fired the bullet.
+    pub fn get_owner(&self) -> EntityId {
+        self.owner
     }
 
     pub fn get_position(&self) -> Vec2 {
-        self.position
+        fixed::to_vec2(self.position)
+    }
+
+    pub fn get_velocity(&self) -> Vec2 {
+        fixed::to_vec2(self.velocity)
+    }
+
+    /// obtains the position and velocity of the bullet as they are moved.
+    pub(crate) fn get_movement(&self) -> (RealVec2, RealVec2) {
+        (self.position, self.velocity)
+    }
+
+    /// obtains the damage (in percent) the bullet adds to a player it hits.
+    pub fn get_damage(&self) -> f32 {
+        BULLET_DAMAGES[self.bullettype as usize]
+    }
+
+    /// obtains the momentum the bullet transfers to a player it hits.
+    pub(crate) fn get_momentum(&self) -> RealVec2 {
+        fixed::real(self.get_mass()) * self.velocity
     }
 
     pub fn update(&mut self, dt: f32) {
-        self.position += self.velocity * dt;
+        (self.position, _) = fixed::integrate(self.position, self.velocity, RealVec2::ZERO, fixed::real(dt));
+    }
+
+    /// pushes the bullet with a constant `force` (e.g. the wind) for `dt` seconds.
+    pub(crate) fn add_force(&mut self, force: RealVec2, dt: f32) {
+        self.velocity += force / fixed::real(self.get_mass()) * fixed::real(dt);
     }
 
     pub fn get_bullet_type(&self) -> BulletType {
         self.bullettype
     }
+
+    /// whether the bullet is done with the cooldown of its last teleport.
+    pub(crate) fn can_teleport(&self, curr_time: u128) -> bool {
+        curr_time >= self.teleport_ready_at
+    }
+
+    /// moves the bullet to `position` through a teleporter, keeping its velocity.
+    pub(crate) fn teleport(&mut self, position: RealVec2, curr_time: u128) {
+        self.position = position;
+        self.teleport_ready_at = curr_time + TELEPORT_COOLDOWN;
+    }
 }
 
 
 /// The weapon "superstruct" as a workaround for rust
 /// not having trait fields.
 ///
-/// direction assumes a unit vector.
+/// `aim` is the unit vector the weapon is pointed at.
 /// Velocity does not matter until the weapon is discarded.
+///
+/// The bullets are fired from the magazine, which is refilled from the reserve by reloading.
+/// A new weapon also needs the reload time before it can fire.
 #[derive(Debug)]
 pub struct Weapon {
-    pub(crate) bullets: u8,
+    pub(crate) magazine: u8,
+    reserve: u16,
     pub(crate) weapontype: WeaponType,
     status: WeaponStatus,
-    position: Vec2,
-    velocity: Vec2,
-    direction: f32,
+    position: RealVec2,
+    velocity: RealVec2,
+    aim: Vec2,
     last_attack_time: u128,
     reload_started_time: u128,
+    /// whether bullets move from the reserve to the magazine when the reload finishes.
+    reload_pending: bool,
 }
 
 /// status of the weapon. Should only update when the attack function is called.
@@ -79,54 +131,59 @@ pub enum WeaponStatus {
 }
 
 impl Weapon {
-    pub fn new(position: Vec2, weapontype: WeaponType, direction: f32) -> Self {
-        let velocity = Vec2::ZERO;
+    /// creates a new weapon pointed horizontally in `direction` (left=-1.0 or right=+1.0).
+    /// `curr_time` is the simulation time of the arena in milliseconds, and is used as the
+    /// start of the weapon's reload.
+    pub fn new(position: Vec2, weapontype: WeaponType, direction: f32, curr_time: u128) -> Self {
+        let position = fixed::real_vec2(position);
+        let velocity = RealVec2::ZERO;
+        let aim = Vec2::new(direction, 0.0);
         let i = weapontype as usize;
-        let bullets = DEFAULT_BULLET_COUNTS[i];
-        let curr_time = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Unable to get current time!")
-            .as_millis();
-
+        let magazine = MAGAZINE_SIZES[i];
+        let reserve = RESERVE_AMMO[i];
         let last_attack_time = curr_time;
         let reload_started_time = curr_time;
+        let reload_pending = false;
         let status = Cooldown;
 
         Self {
             position,
             velocity,
             status,
-            bullets,
+            magazine,
+            reserve,
             weapontype,
-            direction,
+            aim,
             last_attack_time,
             reload_started_time,
+            reload_pending,
         }
     }
 
     /// calls the specific attack function for a weapon of `self.weapontype` only if the
     /// attack has passed the attack cooldown timer, and the gun already reloaded.
     ///
-    /// If the attack was successfully executed, then attack returns FireSuccess, otherwise, 
-    /// it will return WeaponStatus::Empty if there is no more bullets left, but the player can 
-    /// attack, or WeaponStatus::Cooldown if the player cannot attack due to cooldown.
-    pub(crate) fn attack(&mut self) -> WeaponStatus {
+    /// If the attack was successfully executed, then attack returns FireSuccess. If the magazine
+    /// is empty, the weapon starts reloading and returns Cooldown, unless the weapon is thrown
+    /// away when empty or has no reserve left, in which case it returns Empty. Otherwise, returns
+    /// Cooldown if the player cannot attack due to cooldown.
+    ///
+    /// `currtime` is the simulation time of the arena in milliseconds.
+    pub(crate) fn attack(&mut self, currtime: u128) -> WeaponStatus {
         let i = self.weapontype as usize;
+        self.finish_reload(currtime);
 
-        let currtime = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Unable to get current time!")
-            .as_millis();
-
-        let reloaded_check = currtime - self.reload_started_time > RELOAD_TIMES[i];
-        let attack_cooldown_check = currtime - self.last_attack_time > ATTACK_TIMES[i];
+        let reloaded_check = !self.is_reloading(currtime);
+        let attack_cooldown_check = currtime.saturating_sub(self.last_attack_time) > ATTACK_TIMES[i];
         let can_attack = reloaded_check && attack_cooldown_check;
 
-        if can_attack && self.bullets > 0 {
+        if can_attack && self.magazine > 0 {
             self.last_attack_time = currtime;
-            self.bullets -= 1;
+            self.magazine -= 1;
             self.status = Cooldown;
             WeaponStatus::FireSuccess
+        } else if can_attack && !self.discards_when_empty() && self.start_reload(currtime) {
+            WeaponStatus::Cooldown
         } else if can_attack {
             self.status = Empty;
             WeaponStatus::Empty
@@ -135,36 +192,127 @@ impl Weapon {
         }
     }
 
+    /// starts reloading the magazine from the reserve. Does nothing (and returns false) if the
+    /// weapon is already reloading, the magazine is full or the reserve is empty.
+    pub(crate) fn start_reload(&mut self, currtime: u128) -> bool {
+        let can_reload = !self.is_reloading(currtime)
+            && self.magazine < MAGAZINE_SIZES[self.weapontype as usize]
+            && self.reserve > 0;
+
+        if can_reload {
+            self.reload_started_time = currtime;
+            self.reload_pending = true;
+        }
+        can_reload
+    }
+
+    /// refills the magazine from the reserve if the reload finished by `currtime`.
+    pub(crate) fn finish_reload(&mut self, currtime: u128) {
+        if self.reload_pending && !self.is_reloading(currtime) {
+            let missing = MAGAZINE_SIZES[self.weapontype as usize] - self.magazine;
+            let refill = self.reserve.min(missing.into());
+            self.reserve -= refill;
+            self.magazine += refill as u8;
+            self.reload_pending = false;
+        }
+    }
+
+    /// whether the weapon is still reloading at `currtime`.
+    pub(crate) fn is_reloading(&self, currtime: u128) -> bool {
+        let i = self.weapontype as usize;
+        currtime.saturating_sub(self.reload_started_time) <= RELOAD_TIMES[i]
+    }
+
+    /// obtains how far along the reload is at `currtime`, from 0.0 (just started) to 1.0
+    /// (ready to fire).
+    pub fn get_reload_progress(&self, currtime: u128) -> f32 {
+        let elapsed = currtime.saturating_sub(self.reload_started_time) as f32;
+        (elapsed / RELOAD_TIMES[self.weapontype as usize] as f32).min(1.0)
+    }
+
+    /// whether no bullet was fired or reloaded from the weapon yet.
+    pub(crate) fn is_unused(&self) -> bool {
+        let i = self.weapontype as usize;
+        self.magazine == MAGAZINE_SIZES[i] && self.reserve == RESERVE_AMMO[i] && !self.reload_pending
+    }
+
+    /// whether the weapon is thrown away as soon as its magazine is empty.
+    pub fn discards_when_empty(&self) -> bool {
+        DISCARD_WHEN_EMPTY[self.weapontype as usize]
+    }
+
+    /// obtains the number of bullets left in the magazine.
+    pub fn get_magazine(&self) -> u8 {
+        self.magazine
+    }
+
+    /// obtains the number of bullets left in reserve.
+    pub fn get_reserve(&self) -> u16 {
+        self.reserve
+    }
+
     /// obtains the mass of the weapon.
     pub(crate) fn get_mass(&self) -> f32 {
         DEFAULT_MASSES[self.weapontype as usize]
     }
 
     /// throws the weapon.
-    pub(crate) fn discard(&mut self, velocity: Vec2) {
+    pub(crate) fn discard(&mut self, velocity: RealVec2) {
         self.status = Discarded;
         self.velocity = velocity;
     }
 
+    /// moves a thrown weapon, which falls under gravity.
+    pub(crate) fn update(&mut self, dt: f32) {
+        (self.position, self.velocity) = fixed::integrate(self.position, self.velocity, fixed::real_vec2(GRAVITY_DEFAULT), fixed::real(dt));
+    }
+
+    pub fn get_position(&self) -> Vec2 {
+        fixed::to_vec2(self.position)
+    }
+
+    pub fn get_velocity(&self) -> Vec2 {
+        fixed::to_vec2(self.velocity)
+    }
+
+    /// obtains the position and velocity of the weapon as they are moved.
+    pub(crate) fn get_movement(&self) -> (RealVec2, RealVec2) {
+        (self.position, self.velocity)
+    }
+
+    /// obtains the unit vector the weapon is pointed at.
+    pub fn get_aim(&self) -> Vec2 {
+        self.aim
+    }
+
+    pub fn get_weapon_type(&self) -> WeaponType {
+        self.weapontype
+    }
+
     /// sets the position of the weapon the player is holding.
     pub fn set_position(&mut self, position: Vec2) {
+        self.move_to(fixed::real_vec2(position));
+    }
+
+    /// moves the weapon to `position`, like `set_position` without leaving the movement.
+    pub(crate) fn move_to(&mut self, position: RealVec2) {
         self.position = position;
     }
 
-    /// sets the direction the weapon is facing.
-    pub fn set_direction(&mut self, direction: f32) {
-        self.direction = direction;
+    /// points the weapon at `aim`, which is a unit vector.
+    pub fn set_aim(&mut self, aim: Vec2) {
+        self.aim = aim;
     }
 
     pub fn get_bullet_type(&self) -> BulletType {
         BULLET_TYPES[self.weapontype as usize]
     }
 
-    pub(crate) fn get_bullet_momentum(&self) -> Vec2 {
+    pub(crate) fn get_bullet_momentum(&self) -> RealVec2 {
         let bullettype = self.get_bullet_type();
         let bulletspeed = self.get_bullet_speed();
         let bulletmass = DEFAULT_BULLET_MASSES[bullettype as usize];
-        self.direction * Vec2::X * bulletspeed * bulletmass
+        fixed::real_vec2(self.aim * bulletspeed * bulletmass)
     }
 
     pub(crate) fn get_bullet_speed(&self) -> f32 {
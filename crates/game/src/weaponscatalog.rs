@@ -8,15 +8,17 @@ use WeaponType::*;
 use BulletType::*;
 
 /// Type of the weapon
-#[derive(Debug, Clone, EnumCount, Copy)]
+#[derive(Debug, Clone, EnumCount, Copy, PartialEq, Eq, FromRepr)]
 pub enum WeaponType {
     BasicPistol,
+    AssaultRifle,
+    SniperRifle,
 }
 
 /// Type of the bullets fired from various weapons.
 /// README: maybe implement bullet "attacks" types so we can have different implementations
 /// (e.g. exploding missile).
-#[derive(Debug, Clone, EnumCount, Copy, FromRepr)]
+#[derive(Debug, Clone, EnumCount, Copy, PartialEq, Eq, FromRepr)]
 pub enum BulletType {
     Pistol,
     Rifle,
@@ -33,13 +35,43 @@ pub(crate) const DEFAULT_BULLET_MASSES: [f32; BulletType::COUNT] = {
     bullet_masses
 };
 
+/// damage (in percent) added to a player hit by each type of bullet.
+pub const BULLET_DAMAGES: [f32; BulletType::COUNT] = {
+    let mut bullet_damages = [0.0; BulletType::COUNT];
 
-/// number of initial bullets of each gun
-pub(crate) const DEFAULT_BULLET_COUNTS: [u8; WeaponType::COUNT] = {
-    let mut bulletcounts: [u8; WeaponType::COUNT] = [0; WeaponType::COUNT];
+    bullet_damages[Pistol as usize] = 6.0;
+    bullet_damages[Rifle as usize] = 4.0;
+    bullet_damages[Sniper as usize] = 18.0;
+    bullet_damages
+};
+
+/// number of bullets in a full magazine of each gun
+pub(crate) const MAGAZINE_SIZES: [u8; WeaponType::COUNT] = {
+    let mut sizes: [u8; WeaponType::COUNT] = [0; WeaponType::COUNT];
+
+    sizes[BasicPistol as usize] = 8;
+    sizes[AssaultRifle as usize] = 30;
+    sizes[SniperRifle as usize] = 3;
+    sizes
+};
+
+/// number of bullets in reserve (outside of the magazine) of a new gun
+pub(crate) const RESERVE_AMMO: [u16; WeaponType::COUNT] = {
+    let mut reserves: [u16; WeaponType::COUNT] = [0; WeaponType::COUNT];
+
+    reserves[BasicPistol as usize] = 24;
+    reserves[AssaultRifle as usize] = 60;
+    reserves[SniperRifle as usize] = 3;
+    reserves
+};
+
+/// whether each gun is thrown away as soon as its magazine is empty, instead of being
+/// reloaded from the reserve. Guns are always thrown away once the reserve is empty too.
+pub(crate) const DISCARD_WHEN_EMPTY: [bool; WeaponType::COUNT] = {
+    let mut discards: [bool; WeaponType::COUNT] = [false; WeaponType::COUNT];
 
-    bulletcounts[BasicPistol as usize] = 8;
-    bulletcounts
+    discards[SniperRifle as usize] = true;
+    discards
 };
 
 /// masses of each gun
@@ -47,6 +79,8 @@ pub(crate) const DEFAULT_MASSES: [f32; WeaponType::COUNT] = {
     let mut masses: [f32; WeaponType::COUNT] = [0.0; WeaponType::COUNT];
 
     masses[BasicPistol as usize] = 5.0;
+    masses[AssaultRifle as usize] = 8.0;
+    masses[SniperRifle as usize] = 10.0;
     masses
 };
 
@@ -55,6 +89,8 @@ pub(crate) const RELOAD_TIMES: [u128; WeaponType::COUNT] = {
     let mut times: [u128; WeaponType::COUNT] = [0; WeaponType::COUNT];
 
     times[BasicPistol as usize] = 1000;
+    times[AssaultRifle as usize] = 1500;
+    times[SniperRifle as usize] = 2500;
     times
 };
 
@@ -64,6 +100,8 @@ pub(crate) const ATTACK_TIMES: [u128; WeaponType::COUNT] = {
     let mut times: [u128; WeaponType::COUNT] = [0; WeaponType::COUNT];
 
     times[BasicPistol as usize] = 500;
+    times[AssaultRifle as usize] = 100;
+    times[SniperRifle as usize] = 1200;
     times
 };
 
@@ -72,6 +110,8 @@ pub(crate) const BULLET_TYPES: [BulletType; WeaponType::COUNT] = {
     let mut types: [BulletType; WeaponType::COUNT] = [Pistol; WeaponType::COUNT];
 
     types[BasicPistol as usize] = Pistol;
+    types[AssaultRifle as usize] = Rifle;
+    types[SniperRifle as usize] = Sniper;
     types
 };
 
@@ -81,6 +121,8 @@ pub(crate) const BULLET_SPEEDS: [f32; WeaponType::COUNT] = {
     let mut bullet_speeds = [0.0; WeaponType::COUNT];
 
     bullet_speeds[BasicPistol as usize] = 1000.0;
+    bullet_speeds[AssaultRifle as usize] = 1400.0;
+    bullet_speeds[SniperRifle as usize] = 2200.0;
     bullet_speeds
 };
 
@@ -1,6 +1,9 @@
 use crossbeam::channel::{Sender, Receiver};
 
-use game::{arena::Arena, player::Player, map::Map, weapon::Bullet};
+use game::{arena::Arena, player::Player, map::Map, weapon::{Bullet, Weapon}, pickup::Pickup, entity::EntityId};
+use game::gamemode::ModeState;
+use game::stats::MatchStats;
+use game::characterscatalog::CharacterClass;
 use glam::Vec2;
 use laminar::{Socket, Packet, SocketEvent};
 use std::{net::SocketAddr, thread::{self, JoinHandle}, io::{self, Result, ErrorKind}};
@@ -19,13 +22,21 @@ pub struct Client {
     receiver: Receiver<SocketEvent>,
     remote: Option<SocketAddr>,
     arena: Option<Arena>,
-    id: Option<u8>,
+    id: Option<EntityId>,
+    mode: Option<ModeState>,
+    stats: Option<MatchStats>,
+    /// how far along the reload of the weapon of the player is, as last received from the server.
+    reload: f32,
+    /// rows and columns of the blocks changed since the last call to `take_changed_blocks`.
+    changed_blocks: Vec<(usize, usize)>,
     name: String,
+    /// character class chosen by the player.
+    class: CharacterClass,
     _poll_thread: JoinHandle<()>,
 }
 
 impl Client {
-    pub fn new(port: u16, name: &str) -> Result<Self> {
+    pub fn new(port: u16, name: &str, class: CharacterClass) -> Result<Self> {
         let addr = SocketAddr::from(([0, 0, 0, 0], port));
         match Socket::bind(addr) {
             Ok(mut socket) => {
@@ -34,9 +45,13 @@ impl Client {
                 let _poll_thread = thread::spawn(move || socket.start_polling());
                 let arena = None;
                 let id = None;
+                let mode = None;
+                let stats = None;
+                let reload = 1.0;
+                let changed_blocks = Vec::new();
                 let name = name.to_string();
 
-                Ok(Self {sender, receiver, remote, arena, id, name, _poll_thread})
+                Ok(Self {sender, receiver, remote, arena, id, mode, stats, reload, changed_blocks, name, class, _poll_thread})
             },
 
             Err(e) => Err(io::Error::new(ErrorKind::Other, e)),
@@ -83,10 +98,32 @@ impl Client {
         self.arena.as_ref()
     }
 
-    pub fn try_get_id(&self) -> &Option<u8> {
+    pub fn try_get_id(&self) -> &Option<EntityId> {
         &self.id
     }
 
+    /// obtains the state of the game mode last received from the server.
+    pub fn try_get_mode(&self) -> Option<&ModeState> {
+        self.mode.as_ref()
+    }
+
+    /// obtains the statistics of the last match which ended, if any.
+    pub fn try_get_stats(&self) -> Option<&MatchStats> {
+        self.stats.as_ref()
+    }
+
+    /// obtains how far along the reload of the weapon of the player is, from 0.0 (just started)
+    /// to 1.0 (ready to fire).
+    pub fn get_reload_progress(&self) -> f32 {
+        self.reload
+    }
+
+    /// obtains the rows and columns of the blocks of the arena which changed since the last call,
+    /// so only their part of the map has to be drawn again.
+    pub fn take_changed_blocks(&mut self) -> Vec<(usize, usize)> {
+        std::mem::take(&mut self.changed_blocks)
+    }
+
     /// sends the data to a remote socket.
     fn send_to(sender: &Sender<Packet>,
                remote: &SocketAddr,
@@ -101,10 +138,16 @@ impl Client {
     }
 
     /// function to call when the client receives a packet.
+    #[allow(clippy::too_many_arguments)]
     fn on_packet_recv(arena_opt: &mut Option<Arena>,
-                      id_opt: &mut Option<u8>,
+                      id_opt: &mut Option<EntityId>,
+                      mode_opt: &mut Option<ModeState>,
+                      stats_opt: &mut Option<MatchStats>,
+                      reload: &mut f32,
+                      changed_blocks: &mut Vec<(usize, usize)>,
                       client_remote: &mut Option<SocketAddr>,
                       name: &str,
+                      class: CharacterClass,
                       sender: &Sender<Packet>,
                       packet: Packet) {
 
@@ -117,48 +160,113 @@ impl Client {
                 HeaderByte::State => {
                     // updates this client's arena.
                     let state = message.read_state();
-                    if let (Some(arena), Ok((p_ids, p_positions, b_ids, b_type, b_positions))) = (arena_opt, state) {
+                    if let (Some(arena), Ok(snapshot)) = (arena_opt, state) {
+                        // removes the players who left the arena.
+                        let left: Vec<EntityId> = arena.get_players()
+                            .ids()
+                            .filter(|id| !snapshot.players.iter().any(|p| p.id == *id))
+                            .collect();
+
+                        for id in left {
+                            arena.remove_player(id);
+                        }
+
                         // updates players and positions.
-                        // TODO - clear only disconnected players
-                        for (id, pos) in p_ids.iter().zip(p_positions) {
-                            let p = arena.get_mut_player(*id);
-                            if let Some(player) = p {
-                                // TODO - actually send the direction
-                                player.update_position(pos, 1.0);
+                        for p_state in snapshot.players {
+                            let id = p_state.id;
+                            let p = arena.get_mut_player(id);
+                            let player = match p {
+                                Some(player) => player,
+                                None => arena.insert_player(id, Player::new(&id.to_string())),
+                            };
+
+                            if player.get_class() != p_state.class {
+                                player.set_class(p_state.class, 0);
                             }
-                            else {
-                                let player = arena.add_player(Player::new(&id.to_string()), *id);
-                                // TODO - actually send the direction
-                                player.update_position(pos, 1.0);
+
+                            // faces the direction of the aim, unless aiming straight up or down.
+                            let aim = p_state.aim;
+                            let direction = if aim.x.abs() < 0.1 { 0.0 } else { aim.x.signum() };
+                            player.update_position(p_state.position, direction);
+                            player.set_aim(aim);
+                            player.set_damage(p_state.damage);
+                            if *id_opt == Some(id) {
+                                *reload = p_state.reload;
                             }
+
+                            // the arena of the client is not updated, so its time stays at 0
+                            // and the effects expire after their remaining time.
+                            player.set_effects(p_state.effects);
                         }
 
                         // TODO - clear only dead bullets.
                         arena.clear_bullets();
-                        for ((id, bullettype), position) in b_ids.iter().zip(b_type).zip(b_positions) {
-                            let bullet = Bullet::new(position, Vec2::ZERO, bullettype, 0, *id);
-                            arena.update_bullet(bullet);
+                        for (id, bullettype, position) in snapshot.bullets {
+                            // the owner of the bullet is not sent.
+                            let bullet = Bullet::new(position, Vec2::ZERO, bullettype, 0, EntityId::new(0, 0));
+                            arena.update_bullet(id, bullet);
+                        }
+
+                        arena.clear_thrown_weapons();
+                        for (id, weapontype, position) in snapshot.thrown_weapons {
+                            let weapon = Weapon::new(position, weapontype, 1.0, 0);
+                            arena.update_thrown_weapon(id, weapon);
+                        }
+
+                        arena.clear_pickups();
+                        for (id, kind, position) in snapshot.pickups {
+                            arena.update_pickup(id, Pickup::new(position, kind));
                         }
                     } else {
                         println!("Received invalid state packet");
                     }
                 },
 
+                HeaderByte::Mode => {
+                    // updates the scores and objectives of the game mode.
+                    match message.read_mode() {
+                        Ok(mode) => *mode_opt = Some(mode),
+                        Err(_) => println!("Received invalid mode packet"),
+                    }
+                },
+
+                HeaderByte::Stats => {
+                    // the match ended, so the results can be shown.
+                    match message.read_stats() {
+                        Ok(stats) => *stats_opt = Some(stats),
+                        Err(_) => println!("Received invalid stats packet"),
+                    }
+                },
+
+                HeaderByte::BlockChanged => {
+                    // updates the blocks of this client's arena.
+                    match (arena_opt, message.read_block_changes()) {
+                        (Some(arena), Ok(changes)) => {
+                            for (row, col, blocktype) in changes {
+                                arena.set_block(row, col, blocktype);
+                                changed_blocks.push((row, col));
+                            }
+                        },
+
+                        _ => println!("Received invalid block changed packet"),
+                    }
+                },
+
                 HeaderByte::Verify => {
                     // updates player ID and arena.
-                    let batch: Result<(u8, Map)> = message.read_verify();
+                    let batch: Result<(EntityId, Map)> = message.read_verify();
                     if let Ok((id, map)) = batch {
                         let mut new_arena = Arena::new(map);
 
                         // TODO - should remove.
-                        new_arena.add_player(Player::new(name), id);
+                        new_arena.insert_player(id, Player::new(name));
 
                         *id_opt = Some(id);
                         *arena_opt = Some(new_arena);
 
                         Client::set_remote(client_remote, &remote);
 
-                        let request = Message::write_request(name, id);
+                        let request = Message::write_request(name, id, class);
                         Client::send_to(sender, &remote, &request).unwrap();
                     }
                     else {
@@ -178,7 +286,7 @@ impl Client {
             match event {
                 SocketEvent::Packet(packet) => {
                     if self.remote == None || packet.addr() == self.remote.unwrap() {
-                        Client::on_packet_recv(&mut self.arena, &mut self.id, &mut self.remote, self.name.as_str(), &self.sender, packet);
+                        Client::on_packet_recv(&mut self.arena, &mut self.id, &mut self.mode, &mut self.stats, &mut self.reload, &mut self.changed_blocks, &mut self.remote, self.name.as_str(), self.class, &self.sender, packet);
                     }
                 },
 
@@ -1,11 +1,24 @@
 use glam::Vec2;
+use std::f32::consts::TAU;
 use std::io::{self, Error};
 
-use game::{player::Player, input::InputMask, map::{Map, MapBlocksList}, block::BlockType, arena::Arena, weaponscatalog::BulletType};
+use game::{player::Player, input::{Input, InputMask}, map::{Map, MapBlocksList, VERTICAL_BLOCKS, HORIZONTAL_BLOCKS}, block::BlockType, arena::Arena, entity::EntityId};
+use game::weaponscatalog::{BulletType, WeaponType};
+use game::effect::{EffectType, StatusEffect};
+use game::pickup::PickupKind;
+use game::gamemode::{GameMode, GameModeType, ModeState, Scorer};
+use game::stats::MatchStats;
+use game::hazard::{Hazard, HazardType, WindZone};
+use game::teleporter::TeleporterPair;
+use game::characterscatalog::CharacterClass;
 use strum::{IntoEnumIterator, EnumCount};
 use strum_macros::FromRepr;
 use std::io::Result;
 
+/// version of the format of the `input` messages, sent before the mask. The first format had
+/// no version, and was just the mask converted to a u8.
+pub(crate) const INPUT_VERSION: u8 = 1;
+
 #[derive(Debug, PartialEq, FromRepr, Clone, Copy)]
 #[repr(u8)]
 pub(crate) enum HeaderByte {
@@ -14,7 +27,37 @@ pub(crate) enum HeaderByte {
     Verify,
     Request,
     State,
-    Input
+    Input,
+    Mode,
+    Stats,
+    BlockChanged,
+}
+
+/// State of a player decoded from a `state` message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerSnapshot {
+    pub id: EntityId,
+    pub class: CharacterClass,
+    pub position: Vec2,
+    /// damage taken, in percent.
+    pub damage: f32,
+    /// unit vector the player is aiming at.
+    pub aim: Vec2,
+    /// how far along the reload of the weapon is, from 0.0 (just started) to 1.0 (ready to fire).
+    pub reload: f32,
+    /// active effects, which expire relative to the time the state was sent, i.e.
+    /// `get_expires_at` is the time left on the effect in ms.
+    pub effects: Vec<StatusEffect>,
+}
+
+/// State of the arena decoded from a `state` message. Every entity is listed with its id
+/// and the approximation of its position.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StateSnapshot {
+    pub players: Vec<PlayerSnapshot>,
+    pub bullets: Vec<(EntityId, BulletType, Vec2)>,
+    pub thrown_weapons: Vec<(EntityId, WeaponType, Vec2)>,
+    pub pickups: Vec<(EntityId, PickupKind, Vec2)>,
 }
 
 #[derive(Debug, Clone)]
@@ -57,10 +100,12 @@ impl Message {
     }
 
     /// A `request` is formatted with its data being:
-    /// first byte = id of player,
+    /// first 4 bytes = id of player,
+    /// next byte = character class chosen by the player,
     /// rest = name
-    pub fn write_request(name: &str, id: u8) -> Message {
-        let mut data_vec = Vec::from(id.to_le_bytes());
+    pub fn write_request(name: &str, id: EntityId, class: CharacterClass) -> Message {
+        let mut data_vec = Vec::from(id.to_bits().to_le_bytes());
+        data_vec.push(class as u8);
         data_vec.extend(name.bytes());
 
         Message {
@@ -70,26 +115,61 @@ impl Message {
     }
 
     /// A `verify` message is formatted with:
-    /// first byte = id of player,
-    /// rest = map
-    pub fn write_verify(id: u8, map: &Map) -> Message {
+    /// first 4 bytes = id of player,
+    /// next 16 bytes per block type = blocks of the map,
+    /// number of hazards - u8, followed by the row - u8, col - u8 and type - u8 of each hazard,
+    /// number of wind zones - u8, followed by the top left corner, bottom right corner and force
+    /// of each zone as pairs of f32,
+    /// number of teleporter pairs - u8, followed by the row - u8 and col - u8 of both teleporters
+    /// of each pair.
+    pub fn write_verify(id: EntityId, map: &Map) -> Message {
         let mapdata = map.get_mapblocks_list();
-        let mut data_vec = Vec::with_capacity(mapdata.len() + 1);
-        data_vec.extend(id.to_le_bytes());
+        let mut data_vec = Vec::with_capacity(mapdata.len() * 16 + 4);
+        data_vec.extend(id.to_bits().to_le_bytes());
         for blocktypelist in mapdata {
             data_vec.extend(blocktypelist.to_le_bytes());
         }
 
+        let num_hazards: u8 = map.get_hazards().len().try_into().unwrap();
+        data_vec.extend(num_hazards.to_le_bytes());
+        for hazard in map.get_hazards() {
+            data_vec.push(hazard.get_row() as u8);
+            data_vec.push(hazard.get_col() as u8);
+            data_vec.push(hazard.get_hazard_type() as u8);
+        }
+
+        let num_wind_zones: u8 = map.get_wind_zones().len().try_into().unwrap();
+        data_vec.extend(num_wind_zones.to_le_bytes());
+        for zone in map.get_wind_zones() {
+            for value in [zone.get_min(), zone.get_max(), zone.get_force()] {
+                data_vec.extend(value.x.to_le_bytes());
+                data_vec.extend(value.y.to_le_bytes());
+            }
+        }
+
+        let num_teleporters: u8 = map.get_teleporter_pairs().len().try_into().unwrap();
+        data_vec.extend(num_teleporters.to_le_bytes());
+        for pair in map.get_teleporter_pairs() {
+            for (row, col) in [pair.get_first(), pair.get_second()] {
+                data_vec.push(row as u8);
+                data_vec.push(col as u8);
+            }
+        }
+
         Message {
             header: HeaderByte::Verify,
             data: data_vec,
         }
     }
 
-    /// An `input` message is formatted with just an InputMask converted to a u8 byte.
+    /// An `input` message is formatted with
+    ///
+    /// 0 - version of the format - u8 (`INPUT_VERSION`)
+    /// 1 - InputMask converted to a u32
     pub fn write_input(input: InputMask) -> Message {
-        let data: u8 = input.into();
-        let data_vec = Vec::from(data.to_le_bytes());
+        let data: u32 = input.into();
+        let mut data_vec = vec![INPUT_VERSION];
+        data_vec.extend(data.to_le_bytes());
         Message {
             header: HeaderByte::Input,
             data: data_vec
@@ -98,38 +178,85 @@ impl Message {
 
     /// an `arena` message is formatted with
     ///
-    /// 0 - number of players
-    /// 1 - [(player_id_0 - u8, approximation of player position), ... ]
-    /// 2 - [(bullet_id_0 - u16, bullet_type - u8, approximation of bullet position), ... ]
+    /// 0 - number of players - u8
+    /// 1 - [(player_id_0 - u32, class - u8, approximation of player position, damage - u16 (tenths of a
+    ///       percent), aim angle - u8 (1/256 of a turn), reload progress - u8 (1/255 of the
+    ///       reload), number of effects - u8,
+    ///       [(effect_type - u8, remaining ms - u16), ...]), ... ]
+    /// 2 - number of bullets - u16
+    /// 3 - [(bullet_id_0 - u32, bullet_type - u8, approximation of bullet position), ... ]
+    /// 4 - number of thrown weapons - u8
+    /// 5 - [(weapon_id_0 - u32, weapon_type - u8, approximation of weapon position), ... ]
+    /// 6 - number of pickups - u8
+    /// 7 - [(pickup_id_0 - u32, kind - u8 (0 = weapon, 1 = effect), weapon or effect type - u8,
+    ///       approximation of pickup position), ... ]
     pub fn write_state(arena: &Arena) -> Message {
         let mut state_bytes = Vec::new();
         let num_players: u8 = arena.get_players().len().try_into().unwrap();
         state_bytes.extend(num_players.to_le_bytes());
 
-        for (id, player) in arena.get_players() {
-            state_bytes.extend(id.to_le_bytes());
-
-            let (x, y, x_s, y_s) = Arena::get_approximate_position(player.position);
-            state_bytes.extend(x.to_le_bytes());
-            state_bytes.extend(y.to_le_bytes());
-            state_bytes.extend(x_s.to_le_bytes());
-            state_bytes.extend(y_s.to_le_bytes());
+        for (id, player) in arena.get_players().iter() {
+            state_bytes.extend(id.to_bits().to_le_bytes());
+            state_bytes.push(player.get_class() as u8);
+            Message::write_position(&mut state_bytes, player.get_position());
+
+            let damage = (player.get_damage() * 10.0).round().min(u16::MAX as f32) as u16;
+            state_bytes.extend(damage.to_le_bytes());
+            state_bytes.extend(Message::aim_to_byte(player.get_aim()).to_le_bytes());
+            let reload = player.get_weapon().get_reload_progress(arena.get_time());
+            state_bytes.push((reload * 255.0).round() as u8);
+
+            let effects = player.get_effects();
+            let num_effects: u8 = effects.len().try_into().unwrap();
+            state_bytes.extend(num_effects.to_le_bytes());
+
+            for effect in effects {
+                let effecttype: u8 = effect.get_effect_type() as u8;
+                let remaining: u16 = effect.get_remaining(arena.get_time())
+                    .try_into()
+                    .unwrap_or(u16::MAX);
+
+                state_bytes.extend(effecttype.to_le_bytes());
+                state_bytes.extend(remaining.to_le_bytes());
+            }
         }
 
-        for (id, bullet) in arena.get_bullets() {
-            // id sent
-            state_bytes.extend(id.to_le_bytes());
+        let num_bullets: u16 = arena.get_bullets().len().try_into().unwrap();
+        state_bytes.extend(num_bullets.to_le_bytes());
+
+        for (id, bullet) in arena.get_bullets().iter() {
+            state_bytes.extend(id.to_bits().to_le_bytes());
 
-            // bullettype
             let bullettype: u8 = bullet.get_bullet_type() as u8;
             state_bytes.extend(bullettype.to_le_bytes());
+            Message::write_position(&mut state_bytes, bullet.get_position());
+        }
+
+        let num_weapons: u8 = arena.get_thrown_weapons().len().try_into().unwrap();
+        state_bytes.extend(num_weapons.to_le_bytes());
+
+        for (id, weapon) in arena.get_thrown_weapons().iter() {
+            state_bytes.extend(id.to_bits().to_le_bytes());
 
-            // locations
-            let (x, y, x_s, y_s) = Arena::get_approximate_position(bullet.get_position());
-            state_bytes.extend(x.to_le_bytes());
-            state_bytes.extend(y.to_le_bytes());
-            state_bytes.extend(x_s.to_le_bytes());
-            state_bytes.extend(y_s.to_le_bytes());
+            let weapontype: u8 = weapon.get_weapon_type() as u8;
+            state_bytes.extend(weapontype.to_le_bytes());
+            Message::write_position(&mut state_bytes, weapon.get_position());
+        }
+
+        let num_pickups: u8 = arena.get_pickups().len().try_into().unwrap();
+        state_bytes.extend(num_pickups.to_le_bytes());
+
+        for (id, pickup) in arena.get_pickups().iter() {
+            state_bytes.extend(id.to_bits().to_le_bytes());
+
+            let (kind, type_byte): (u8, u8) = match pickup.get_kind() {
+                PickupKind::Weapon(weapontype) => (0, weapontype as u8),
+                PickupKind::Effect(effecttype) => (1, effecttype as u8),
+            };
+
+            state_bytes.extend(kind.to_le_bytes());
+            state_bytes.extend(type_byte.to_le_bytes());
+            Message::write_position(&mut state_bytes, pickup.get_position());
         }
 
         Message {
@@ -138,55 +265,315 @@ impl Message {
         }
     }
 
-    /// Reads the packet as a gamestate packet.
+    /// a `mode` message is formatted with
     ///
-    /// returned data:
-    /// (player_ids, player_positions, bullet_ids, bullet_types, bullet_positions)
-    pub fn read_state(&self) -> Result<(Vec<u8>, Vec<Vec2>, Vec<u16>, Vec<BulletType>, Vec<Vec2>)> {
-        let mut data_iter = self.data.iter();
-        let mut player_count = *data_iter.next()
-            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Unable to read player count"))?;
+    /// 0 - game mode type - u8
+    /// 1 - whether there is a winner - u8, followed by the winner
+    /// 2 - number of scores - u8
+    /// 3 - [(scorer, score - u32), ... ]
+    /// 4 - number of objectives - u8
+    /// 5 - [approximation of objective position, ... ]
+    /// 6 - whether the match is in sudden death - u8
+    /// 7 - number of blocks about to collapse - u8
+    /// 8 - [(row - u8, col - u8), ... ]
+    ///
+    /// where a scorer is a kind - u8 (0 = player, 1 = team), followed by the id of the player
+    /// - u32, or the team - u8.
+    pub fn write_mode(mode: &GameMode) -> Message {
+        let state = mode.get_state();
+        let mut mode_bytes = vec![state.modetype as u8];
+
+        mode_bytes.push(state.winner.is_some() as u8);
+        if let Some(winner) = state.winner {
+            Message::write_scorer(&mut mode_bytes, winner);
+        }
+
+        let num_scores: u8 = state.scores.len().try_into().unwrap();
+        mode_bytes.extend(num_scores.to_le_bytes());
+        for (scorer, score) in state.scores {
+            Message::write_scorer(&mut mode_bytes, scorer);
+            mode_bytes.extend(score.to_le_bytes());
+        }
 
-        player_count = u8::from_le(player_count);
+        let num_objectives: u8 = state.objectives.len().try_into().unwrap();
+        mode_bytes.extend(num_objectives.to_le_bytes());
+        for position in state.objectives {
+            Message::write_position(&mut mode_bytes, position);
+        }
+
+        mode_bytes.push(state.sudden_death as u8);
+        let num_collapsing: u8 = state.collapsing.len().try_into().unwrap();
+        mode_bytes.extend(num_collapsing.to_le_bytes());
+        for (row, col) in state.collapsing {
+            mode_bytes.push(row as u8);
+            mode_bytes.push(col as u8);
+        }
+
+        Message {
+            header: HeaderByte::Mode,
+            data: mode_bytes
+        }
+    }
+
+    /// a `stats` message is formatted with its data being the JSON document of the statistics.
+    pub fn write_stats(stats: &MatchStats) -> Message {
+        Message {
+            header: HeaderByte::Stats,
+            data: stats.to_json().into_bytes()
+        }
+    }
+
+    /// a `block changed` message is formatted with:
+    /// number of changed blocks - u8,
+    /// followed by the row - u8, col - u8 and new type - u8 of each block, where a type of
+    /// `u8::MAX` means the block was removed.
+    pub fn write_block_changes(changes: &[(usize, usize, Option<BlockType>)]) -> Message {
+        let num_changes: u8 = changes.len().try_into().unwrap();
+        let mut change_bytes = Vec::from(num_changes.to_le_bytes());
+        for (row, col, blocktype) in changes {
+            change_bytes.push(*row as u8);
+            change_bytes.push(*col as u8);
+            change_bytes.push(blocktype.map_or(u8::MAX, |blocktype| blocktype as u8));
+        }
 
-        let mut player_ids = Vec::with_capacity(player_count.into());
-        let mut player_positions = Vec::with_capacity(player_count.into());
-        let mut bullet_ids = Vec::with_capacity(player_count as usize * 3);
-        let mut bullet_types = Vec::with_capacity(player_count as usize * 3);
-        let mut bullet_positions = Vec::with_capacity(player_count as usize * 3);
+        Message {
+            header: HeaderByte::BlockChanged,
+            data: change_bytes
+        }
+    }
 
+    /// appends the kind of the scorer and its id or team.
+    fn write_scorer(bytes: &mut Vec<u8>, scorer: Scorer) {
+        match scorer {
+            Scorer::Player(id) => {
+                bytes.push(0);
+                bytes.extend(id.to_bits().to_le_bytes());
+            },
+
+            Scorer::Team(team) => {
+                bytes.push(1);
+                bytes.push(team);
+            },
+        }
+    }
+
+    /// approximates the aim vector as an angle, in 1/256 of a turn.
+    fn aim_to_byte(aim: Vec2) -> u8 {
+        let turns = aim.y.atan2(aim.x) / TAU;
+        (turns * 256.0).round().rem_euclid(256.0) as u8
+    }
+
+    /// obtains the aim vector from the angle created by `aim_to_byte`.
+    fn byte_to_aim(byte: u8) -> Vec2 {
+        let angle = byte as f32 / 256.0 * TAU;
+        Vec2::new(angle.cos(), angle.sin())
+    }
+
+    /// appends the approximation of the position.
+    fn write_position(bytes: &mut Vec<u8>, position: Vec2) {
+        let (x, y, x_s, y_s) = Arena::get_approximate_position(position);
+        bytes.extend(x.to_le_bytes());
+        bytes.extend(y.to_le_bytes());
+        bytes.extend(x_s.to_le_bytes());
+        bytes.extend(y_s.to_le_bytes());
+    }
+
+    /// Reads the packet as a gamestate packet.
+    pub fn read_state(&self) -> Result<StateSnapshot> {
+        let mut data_iter = self.data.iter();
+        let mut snapshot = StateSnapshot::default();
+
+        let player_count = Message::read_next_byte(&mut data_iter)?;
         for _ in 0..player_count {
-            let id: u8 = u8::from_le(
-                *data_iter.next()
-                .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Unable to read ID"))?
-                );
-
-            player_ids.push(id);
-            player_positions.push(Message::read_next_position(&mut data_iter)?);
-        }
-
-        // total remaining bytes divided by bytes per bullet
-        // 2 for id, 1 for type, 4 for position
-        let num_bullets = data_iter.len() / (2 + 1 + 4);
-        for _ in 0..num_bullets {
-            let byte_1: u8 = *data_iter.next().ok_or(io::Error::new(io::ErrorKind::InvalidData, "Invalid data"))?;
-            let byte_2: u8 = *data_iter.next().ok_or(io::Error::new(io::ErrorKind::InvalidData, "Invalid data"))?;
-            let id: u16 = u16::from_le_bytes([byte_1, byte_2]);
-
-            let type_byte: u8 = u8::from_le(
-                *data_iter.next()
-                .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine type"))?);
+            let id = Message::read_next_id(&mut data_iter)?;
+            let class = CharacterClass::from_repr(Message::read_next_byte(&mut data_iter)? as usize)
+                .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine class"))?;
+            let position = Message::read_next_position(&mut data_iter)?;
+            let damage = u16::from_le_bytes([
+                Message::read_next_byte(&mut data_iter)?,
+                Message::read_next_byte(&mut data_iter)?,
+            ]) as f32 / 10.0;
+            let aim = Message::byte_to_aim(Message::read_next_byte(&mut data_iter)?);
+            let reload = Message::read_next_byte(&mut data_iter)? as f32 / 255.0;
+
+            let effect_count = Message::read_next_byte(&mut data_iter)?;
+            let mut effects = Vec::with_capacity(effect_count.into());
+            for _ in 0..effect_count {
+                let type_byte = Message::read_next_byte(&mut data_iter)?;
+                let effecttype = EffectType::from_repr(type_byte as usize)
+                    .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine effect"))?;
+
+                let remaining = u16::from_le_bytes([
+                    Message::read_next_byte(&mut data_iter)?,
+                    Message::read_next_byte(&mut data_iter)?,
+                ]);
+
+                effects.push(StatusEffect::new(effecttype, remaining.into()));
+            }
+
+            snapshot.players.push(PlayerSnapshot { id, class, position, damage, aim, reload, effects });
+        }
+
+        let bullet_count = u16::from_le_bytes([
+            Message::read_next_byte(&mut data_iter)?,
+            Message::read_next_byte(&mut data_iter)?,
+        ]);
+
+        for _ in 0..bullet_count {
+            let id = Message::read_next_id(&mut data_iter)?;
+            let type_byte = Message::read_next_byte(&mut data_iter)?;
             let bullettype: BulletType = BulletType::from_repr(type_byte as usize)
                 .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine type"))?;
 
             let position = Message::read_next_position(&mut data_iter)?;
+            snapshot.bullets.push((id, bullettype, position));
+        }
 
-            bullet_ids.push(id);
-            bullet_types.push(bullettype);
-            bullet_positions.push(position);
+        let weapon_count = Message::read_next_byte(&mut data_iter)?;
+        for _ in 0..weapon_count {
+            let (id, weapontype, position) = Message::read_next_weapon(&mut data_iter)?;
+            snapshot.thrown_weapons.push((id, weapontype, position));
+        }
+
+        let pickup_count = Message::read_next_byte(&mut data_iter)?;
+        for _ in 0..pickup_count {
+            let id = Message::read_next_id(&mut data_iter)?;
+            let kind_byte = Message::read_next_byte(&mut data_iter)?;
+            let type_byte = Message::read_next_byte(&mut data_iter)? as usize;
+            let kind = match kind_byte {
+                0 => WeaponType::from_repr(type_byte).map(PickupKind::Weapon),
+                1 => EffectType::from_repr(type_byte).map(PickupKind::Effect),
+                _ => None,
+            }.ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine pickup"))?;
+
+            let position = Message::read_next_position(&mut data_iter)?;
+            snapshot.pickups.push((id, kind, position));
         }
 
-        Ok((player_ids, player_positions, bullet_ids, bullet_types, bullet_positions))
+        Ok(snapshot)
+    }
+
+    /// Reads the packet as a mode packet.
+    pub fn read_mode(&self) -> Result<ModeState> {
+        let mut data_iter = self.data.iter();
+        let type_byte = Message::read_next_byte(&mut data_iter)?;
+        let modetype = GameModeType::from_repr(type_byte as usize)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine game mode"))?;
+
+        let winner = match Message::read_next_byte(&mut data_iter)? {
+            0 => None,
+            _ => Some(Message::read_next_scorer(&mut data_iter)?),
+        };
+
+        let score_count = Message::read_next_byte(&mut data_iter)?;
+        let mut scores = Vec::with_capacity(score_count.into());
+        for _ in 0..score_count {
+            let scorer = Message::read_next_scorer(&mut data_iter)?;
+            let mut bytes = [0; 4];
+            for byte in bytes.iter_mut() {
+                *byte = Message::read_next_byte(&mut data_iter)?;
+            }
+            scores.push((scorer, u32::from_le_bytes(bytes)));
+        }
+
+        let objective_count = Message::read_next_byte(&mut data_iter)?;
+        let mut objectives = Vec::with_capacity(objective_count.into());
+        for _ in 0..objective_count {
+            objectives.push(Message::read_next_position(&mut data_iter)?);
+        }
+
+        let sudden_death = Message::read_next_byte(&mut data_iter)? != 0;
+        let collapsing_count = Message::read_next_byte(&mut data_iter)?;
+        let mut collapsing = Vec::with_capacity(collapsing_count.into());
+        for _ in 0..collapsing_count {
+            let row = Message::read_next_byte(&mut data_iter)?;
+            let col = Message::read_next_byte(&mut data_iter)?;
+            if usize::from(row) >= VERTICAL_BLOCKS || usize::from(col) >= HORIZONTAL_BLOCKS {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Block outside of the map"));
+            }
+            collapsing.push((row.into(), col.into()));
+        }
+
+        Ok(ModeState { modetype, scores, objectives, winner, sudden_death, collapsing })
+    }
+
+    /// Reads the packet as a block changed packet.
+    pub fn read_block_changes(&self) -> Result<Vec<(usize, usize, Option<BlockType>)>> {
+        let mut data_iter = self.data.iter();
+        let change_count = Message::read_next_byte(&mut data_iter)?;
+        let mut changes = Vec::with_capacity(change_count.into());
+        for _ in 0..change_count {
+            let row = Message::read_next_byte(&mut data_iter)?;
+            let col = Message::read_next_byte(&mut data_iter)?;
+            let blocktype = match Message::read_next_byte(&mut data_iter)? {
+                u8::MAX => None,
+                type_byte => Some(BlockType::from_repr(type_byte as usize)
+                    .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine block"))?),
+            };
+
+            if usize::from(row) >= VERTICAL_BLOCKS || usize::from(col) >= HORIZONTAL_BLOCKS {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Block outside of the map"));
+            }
+            changes.push((row.into(), col.into(), blocktype));
+        }
+
+        Ok(changes)
+    }
+
+    /// Reads the packet as a stats packet.
+    pub fn read_stats(&self) -> Result<MatchStats> {
+        let json = std::str::from_utf8(&self.data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        MatchStats::from_json(json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// obtains the scorer written by `write_scorer` from the iterator.
+    fn read_next_scorer<'a>(data_bytes: &mut impl Iterator<Item = &'a u8>) -> Result<Scorer> {
+        match Message::read_next_byte(data_bytes)? {
+            0 => Ok(Scorer::Player(Message::read_next_id(data_bytes)?)),
+            1 => Ok(Scorer::Team(Message::read_next_byte(data_bytes)?)),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine scorer")),
+        }
+    }
+
+    /// obtains the next byte of the iterator.
+    fn read_next_byte<'a>(data_bytes: &mut impl Iterator<Item = &'a u8>) -> Result<u8> {
+        data_bytes.next()
+            .map(|byte| u8::from_le(*byte))
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Unexpected end of data"))
+    }
+
+    /// obtains the f32 decoded from the next 4 bytes of the iterator.
+    fn read_next_f32<'a>(data_bytes: &mut impl Iterator<Item = &'a u8>) -> Result<f32> {
+        let mut bytes = [0; 4];
+        for byte in bytes.iter_mut() {
+            *byte = Message::read_next_byte(data_bytes)?;
+        }
+
+        Ok(f32::from_le_bytes(bytes))
+    }
+
+    /// obtains the entity id decoded from the next 4 bytes of the iterator.
+    fn read_next_id<'a>(data_bytes: &mut impl Iterator<Item = &'a u8>) -> Result<EntityId> {
+        let mut bytes = [0; 4];
+        for byte in bytes.iter_mut() {
+            *byte = *data_bytes.next()
+                .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Unable to read ID"))?;
+        }
+
+        Ok(EntityId::from_bits(u32::from_le_bytes(bytes)))
+    }
+
+    /// obtains the id, type and position of a thrown weapon from the iterator.
+    fn read_next_weapon<'a>(data_bytes: &mut impl Iterator<Item = &'a u8>) -> Result<(EntityId, WeaponType, Vec2)> {
+        let id = Message::read_next_id(data_bytes)?;
+        let type_byte = Message::read_next_byte(data_bytes)?;
+        let weapontype = WeaponType::from_repr(type_byte as usize)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine type"))?;
+
+        let position = Message::read_next_position(data_bytes)?;
+        Ok((id, weapontype, position))
     }
 
     /// obtains the position decoded from the bytes of the iterator.
@@ -218,27 +605,26 @@ impl Message {
     }
 
     /// Reads the packet as a request packet.
-    pub fn read_request(&self) -> Result<(u8, Player)> {
+    /// Fails if the class is unknown.
+    pub fn read_request(&self) -> Result<(EntityId, Player)> {
         let mut data_iter = self.data.iter();
-        let id: u8 = u8::from_le(
-            *data_iter.next()
-            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Unable to read ID"))?
-            );
+        let id = Message::read_next_id(&mut data_iter)?;
+        let class = CharacterClass::from_repr(Message::read_next_byte(&mut data_iter)? as usize)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine class"))?;
 
         let namebytes: Vec<u8> = data_iter.cloned().collect();
         let name = String::from_utf8_lossy(&namebytes);
-        Ok((id, Player::new(&name)))
+        let mut player = Player::new(&name);
+        player.set_class(class, 0);
+        Ok((id, player))
     }
 
     /// Reads the packet as a verify packet.
-    pub fn read_verify(&self) -> Result<(u8, Map)> {
+    pub fn read_verify(&self) -> Result<(EntityId, Map)> {
         let bytes = &self.data;
-        let id: u8 = u8::from_le(
-            *bytes.get(0)
-            .ok_or_else(|| Error::new(io::ErrorKind::InvalidData, "Unable to read ID"))?
-            );
+        let id = Message::read_next_id(&mut bytes.iter())?;
 
-        let mut starter_bit = 1;
+        let mut starter_bit = 4;
         let mut mapblockslist: MapBlocksList = [0; BlockType::COUNT];
 
         for blocktype in BlockType::iter() {
@@ -251,23 +637,66 @@ impl Message {
             starter_bit += 16;
         }
 
-        let constructed_map = Map::new(mapblockslist.into())
+        let mut constructed_map = Map::new(mapblockslist.into())
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
 
+        // maps without hazards, wind zones or teleporters may leave out their counts.
+        let mut data_iter = bytes[starter_bit..].iter();
+        let hazard_count = Message::read_next_byte(&mut data_iter).unwrap_or(0);
+        for _ in 0..hazard_count {
+            let row = Message::read_next_byte(&mut data_iter)?;
+            let col = Message::read_next_byte(&mut data_iter)?;
+            let hazardtype = HazardType::from_repr(Message::read_next_byte(&mut data_iter)? as usize)
+                .ok_or(io::Error::new(io::ErrorKind::InvalidData, "Cannot determine hazard"))?;
+
+            constructed_map.add_hazard(Hazard::new(row.into(), col.into(), hazardtype))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        }
+
+        let wind_zone_count = Message::read_next_byte(&mut data_iter).unwrap_or(0);
+        for _ in 0..wind_zone_count {
+            let mut values = [Vec2::ZERO; 3];
+            for value in values.iter_mut() {
+                *value = Vec2::new(Message::read_next_f32(&mut data_iter)?, Message::read_next_f32(&mut data_iter)?);
+            }
+
+            let [min, max, force] = values;
+            constructed_map.add_wind_zone(WindZone::new(min, max, force));
+        }
+
+        let teleporter_count = Message::read_next_byte(&mut data_iter).unwrap_or(0);
+        for _ in 0..teleporter_count {
+            let mut cells = [(0, 0); 2];
+            for cell in cells.iter_mut() {
+                *cell = (Message::read_next_byte(&mut data_iter)?.into(), Message::read_next_byte(&mut data_iter)?.into());
+            }
+
+            constructed_map.add_teleporter_pair(TeleporterPair::new(cells[0], cells[1]))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        }
+
         Ok((id, constructed_map))
     }
 
-    /// Reads the packet as an input packet.
-    ///
-    /// TODO - returns an err result if unable to do so.
+    /// Reads the packet as an input packet. Missing bytes are read as no input, and so is a
+    /// packet of an unknown version. A packet of a single byte is read as the first format,
+    /// without a version, which only knew the inputs before `Melee`.
     pub fn read_input(&self) -> InputMask {
-        let mask = self.data.get(0);
-        if let Some(mask) = mask {
-            let maskdata = u8::from_le(*mask);
-            InputMask::from(maskdata)
+        let mask_data: &[u8] = match self.data.split_first() {
+            _ if self.data.len() == 1 => &self.data,
+            Some((&INPUT_VERSION, mask_data)) => mask_data,
+            _ => &[],
+        };
+
+        let mut mask = [0; 4];
+        for (byte, data) in mask.iter_mut().zip(mask_data.iter()) {
+            *byte = *data;
         }
-        else {
-            InputMask::new()
+
+        let mut input = InputMask::from(u32::from_le_bytes(mask));
+        if self.data.len() == 1 {
+            input.remove_mask(Input::Melee);
         }
+        input
     }
 }
@@ -1,7 +1,11 @@
 use crossbeam::channel::{Sender, Receiver};
-use game::{arena::Arena, input::InputMask};
+use game::{arena::Arena, input::InputMask, bot::{Bot, Difficulty}, player::Player, event::GameEvent, entity::EntityId};
+use game::gamemode::{GameMode, GameModeType};
+use game::pickup::PickupSpawner;
+use game::script::ModeScript;
 use laminar::{Socket, Packet, SocketEvent};
 use std::{net::SocketAddr, thread::{self, JoinHandle}, collections::HashMap, io::{self, ErrorKind}};
+use std::time::{SystemTime, UNIX_EPOCH};
 use crate::message::{Message, HeaderByte};
 use std::io::Result;
 
@@ -14,11 +18,18 @@ use std::io::Result;
 pub struct Server {
     sender: Sender<Packet>,
     receiver: Receiver<SocketEvent>,
-    remotes: HashMap<SocketAddr, u8>,
-    inputs: HashMap<u8, InputMask>,
+    remotes: HashMap<SocketAddr, EntityId>,
+    inputs: HashMap<EntityId, InputMask>,
+    bots: HashMap<EntityId, Bot>,
+    /// difficulty of the bots filling the slots left by the remotes, if the server is filled
+    /// with bots.
+    bot_difficulty: Option<Difficulty>,
     max_remotes: u8,
     arena: Arena,
-    next_id: u8,
+    mode: GameMode,
+    /// script run on top of the game mode, if any.
+    script: Option<ModeScript>,
+    events: Vec<GameEvent>,
     _poll_thread: JoinHandle<()>
 }
 
@@ -31,44 +42,113 @@ impl Server {
                 let (sender, receiver) = (socket.get_packet_sender(), socket.get_event_receiver());
                 let remotes = HashMap::new();
                 let inputs = HashMap::new();
+                let bots = HashMap::new();
+                let bot_difficulty = None;
+                let events = Vec::new();
                 let _poll_thread = thread::spawn(move || socket.start_polling());
-                let arena = Arena::default();
-                let next_id = 0;
+                // the server spawns different pickups on every run.
+                let mut arena = Arena::default();
+                let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+                arena.set_pickup_spawner(Some(PickupSpawner::new(seed)));
+                let mode = GameMode::new(GameModeType::FreeForAll, &arena);
+                let script = None;
 
-                Ok(Self {sender, receiver, max_remotes, remotes, inputs, arena, next_id, _poll_thread})
+                Ok(Self {sender, receiver, max_remotes, remotes, inputs, bots, bot_difficulty, arena, mode, script, events, _poll_thread})
             },
 
             Err(e) => {Err(io::Error::new(ErrorKind::Other, e))}
         }
     }
 
-    /// connects to a valid address.
-    fn add_remote(remotes: &mut HashMap<SocketAddr, u8>,
+    /// connects to a valid address, adding a new player to the arena for the remote.
+    /// Returns the id of the remote's player, or None if the server is full.
+    fn add_remote(remotes: &mut HashMap<SocketAddr, EntityId>,
+                  arena: &mut Arena,
                   addr: &SocketAddr,
-                  max_remotes: u8,
-                  id: u8) -> bool {
+                  max_remotes: u8) -> Option<EntityId> {
 
         if Server::can_add_new_client(remotes, max_remotes) {
+            let id = arena.add_player(Player::new(&addr.to_string()));
             remotes.insert(*addr, id);
-            true
+            Some(id)
         } else {
-            false
+            None
         }
     }
 
-    fn can_add_new_client(remotes: &mut HashMap<SocketAddr, u8>, max_remotes: u8) -> bool {
+    fn can_add_new_client(remotes: &mut HashMap<SocketAddr, EntityId>, max_remotes: u8) -> bool {
         remotes.len() < max_remotes.into()
     }
 
-    /// removes the given socket from the remotes list and the arena.
-    fn remove_remote(remotes: &mut HashMap<SocketAddr, u8>,
+    /// adds a bot of the given difficulty to the arena if there is a spare slot.
+    /// Returns the id of the bot's player.
+    pub fn add_bot(&mut self, difficulty: Difficulty) -> Option<EntityId> {
+        Server::add_bot_to(&mut self.bots, &mut self.arena, self.remotes.len(), self.max_remotes, difficulty)
+    }
+
+    /// adds a bot of the given difficulty to the arena if the remotes and the bots leave a
+    /// spare slot. Returns the id of the bot's player.
+    fn add_bot_to(bots: &mut HashMap<EntityId, Bot>,
+                  arena: &mut Arena,
+                  remotes_count: usize,
+                  max_remotes: u8,
+                  difficulty: Difficulty) -> Option<EntityId> {
+
+        if remotes_count + bots.len() >= max_remotes.into() {
+            return None;
+        }
+
+        let id = arena.add_player(Player::new("bot"));
+        if let Some(player) = arena.get_mut_player(id) {
+            player.name = format!("bot {}", id);
+        }
+
+        bots.insert(id, Bot::new(id, difficulty, id.to_bits().into()));
+        Some(id)
+    }
+
+    /// fills up all spare `max_remotes` slots with bots of the given difficulty, now and
+    /// whenever a remote leaves.
+    pub fn fill_with_bots(&mut self, difficulty: Difficulty) {
+        self.bot_difficulty = Some(difficulty);
+        while self.add_bot(difficulty).is_some() {}
+    }
+
+    pub fn get_bots(&self) -> &HashMap<EntityId, Bot> {
+        &self.bots
+    }
+
+    /// removes the bot with the fewest knockouts (the one with the highest id among those tied)
+    /// from the arena, if there are any, so a remote can take its slot.
+    fn remove_weakest_bot(bots: &mut HashMap<EntityId, Bot>, arena: &mut Arena) {
+        let get_knockouts = |id: EntityId| arena.get_stats().get(id).map_or(0, |stats| stats.knockouts);
+        let bot_id = bots.keys()
+            .copied()
+            .min_by_key(|id| (get_knockouts(*id), std::cmp::Reverse(*id)));
+
+        if let Some(id) = bot_id {
+            bots.remove(&id);
+            arena.remove_player(id);
+        }
+    }
+
+    /// removes the given socket from the remotes list and the arena. Its slot goes back to a
+    /// bot of `bot_difficulty`, if the server is filled with bots.
+    fn remove_remote(remotes: &mut HashMap<SocketAddr, EntityId>,
                      remote: &SocketAddr,
-                     arena: &mut Arena) {
+                     arena: &mut Arena,
+                     bots: &mut HashMap<EntityId, Bot>,
+                     max_remotes: u8,
+                     bot_difficulty: Option<Difficulty>) {
 
         let player_id = remotes.remove(remote);
         if let Some(id) = player_id {
             arena.remove_player(id);
             println!("Removed {}", id);
+
+            if let Some(difficulty) = bot_difficulty {
+                while Server::add_bot_to(bots, arena, remotes.len(), max_remotes, difficulty).is_some() {}
+            }
         }
     }
 
@@ -81,13 +161,28 @@ impl Server {
         Ok(())
     }
 
-    pub(crate) fn get_remotes(&self) -> &HashMap<SocketAddr, u8> {
+    /// sends the data to all connected clients, in the same order as the other messages sent
+    /// with this function (e.g. a block breaking and then growing back).
+    pub fn send_ordered_message(&self, message: &Message) -> Result<()> {
+        for remote in self.remotes.keys() {
+            let packet = Packet::reliable_ordered(*remote, message.to_vec(), None);
+            Server::send_packet(&self.sender, packet)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_remotes(&self) -> &HashMap<SocketAddr, EntityId> {
         &self.remotes
     }
 
     /// sends the data to a remote socket.
     fn send_to(sender: &Sender<Packet>, remote: &SocketAddr, message: &Message) -> Result<()> {
         let packet = Packet::reliable_unordered(*remote, message.to_vec());
+        Server::send_packet(sender, packet)
+    }
+
+    /// sends the packet through the socket.
+    fn send_packet(sender: &Sender<Packet>, packet: Packet) -> Result<()> {
         match sender.try_send(packet) {
             Ok(_) => Ok(()),
             Err(e) => Err(io::Error::new(ErrorKind::Other, e)),
@@ -98,14 +193,43 @@ impl Server {
         &self.arena
     }
 
+    pub fn get_mode(&self) -> &GameMode {
+        &self.mode
+    }
+
+    /// starts a new match of the given game mode, keeping the players in the arena.
+    /// The statistics of the players start over.
+    pub fn set_mode(&mut self, modetype: GameModeType) {
+        self.mode = GameMode::new(modetype, &self.arena);
+        self.arena.reset_stats();
+    }
+
+    /// sets the time (in ms) from the start of the current match to sudden death, or disables
+    /// sudden death if None.
+    pub fn set_time_limit(&mut self, time_limit: Option<u128>) {
+        self.mode.set_time_limit(time_limit);
+    }
+
+    /// runs the `script` after every update of the game mode, or stops running a script if None.
+    pub fn set_script(&mut self, script: Option<ModeScript>) {
+        self.script = script;
+    }
+
+    /// obtains the events that happened in the arena during the last tick.
+    pub fn get_events(&self) -> &Vec<GameEvent> {
+        &self.events
+    }
+
     /// function to call when the client receives a packet.
+    #[allow(clippy::too_many_arguments)]
     fn on_packet_recv(sender: &Sender<Packet>,
                       arena: &mut Arena,
-                      remotes: &mut HashMap<SocketAddr, u8>,
-                      inputs: &mut HashMap<u8, InputMask>,
+                      remotes: &mut HashMap<SocketAddr, EntityId>,
+                      inputs: &mut HashMap<EntityId, InputMask>,
+                      bots: &mut HashMap<EntityId, Bot>,
                       max_remotes: u8,
-                      packet: Packet,
-                      next_id: &mut u8) {
+                      bot_difficulty: Option<Difficulty>,
+                      packet: Packet) {
 
         let payload = packet.payload();
         let addr = packet.addr();
@@ -115,27 +239,37 @@ impl Server {
             match message.header {
                 HeaderByte::Connect => {
                     // acknowledges the player, sends them verification, containing map and ID.
+                    // the player is added to the arena right away, so the arena gives out the ID.
+                    let id = match remotes.get(&addr) {
+                        Some(id) => Some(*id),
+                        None => {
+                            // bots give up their slots for remotes.
+                            if remotes.len() + bots.len() >= max_remotes.into() {
+                                Server::remove_weakest_bot(bots, arena);
+                            }
 
-                    // finds the next available free number
-                    let mut id = *next_id;
-                    while arena.get_players().contains_key(&id) {
-                        id = id.wrapping_add(1);
-                    }
-
-                    let verification = Message::write_verify(id, arena.get_map());
-                    Server::send_to(sender, &addr, &verification).unwrap();
+                            Server::add_remote(remotes, arena, &addr, max_remotes)
+                        },
+                    };
 
-                    // the next id gets updated.
-                    *next_id = next_id.wrapping_add(1);
+                    if let Some(id) = id {
+                        let verification = Message::write_verify(id, arena.get_map());
+                        Server::send_to(sender, &addr, &verification).unwrap();
+                    }
                 },
 
                 HeaderByte::Request => {
-                    // sends the compressed arena state.
+                    // sets the name and class of the remote's player. Requests with an unknown
+                    // class are ignored.
                     let request_data  = message.read_request();
+                    let curr_time = arena.get_time();
                     if let Ok((id, player)) = request_data {
-                        let successful = Server::add_remote(remotes, &addr, max_remotes, id);
-                        if successful {
-                            arena.add_player(player, id);
+                        let is_remote_player = remotes.get(&addr) == Some(&id);
+                        if let (true, Some(arena_player)) = (is_remote_player, arena.get_mut_player(id)) {
+                            if arena_player.get_class() != player.get_class() {
+                                arena_player.set_class(player.get_class(), curr_time);
+                            }
+                            arena_player.name = player.name;
                         }
                     }
                 },
@@ -153,7 +287,7 @@ impl Server {
                 HeaderByte::Disconnect => {
                     // removes the remote from the connected remotes.
                     // also, removes player from arena if possible.
-                    Server::remove_remote(remotes, &addr, arena);
+                    Server::remove_remote(remotes, &addr, arena, bots, max_remotes, bot_difficulty);
                 },
 
                 _ => println!("Unknown packet received")
@@ -171,13 +305,19 @@ impl Server {
                                            &mut self.arena,
                                            &mut self.remotes,
                                            &mut self.inputs,
+                                           &mut self.bots,
                                            self.max_remotes,
-                                           packet,
-                                           &mut self.next_id);
+                                           self.bot_difficulty,
+                                           packet);
                 },
 
                 SocketEvent::Timeout(addr) => {
-                    Server::remove_remote(&mut self.remotes, &addr, &mut self.arena);
+                    Server::remove_remote(&mut self.remotes,
+                                          &addr,
+                                          &mut self.arena,
+                                          &mut self.bots,
+                                          self.max_remotes,
+                                          self.bot_difficulty);
                 },
 
                 _ => {},
@@ -190,6 +330,19 @@ impl Server {
         self.inputs.clear();
         self.receive();
 
-        self.arena.update(dt, &self.inputs);
+        for bot in self.bots.values_mut() {
+            self.inputs.insert(bot.get_id(), bot.think(&self.arena));
+        }
+
+        self.events = self.arena.update(dt, &self.inputs);
+        self.mode.update(&mut self.arena, &self.events);
+
+        // a failing script is stopped, so the match goes on without it.
+        if let Some(script) = &mut self.script {
+            if let Err(e) = script.update(&mut self.arena, &mut self.mode, &self.events, dt) {
+                println!("Stopping the mode script after an error: {}", e);
+                self.script = None;
+            }
+        }
     }
 }
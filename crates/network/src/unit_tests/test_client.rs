@@ -7,7 +7,7 @@ fn client_connect() {
     let mut s1 = Server::new(DEFAULT_PORT - 2, 4).unwrap();
 
     let t = thread::spawn(|| {
-        let mut c1 = Client::new(DEFAULT_PORT + 2, "test").unwrap();
+        let mut c1 = Client::new(DEFAULT_PORT + 2, "test", game::characterscatalog::CharacterClass::Brawler).unwrap();
         // sends connect request
         c1.connect(&SocketAddr::from(([0,0,0,0], DEFAULT_PORT-2))).unwrap();
         sleep(Duration::from_millis(50));
@@ -39,7 +39,7 @@ fn client_verification() {
     let mut s1 = Server::new(server_port, 2).unwrap();
 
     let t1 = thread::spawn(move || {
-        let mut c1 = Client::new(client_port, "test").unwrap();
+        let mut c1 = Client::new(client_port, "test", game::characterscatalog::CharacterClass::Scout).unwrap();
         // sends connect request
         c1.connect(&SocketAddr::from(([0,0,0,0], server_port))).unwrap();
         sleep(Duration::from_millis(50));
@@ -47,7 +47,7 @@ fn client_verification() {
         sleep(Duration::from_millis(50));
         c1.receive();
         assert!(c1.try_get_id().is_some());
-        assert_eq!(c1.try_get_id().unwrap(), 0);
+        assert_eq!(c1.try_get_id().unwrap(), game::entity::EntityId::new(0, 0));
 
         assert!(c1.try_get_arena().is_some());
     });
@@ -71,6 +71,55 @@ fn client_verification() {
     assert!(connected_client.is_some());
 
     let connected_client_id = connected_client.unwrap();
-    assert_eq!(*connected_client_id, 0);
+    assert_eq!(*connected_client_id, game::entity::EntityId::new(0, 0));
+
+    // the player of the client plays the class it asked for.
+    let player = s1.get_arena().get_player(*connected_client_id).unwrap();
+    assert_eq!(player.get_class(), game::characterscatalog::CharacterClass::Scout);
     t1.join().unwrap();
 }
+
+#[test]
+fn bots_give_up_and_take_back_slots() {
+    use game::bot::Difficulty;
+    use crate::message::Message;
+
+    let server_port = DEFAULT_PORT - 3;
+    let client_port = DEFAULT_PORT + 3;
+    let mut s1 = Server::new(server_port, 3).unwrap();
+    s1.fill_with_bots(Difficulty::Easy);
+    assert_eq!(s1.get_bots().len(), 3);
+
+    // the newest of the bots, tied with no knockouts, leaves for the remote.
+    let newest_bot = *s1.get_bots().keys().max().unwrap();
+
+    let t1 = thread::spawn(move || {
+        let mut c1 = Client::new(client_port, "test", game::characterscatalog::CharacterClass::Brawler).unwrap();
+        c1.connect(&SocketAddr::from(([0,0,0,0], server_port))).unwrap();
+        sleep(Duration::from_millis(50));
+        c1.receive();
+        sleep(Duration::from_millis(50));
+        c1.receive();
+        sleep(Duration::from_millis(100));
+
+        let disconnect = Message::try_from(vec![crate::message::HeaderByte::Disconnect as u8]).unwrap();
+        c1.send_message(&disconnect).unwrap();
+        sleep(Duration::from_millis(50));
+    });
+
+    sleep(Duration::from_millis(50));
+    s1.receive();
+    sleep(Duration::from_millis(50));
+    s1.receive();
+
+    assert_eq!(s1.get_remotes().len(), 1);
+    assert_eq!(s1.get_bots().len(), 2);
+    assert!(!s1.get_bots().contains_key(&newest_bot));
+
+    t1.join().unwrap();
+    s1.receive();
+
+    // the slot of the remote is filled with a bot again.
+    assert_eq!(s1.get_remotes().len(), 0);
+    assert_eq!(s1.get_bots().len(), 3);
+}
@@ -5,3 +5,209 @@ fn headerbyte_from_test() {
     let a: u8 = 1;
     assert_eq!(HeaderByte::from_repr(a), Some(HeaderByte::Disconnect));
 }
+
+#[test]
+fn state_round_trip_test() {
+    use game::{arena::Arena, player::Player, pickup::{Pickup, PickupKind}, weapon::Bullet};
+    use game::effect::EffectType;
+    use game::weaponscatalog::{BulletType, WeaponType};
+    use glam::Vec2;
+    use crate::message::Message;
+
+    let mut arena = Arena::default();
+    let player = arena.add_player(Player::new("a"));
+    let owner = arena.add_player(Player::new("b"));
+    arena.remove_player(player);
+
+    let position = Vec2::new(500.0, 300.0);
+    let bullet = arena.add_bullet(Bullet::new(position, Vec2::ZERO, BulletType::Rifle, 0, owner));
+    let kind = PickupKind::Weapon(WeaponType::BasicPistol);
+    let pickup = arena.add_pickup(Pickup::new(position, kind));
+    let effect = arena.add_pickup(Pickup::new(position, PickupKind::Effect(EffectType::Shield)));
+
+    arena.get_mut_player(owner).unwrap().set_damage(42.5);
+    arena.get_mut_player(owner).unwrap().set_aim(Vec2::new(-1.0, -1.0).normalize());
+
+    let snapshot = Message::write_state(&arena).read_state().unwrap();
+    assert_eq!(snapshot.players.len(), 1);
+    assert_eq!(snapshot.players[0].id, owner);
+    assert_eq!(snapshot.players[0].damage, 42.5);
+    assert!(snapshot.players[0].aim.distance(Vec2::new(-1.0, -1.0).normalize()) < 0.01);
+    // the weapon of a new player is still reloading.
+    assert_eq!(snapshot.players[0].reload, 0.0);
+    assert_eq!(snapshot.bullets.len(), 1);
+    assert_eq!((snapshot.bullets[0].0, snapshot.bullets[0].1), (bullet, BulletType::Rifle));
+    assert!(snapshot.bullets[0].2.distance(position) < 1.0);
+    assert!(snapshot.thrown_weapons.is_empty());
+    assert_eq!(snapshot.pickups.len(), 2);
+    assert_eq!((snapshot.pickups[0].0, snapshot.pickups[0].1), (pickup, kind));
+    assert_eq!(snapshot.pickups[1].0, effect);
+
+    // the pickups are touched by the player on the next update, giving it a shield.
+    arena.get_mut_player(owner).unwrap().update_position(position, 0.0);
+    arena.update(1.0 / 60.0, &std::collections::HashMap::new());
+    let snapshot = Message::write_state(&arena).read_state().unwrap();
+    let effects = &snapshot.players[0].effects;
+    assert_eq!(effects.len(), 1);
+    assert_eq!(effects[0].get_effect_type(), EffectType::Shield);
+    assert!(effects[0].get_expires_at() > 0);
+}
+
+#[test]
+fn mode_round_trip_test() {
+    use game::{arena::Arena, player::Player};
+    use game::gamemode::{GameMode, GameModeType, Scorer};
+    use crate::message::Message;
+
+    let mut arena = Arena::default();
+    let mut mode = GameMode::new(GameModeType::CaptureTheFlag, &arena);
+    arena.add_player(Player::new("a"));
+    mode.update(&mut arena, &[]);
+
+    let state = Message::write_mode(&mode).read_mode().unwrap();
+    assert_eq!(state.modetype, GameModeType::CaptureTheFlag);
+    assert_eq!(state.scores, vec![(Scorer::Team(0), 0), (Scorer::Team(1), 0)]);
+    assert_eq!(state.winner, None);
+    assert_eq!(state.objectives.len(), 2);
+    for (objective, flag) in state.objectives.iter().zip(mode.get_objectives()) {
+        assert!(objective.distance(flag) < 1.0);
+    }
+
+    let mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    let state = Message::write_mode(&mode).read_mode().unwrap();
+    assert_eq!(state.scores, Vec::new());
+    assert!(state.objectives.is_empty());
+}
+
+#[test]
+fn stats_round_trip_test() {
+    use game::{arena::Arena, player::Player};
+    use crate::message::Message;
+
+    let mut arena = Arena::default();
+    let id = arena.add_player(Player::new("a"));
+    arena.update(1.0 / 60.0, &std::collections::HashMap::new());
+
+    let stats = Message::write_stats(arena.get_stats()).read_stats().unwrap();
+    assert_eq!(stats.get(id), arena.get_stats().get(id));
+    assert_eq!(stats.get(id).unwrap().name, "a");
+}
+
+#[test]
+fn verify_round_trip_test() {
+    use game::{entity::EntityId, map::Map};
+    use game::hazard::{Hazard, HazardType, WindZone};
+    use game::teleporter::TeleporterPair;
+    use glam::Vec2;
+    use crate::message::Message;
+
+    let mut map = Map::default();
+    map.add_hazard(Hazard::new(3, 5, HazardType::Spikes)).unwrap();
+    map.add_hazard(Hazard::new(7, 15, HazardType::Lava)).unwrap();
+    let zone = WindZone::new(Vec2::new(200.0, 150.5), Vec2::new(600.0, 400.0), Vec2::new(-12000.0, 0.0));
+    map.add_wind_zone(zone);
+    map.add_teleporter_pair(TeleporterPair::new((2, 1), (5, 14))).unwrap();
+
+    let id = EntityId::new(3, 1);
+    let (read_id, read_map) = Message::write_verify(id, &map).read_verify().unwrap();
+    assert_eq!(read_id, id);
+    assert_eq!(read_map.get_mapblocks_list(), map.get_mapblocks_list());
+    assert_eq!(read_map.get_hazards(), map.get_hazards());
+    assert_eq!(read_map.get_wind_zones(), &[zone]);
+    assert_eq!(read_map.get_teleporter_pairs(), map.get_teleporter_pairs());
+}
+
+#[test]
+fn block_changes_round_trip_test() {
+    use game::block::BlockType;
+    use game::map::VERTICAL_BLOCKS;
+    use crate::message::Message;
+
+    let changes = vec![(3, 6, None), (VERTICAL_BLOCKS - 1, 0, Some(BlockType::CrateBlock))];
+    let read_changes = Message::write_block_changes(&changes).read_block_changes().unwrap();
+    assert_eq!(read_changes, changes);
+
+    // blocks outside of the map are rejected.
+    let outside = Message::write_block_changes(&[(VERTICAL_BLOCKS, 0, None)]);
+    assert!(outside.read_block_changes().is_err());
+}
+
+#[test]
+fn sudden_death_mode_round_trip_test() {
+    use game::{arena::Arena, map::Map, player::Player};
+    use game::gamemode::{GameMode, GameModeType};
+    use game::suddendeath::CollapseSchedule;
+    use crate::message::Message;
+
+    let mut map = Map::default();
+    map.set_collapse_schedule(CollapseSchedule::new(1000, 1000, 4, 3).unwrap());
+    let mut arena = Arena::new(map);
+    let mut mode = GameMode::new(GameModeType::FreeForAll, &arena);
+    mode.set_time_limit(Some(0));
+    arena.add_player(Player::new("a"));
+    arena.add_player(Player::new("b"));
+    mode.update(&mut arena, &[]);
+
+    let state = Message::write_mode(&mode).read_mode().unwrap();
+    assert!(state.sudden_death);
+    assert!(!state.collapsing.is_empty());
+    assert_eq!(state.collapsing, mode.get_collapsing_cells());
+}
+
+#[test]
+fn request_round_trip_test() {
+    use game::characterscatalog::CharacterClass;
+    use game::entity::EntityId;
+    use crate::message::Message;
+
+    let id = EntityId::new(2, 0);
+    let (read_id, player) = Message::write_request("tank", id, CharacterClass::Tank).read_request().unwrap();
+    assert_eq!(read_id, id);
+    assert_eq!(player.name, "tank");
+    assert_eq!(player.get_class(), CharacterClass::Tank);
+
+    // unknown classes are rejected.
+    let mut message = Message::write_request("cheater", id, CharacterClass::Tank);
+    message.data[4] = u8::MAX;
+    assert!(message.read_request().is_err());
+}
+
+#[test]
+fn input_round_trip_test() {
+    use game::input::{Input, InputMask};
+    use crate::message::{HeaderByte, Message};
+
+    let mut input = InputMask::new();
+    input.add_mask(Input::Left);
+    input.add_mask(Input::Dash);
+    let read_input = Message::write_input(input).read_input();
+    assert!(read_input.has_mask(Input::Left));
+    assert!(read_input.has_mask(Input::Dash));
+    assert!(!read_input.has_mask(Input::Right));
+
+    // the reload bit only travels in the versioned format.
+    let mut reload = InputMask::new();
+    reload.add_mask(Input::Reload);
+    assert!(Message::write_input(reload).read_input().has_mask(Input::Reload));
+    let unversioned = Message { header: HeaderByte::Input, data: vec![0, 1] };
+    assert!(!unversioned.read_input().has_mask(Input::Reload));
+
+    // the first format, a single byte without a version, is still understood.
+    let legacy = Message { header: HeaderByte::Input, data: vec![0x01] };
+    let read_input = legacy.read_input();
+    assert!(read_input.has_mask(Input::Left));
+    assert!(!read_input.has_mask(Input::Right));
+
+    // the melee bit only travels in the versioned format.
+    let mut melee = InputMask::new();
+    melee.add_mask(Input::Melee);
+    assert!(Message::write_input(melee).read_input().has_mask(Input::Melee));
+    let legacy = Message { header: HeaderByte::Input, data: vec![0x80] };
+    assert!(!legacy.read_input().has_mask(Input::Melee));
+
+    // unknown versions are read as no input.
+    let mut message = Message::write_input(input);
+    message.data[0] = u8::MAX;
+    let data: u32 = message.read_input().into();
+    assert_eq!(data, 0);
+}
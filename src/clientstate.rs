@@ -1,13 +1,21 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::thread;
 use std::time::Duration;
 
 use game::player::Player;
 use game::arena::Arena;
+use game::pickup::{PickupKind, PICKUP_SIZE};
+use game::gamemode::{GameModeType, ModeState, Scorer, FLAG_SIZE};
+use game::stats::MatchStats;
+use game::characterscatalog::CharacterClass;
+use game::teleporter;
+use game::{BLOCK_HEIGHT, BLOCK_WIDTH, PLAYER_HEIGHT};
+use game::map::{HORIZONTAL_BLOCKS, VERTICAL_BLOCKS};
 use game::input::{InputMask, Input};
 use ggez::Context;
 use ggez::event::KeyCode;
-use ggez::graphics::spritebatch::SpriteBatch;
+use ggez::graphics::spritebatch::{SpriteBatch, SpriteIdx};
 use ggez::{event::EventHandler, GameResult, timer, graphics};
 use ggez::graphics::{Color, Mesh, DrawMode, DrawParam};
 use glam::Vec2;
@@ -18,18 +26,32 @@ use crate::{BACKGROUND_COLOR, TICK_RATE};
 use crate::viewport::Viewport;
 use std::io::Result;
 
+/// sprite of the block at each row and column of the map.
+type BlockSprites = HashMap<(usize, usize), SpriteIdx>;
+
+/// colors of the teleporter pairs, in the order of the pairs in the map.
+const TELEPORTER_COLORS: [Color; 4] = [
+    Color::new(0.0, 1.0, 1.0, 1.0),
+    Color::new(1.0, 0.0, 1.0, 1.0),
+    Color::new(1.0, 0.6, 0.0, 1.0),
+    Color::new(0.4, 1.0, 0.2, 1.0),
+];
+
 #[derive(Debug)]
 pub struct ClientState {
     client: Client,
+    atlas: Atlas,
     mapmesh: SpriteBatch,
+    /// sprites of the blocks in `mapmesh`.
+    block_sprites: BlockSprites,
     inputmask: InputMask,
 }
 
 
 
 impl ClientState {
-    pub fn new(ctx: &mut Context, atlas: &Atlas, server: &SocketAddr, name: &str) -> Result<ClientState> {
-        let mut client = Client::new(0, name)?;
+    pub fn new(ctx: &mut Context, atlas: Atlas, server: &SocketAddr, name: &str, class: CharacterClass) -> Result<ClientState> {
+        let mut client = Client::new(0, name, class)?;
         client.connect(server)?;
 
         // keep trying to receive, until timeout.
@@ -41,40 +63,208 @@ impl ClientState {
         }
 
         let arena = client.try_get_arena().expect("Failed to connect to server");
-        let mapmesh = ClientState::build_mapmesh(arena, ctx, atlas).unwrap();
+        let (mapmesh, block_sprites) = ClientState::build_mapmesh(arena, ctx, &atlas).unwrap();
         let inputmask = InputMask::new();
-        Ok(ClientState {client, mapmesh, inputmask})
+        Ok(ClientState {client, atlas, mapmesh, block_sprites, inputmask})
     }
 
     /// TODO: Use player sprite rather than just a rectangle.
     pub fn draw_player(ctx: &mut ggez::Context, player: &Player, offset: Vec2, color: Color) -> GameResult {
-        let [x, y] = player.position.to_array();
+        let [x, y] = player.get_position().to_array();
         let playerrect = ggez::graphics::Rect {x, y, w: player.width, h: player.height};
         let meshrect = Mesh::new_rectangle(ctx, DrawMode::fill(), playerrect, color)?;
         graphics::draw(ctx, &meshrect, DrawParam::default().dest(offset))?;
+
+        // draws the gun as a bar from the center of the player, rotated towards the aim.
+        let aim = player.get_aim();
+        let gunrect = ggez::graphics::Rect {x: 0.0, y: -3.0, w: player.width * 0.75, h: 6.0};
+        let gunmesh = Mesh::new_rectangle(ctx, DrawMode::fill(), gunrect, Color::BLACK)?;
+        let center = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
+        let param = DrawParam::default()
+            .dest(offset + center)
+            .rotation(aim.y.atan2(aim.x));
+
+        graphics::draw(ctx, &gunmesh, param)?;
+        Ok(())
+    }
+
+    /// draws the progress of the reload as a bar below the player, while it is reloading.
+    fn draw_reload(ctx: &mut ggez::Context, player: &Player, progress: f32, offset: Vec2) -> GameResult {
+        if progress >= 1.0 {
+            return Ok(());
+        }
+
+        let [x, y] = (player.get_position() + Vec2::new(0.0, player.height + 4.0)).to_array();
+        let background = ggez::graphics::Rect {x, y, w: player.width, h: 4.0};
+        let bar = ggez::graphics::Rect {x, y, w: player.width * progress, h: 4.0};
+        for (r, color) in [(background, Color::BLACK), (bar, Color::WHITE)] {
+            let mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), r, color)?;
+            graphics::draw(ctx, &mesh, DrawParam::default().dest(offset))?;
+        }
+        Ok(())
+    }
+
+    /// draws the active effects of the player and their remaining time on the top left corner.
+    fn draw_effects(ctx: &mut ggez::Context, player: &Player, curr_time: u128) -> GameResult {
+        for (i, effect) in player.get_effects().iter().enumerate() {
+            let seconds = effect.get_remaining(curr_time) as f32 / 1000.0;
+            let text = graphics::Text::new(format!("{} {:.1}s", effect.get_effect_type(), seconds));
+            let dest = Vec2::new(10.0, 10.0 + i as f32 * 20.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest(dest))?;
+        }
+        Ok(())
+    }
+
+    /// draws the objectives of the game mode, the blocks about to collapse in sudden death, and
+    /// the scores on the top right corner.
+    fn draw_mode(ctx: &mut ggez::Context, arena: &Arena, mode: &ModeState, offset: Vec2) -> GameResult {
+        for (i, position) in mode.objectives.iter().enumerate() {
+            let [x, y] = position.to_array();
+            let (r, color) = match mode.modetype {
+                GameModeType::KingOfTheHill => {
+                    let r = ggez::graphics::Rect {x, y, w: BLOCK_WIDTH, h: PLAYER_HEIGHT};
+                    (r, Color::new(1.0, 1.0, 0.0, 0.3))
+                },
+
+                _ => {
+                    let r = ggez::graphics::Rect {x, y, w: FLAG_SIZE, h: FLAG_SIZE};
+                    (r, if i == 0 { Color::RED } else { Color::BLUE })
+                },
+            };
+
+            let mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), r, color)?;
+            graphics::draw(ctx, &mesh, DrawParam::default().dest(offset))?;
+        }
+
+        // the blocks about to collapse blink red.
+        let alpha = if (timer::time_since_start(ctx).as_millis() / 250).is_multiple_of(2) { 0.6 } else { 0.2 };
+        for (row, col) in mode.collapsing.iter() {
+            let [x, y] = arena.get_block_position_at(*row, *col).to_array();
+            let r = ggez::graphics::Rect {x, y, w: BLOCK_WIDTH, h: BLOCK_HEIGHT};
+            let mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), r, Color::new(1.0, 0.0, 0.0, alpha))?;
+            graphics::draw(ctx, &mesh, DrawParam::default().dest(offset))?;
+        }
+
+        let scorer_name = |scorer: Scorer| match scorer {
+            Scorer::Player(id) => arena.get_player(id).map_or(id.to_string(), |p| p.name.clone()),
+            Scorer::Team(team) => format!("team {}", team),
+        };
+
+        let mut lines = vec![mode.modetype.to_string()];
+        lines.extend(mode.scores.iter().map(|(scorer, score)| format!("{}: {}", scorer_name(*scorer), score)));
+        if mode.sudden_death {
+            lines.push("Sudden death!".to_string());
+        }
+        if let Some(winner) = mode.winner {
+            lines.push(format!("{} wins!", scorer_name(winner)));
+        }
+
+        let x = graphics::drawable_size(ctx).0 - 200.0;
+        for (i, line) in lines.into_iter().enumerate() {
+            let text = graphics::Text::new(line);
+            let dest = Vec2::new(x, 10.0 + i as f32 * 20.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest(dest))?;
+        }
         Ok(())
     }
 
-    /// builds a mapmesh from a given arena.
-    fn build_mapmesh(arena: &Arena, ctx: &mut Context, atlas: &Atlas) -> GameResult<SpriteBatch> {
+    /// draws the statistics of every player at the end of the match.
+    fn draw_results(ctx: &mut ggez::Context, stats: &MatchStats) -> GameResult {
+        let mut lines = vec![format!("{:<16} {:>6} {:>6} {:>6} {:>9} {:>9}", "name", "KOs", "deaths", "acc", "dealt", "taken")];
+        for (_, p) in stats.iter() {
+            lines.push(format!("{:<16} {:>6} {:>6} {:>5.0}% {:>8.0}% {:>8.0}%",
+                               p.name, p.knockouts, p.deaths, p.accuracy * 100.0, p.damage_dealt, p.damage_taken));
+        }
+
+        let (w, h) = graphics::drawable_size(ctx);
+        let top = h / 2.0 - lines.len() as f32 * 10.0;
+        for (i, line) in lines.into_iter().enumerate() {
+            let text = graphics::Text::new(line);
+            let dest = Vec2::new(w / 2.0 - 250.0, top + i as f32 * 20.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest(dest))?;
+        }
+        Ok(())
+    }
+
+    /// builds a mapmesh from a given arena. Also returns the sprite of each block in the mapmesh.
+    fn build_mapmesh(arena: &Arena,
+                     ctx: &mut Context,
+                     atlas: &Atlas) -> GameResult<(SpriteBatch, BlockSprites)> {
         let spritesheet_image = graphics::Image::new(ctx, "/sprites/platforms.png")?;
         let mut spritebatch = graphics::spritebatch::SpriteBatch::new(spritesheet_image);
 
         // Nearest or Linear
         spritebatch.set_filter(graphics::FilterMode::Nearest);
 
-        for block in arena.get_blocks_iter() {
-            let spritename = block.blocktype.to_string() + ".png";
-            let (x, y, w, h) = (block.x, block.y, block.w, block.h);
-            let dest = Vec2::new(x, y);
-            let size = Vec2::new(w, h);
+        // wind zones are drawn faintly behind the blocks.
+        for zone in arena.get_map().get_wind_zones() {
+            let (dest, size) = (zone.get_min(), zone.get_max() - zone.get_min());
+            let sprite_rect = atlas.create_sprite("WindZone.png", size).draw_to(dest);
+            spritebatch.add(sprite_rect.color(Color::new(1.0, 1.0, 1.0, 0.25)));
+        }
 
-            // cut out image from the spritesheet and resize.
-            let sprite_rect = atlas.create_sprite(&spritename, size).draw_to(dest);
+        let mut block_sprites = HashMap::new();
+        for row in 0..VERTICAL_BLOCKS {
+            for col in 0..HORIZONTAL_BLOCKS {
+                if arena.get_block(row, col).is_some() {
+                    let sprite = spritebatch.add(ClientState::get_block_sprite(arena, atlas, row, col));
+                    block_sprites.insert((row, col), sprite);
+                }
+            }
+        }
+
+        for hazard in arena.get_map().get_hazards() {
+            let spritename = hazard.get_hazard_type().to_string() + ".png";
+            let (min, max) = hazard.get_rect();
+            let sprite_rect = atlas.create_sprite(&spritename, max - min).draw_to(min);
             spritebatch.add(sprite_rect);
         }
 
-        Ok(spritebatch)
+        // both teleporters of a pair share a color.
+        for (i, pair) in arena.get_map().get_teleporter_pairs().iter().enumerate() {
+            let color = TELEPORTER_COLORS[i % TELEPORTER_COLORS.len()];
+            for cell in [pair.get_first(), pair.get_second()] {
+                let (min, max) = teleporter::get_teleporter_rect(cell);
+                let sprite_rect = atlas.create_sprite("Teleporter.png", max - min).draw_to(min);
+                spritebatch.add(sprite_rect.color(color));
+            }
+        }
+
+        Ok((spritebatch, block_sprites))
+    }
+
+    /// cuts out the image of the block at `row` and `col` from the spritesheet, resized to the
+    /// block. A missing block gets an invisible sprite.
+    fn get_block_sprite(arena: &Arena, atlas: &Atlas, row: usize, col: usize) -> DrawParam {
+        match arena.get_block(row, col) {
+            Some(blocktype) => {
+                let spritename = blocktype.to_string() + ".png";
+                let size = Vec2::new(BLOCK_WIDTH, BLOCK_HEIGHT);
+                atlas.create_sprite(&spritename, size).draw_to(arena.get_block_position_at(row, col))
+            },
+
+            None => DrawParam::default().color(Color::new(0.0, 0.0, 0.0, 0.0)),
+        }
+    }
+
+    /// updates the sprites of the blocks which broke or grew back, leaving the rest of the
+    /// mapmesh as it is.
+    fn update_block_sprites(&mut self) -> GameResult {
+        let changed_blocks = self.client.take_changed_blocks();
+        let arena = self.client.try_get_arena().expect("Cannot draw arena");
+
+        for (row, col) in changed_blocks {
+            let sprite = ClientState::get_block_sprite(arena, &self.atlas, row, col);
+            match self.block_sprites.get(&(row, col)) {
+                Some(handle) => self.mapmesh.set(*handle, sprite)?,
+                None => {
+                    let handle = self.mapmesh.add(sprite);
+                    self.block_sprites.insert((row, col), handle);
+                },
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -93,6 +283,8 @@ impl EventHandler for ClientState {
             self.client.receive();
         }
 
+        self.update_block_sprites()?;
+
         Ok(())
     }
 
@@ -114,9 +306,10 @@ impl EventHandler for ClientState {
 
         // draw self player
         ClientState::draw_player(ctx, player, offset, Color::GREEN)?;
+        ClientState::draw_reload(ctx, player, self.client.get_reload_progress(), offset)?;
 
         for (player_id, p) in arena.get_players().iter() {
-            if *player_id != id {
+            if player_id != id {
                 ClientState::draw_player(ctx, p, offset, Color::WHITE)?;
             }
         }
@@ -131,6 +324,39 @@ impl EventHandler for ClientState {
             graphics::draw(ctx, &mesh, DrawParam::default().dest(offset))?;
         }
 
+        // draws thrown weapons and pickups
+        let weapons = arena.get_thrown_weapons().iter().map(|(_, w)| (w.get_position(), Color::YELLOW));
+        let pickups = arena.get_pickups().iter().map(|(_, p)| {
+            let color = match p.get_kind() {
+                PickupKind::Weapon(_) => Color::CYAN,
+                PickupKind::Effect(_) => Color::BLUE,
+            };
+            (p.get_position(), color)
+        });
+        for (position, color) in weapons.chain(pickups) {
+            let [x, y] = position.to_array();
+            let r = ggez::graphics::Rect {x, y, w: PICKUP_SIZE, h: PICKUP_SIZE};
+            let mesh = Mesh::new_rectangle(ctx, DrawMode::fill(), r, color)?;
+            graphics::draw(ctx, &mesh, DrawParam::default().dest(offset))?;
+        }
+
+        // draws the damage of every player above its head.
+        for (_, p) in arena.get_players().iter() {
+            let text = graphics::Text::new(format!("{:.0}%", p.get_damage()));
+            let dest = offset + p.get_position() - Vec2::new(0.0, 20.0);
+            graphics::draw(ctx, &text, DrawParam::default().dest(dest))?;
+        }
+
+        ClientState::draw_effects(ctx, player, arena.get_time())?;
+
+        if let Some(mode) = self.client.try_get_mode() {
+            ClientState::draw_mode(ctx, arena, mode, offset)?;
+        }
+
+        if let Some(stats) = self.client.try_get_stats() {
+            ClientState::draw_results(ctx, stats)?;
+        }
+
         graphics::present(ctx)
     }
 
@@ -144,6 +370,9 @@ impl EventHandler for ClientState {
             KeyCode::O => self.inputmask.add_mask(Input::Shoot),
             KeyCode::P => self.inputmask.add_mask(Input::Bomb),
             KeyCode::I => self.inputmask.add_mask(Input::Throw),
+            KeyCode::K => self.inputmask.add_mask(Input::Melee),
+            KeyCode::R => self.inputmask.add_mask(Input::Reload),
+            KeyCode::L => self.inputmask.add_mask(Input::Dash),
             _ => ()
         }
     }
@@ -157,6 +386,9 @@ impl EventHandler for ClientState {
             KeyCode::O => self.inputmask.remove_mask(Input::Shoot),
             KeyCode::P => self.inputmask.remove_mask(Input::Bomb),
             KeyCode::I => self.inputmask.remove_mask(Input::Throw),
+            KeyCode::K => self.inputmask.remove_mask(Input::Melee),
+            KeyCode::R => self.inputmask.remove_mask(Input::Reload),
+            KeyCode::L => self.inputmask.remove_mask(Input::Dash),
             _ => ()
         }
     }
@@ -13,8 +13,12 @@ pub const ASPECT_RATIO_Y: f32 = 9.0;
 
 pub const SPRITE_JSON: &str = "sprites/platforms.json";
 pub const MAPS_DIR: &str = "maps";
+pub const SCRIPTS_DIR: &str = "scripts";
 pub const FONTS_DIR: &str = "fonts";
 pub const BACKGROUND_COLOR: u32 = 0x4d6a77;
 
+/// file the statistics of the players are exported to when a match ends.
+pub const STATS_FILE: &str = "match_stats.json";
+
 pub const TICK_RATE: u32 = 60;
 pub const DELTA_T: f32 = 1.0 / TICK_RATE as f32;
@@ -2,6 +2,10 @@ use micromayhem::clientstate::ClientState;
 use micromayhem::serverstate::ServerState;
 use network::DEFAULT_PORT;
 use network::server::Server;
+use game::bot::Difficulty;
+use game::gamemode::GameModeType;
+use game::characterscatalog::CharacterClass;
+use game::script::ModeScript;
 use std::net::ToSocketAddrs;
 use std::{env, io};
 use std::path::{Path, self, PathBuf};
@@ -9,24 +13,67 @@ use ggez::{GameResult, ContextBuilder, event};
 
 use gui::spriteloader::Atlas;
 use micromayhem::configuration;
-use micromayhem::{RESOURCES, AUTHOR, GAME_TITLE, SPRITE_JSON};
+use micromayhem::{RESOURCES, AUTHOR, GAME_TITLE, SCRIPTS_DIR, SPRITE_JSON};
 
 fn main() -> GameResult {
     let args: Vec<String> = env::args().collect();
     if args.len() > 1 && &args[1] == "server" {
-        run_server();
+        let with_bots = args[2..].iter().any(|arg| arg == "bots");
+        let modetype = args[2..].iter()
+            .find_map(|arg| parse_mode(arg))
+            .unwrap_or(GameModeType::FreeForAll);
+        let script = args[2..].iter().find_map(|arg| arg.strip_prefix("script="));
+        let time_limit = args[2..].iter()
+            .find_map(|arg| arg.strip_prefix("time_limit="))
+            .map(|seconds| seconds.parse::<u128>().expect("The time limit should be a number of seconds") * 1000);
+
+        run_server(with_bots, modetype, time_limit, script);
         Ok(())
     } else {
         run_client()
     }
 }
 
+/// obtains the game mode named by a command line argument.
+fn parse_mode(arg: &str) -> Option<GameModeType> {
+    match arg {
+        "ffa" => Some(GameModeType::FreeForAll),
+        "koth" => Some(GameModeType::KingOfTheHill),
+        "gungame" => Some(GameModeType::GunGame),
+        "ctf" => Some(GameModeType::CaptureTheFlag),
+        _ => None,
+    }
+}
+
+/// obtains the character class named by the player, ignoring the case.
+fn parse_class(name: &str) -> Option<CharacterClass> {
+    (0..).map_while(CharacterClass::from_repr)
+        .find(|class| class.to_string().eq_ignore_ascii_case(name))
+}
+
 /// runs the server side of the game, which only handles physics and player interaction.
-fn run_server() {
-    let server = Server::new(DEFAULT_PORT, 4).expect("Cannot create server");
+///
+/// If `with_bots` is set, then the empty player slots are filled with bots. `time_limit` (in ms)
+/// replaces the time limit of the mode before sudden death, if given. `script` is the name of a
+/// mode script in the scripts directory of the resources, run on top of the mode.
+fn run_server(with_bots: bool, modetype: GameModeType, time_limit: Option<u128>, script: Option<&str>) {
+    let mut server = Server::new(DEFAULT_PORT, 4).expect("Cannot create server");
+    if with_bots {
+        server.fill_with_bots(Difficulty::Normal);
+    }
+    if let Some(name) = script {
+        let path = load_resources().join(SCRIPTS_DIR).join(format!("{}.rhai", name));
+        let script = ModeScript::from_file(&path).expect("Unable to load the mode script");
+        server.set_script(Some(script));
+    }
+
     let mut serverstate = ServerState::new(server);
+    serverstate.set_mode(modetype);
+    if time_limit.is_some() {
+        serverstate.set_time_limit(time_limit);
+    }
 
-    println!("Starting server on port {}", DEFAULT_PORT);
+    println!("Starting {} server on port {}", modetype, DEFAULT_PORT);
     serverstate.run();
 }
 
@@ -46,6 +93,11 @@ fn run_client() -> GameResult {
     io::stdin().read_line(&mut name)?;
     name = name.trim().to_string();
 
+    println!("Choose your character class (Brawler, Scout or Tank) below:");
+    let mut class_name = String::new();
+    io::stdin().read_line(&mut class_name)?;
+    let class = parse_class(class_name.trim()).unwrap_or(CharacterClass::Brawler);
+
     // loads atlas
     let resource_dir = load_resources();
     let atlaspath = resource_dir.join(Path::new(SPRITE_JSON));
@@ -56,7 +108,7 @@ fn run_client() -> GameResult {
     cb = cb.add_resource_path(resource_dir);
 
     let (mut ctx, event_loop) = cb.build()?;
-    let g = ClientState::new(&mut ctx, &atlas, &address, &name)
+    let g = ClientState::new(&mut ctx, atlas, &address, &name, class)
         .expect("Unable to create new client state");
 
     event::run(ctx, event_loop, g);
@@ -1,20 +1,44 @@
-use std::{time::{Instant, Duration}, thread};
+use std::{time::{Instant, Duration}, thread, fs};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 
+use game::event::GameEvent;
+use game::gamemode::GameModeType;
 use network::{server::Server, message::Message};
 
-use crate::DELTA_T;
+use crate::{DELTA_T, STATS_FILE};
 
 pub struct ServerState {
     server: Server,
+    /// whether the statistics of the current match were already exported.
+    stats_exported: bool,
 }
 
 impl ServerState {
     pub fn new(server: Server) -> Self {
-        Self { server }
+        Self { server, stats_exported: false }
     }
 
+    /// starts a new match of the given game mode, whose statistics are exported once it ends.
+    pub fn set_mode(&mut self, modetype: GameModeType) {
+        self.server.set_mode(modetype);
+        self.stats_exported = false;
+    }
+
+    /// sets the time (in ms) from the start of the current match to sudden death, or disables
+    /// sudden death if None.
+    pub fn set_time_limit(&mut self, time_limit: Option<u128>) {
+        self.server.set_time_limit(time_limit);
+    }
+
+    /// runs the server until it is stopped with Ctrl-C. The statistics of the match are exported
+    /// when the match ends, or when the server stops if the match did not end before.
     pub fn run(&mut self) {
-        loop {
+        let running = Arc::new(AtomicBool::new(true));
+        let handler_running = running.clone();
+        ctrlc::set_handler(move || handler_running.store(false, Ordering::SeqCst))
+            .expect("Unable to set the Ctrl-C handler");
+
+        while running.load(Ordering::SeqCst) {
             let start_time = Instant::now();
             self.server.tick(DELTA_T);
             let time_elapsed = start_time.elapsed();
@@ -23,7 +47,49 @@ impl ServerState {
             let game_state_message = Message::write_state(self.server.get_arena());
             self.server.send_message(&game_state_message).expect("Message should send");
 
+            let mode_message = Message::write_mode(self.server.get_mode());
+            self.server.send_message(&mode_message).expect("Message should send");
+
+            // blocks which broke or grew back are sent in order, so the clients end up with the
+            // same blocks as the server.
+            let block_changes: Vec<_> = self.server.get_events()
+                .iter()
+                .filter_map(|event| match *event {
+                    GameEvent::BlockChanged { row, col, blocktype } => Some((row, col, blocktype)),
+                    _ => None,
+                })
+                .collect();
+
+            if !block_changes.is_empty() {
+                let block_message = Message::write_block_changes(&block_changes);
+                self.server.send_ordered_message(&block_message).expect("Message should send");
+            }
+
+            let curr_time = self.server.get_arena().get_time();
+            if self.server.get_mode().is_over(curr_time) && !self.stats_exported {
+                self.export_stats();
+            }
+
             thread::sleep(diff_from_target);
         }
+
+        if !self.stats_exported {
+            self.export_stats();
+        }
+    }
+
+    /// writes the statistics of the match which just ended to `STATS_FILE`, and sends them
+    /// to the clients for the results screen.
+    fn export_stats(&mut self) {
+        let stats = self.server.get_arena().get_stats();
+        if let Err(e) = fs::write(STATS_FILE, stats.to_json()) {
+            println!("Unable to export match statistics: {}", e);
+        }
+
+        let stats_message = Message::write_stats(stats);
+        if let Err(e) = self.server.send_message(&stats_message) {
+            println!("Unable to send match statistics: {}", e);
+        }
+        self.stats_exported = true;
     }
 }
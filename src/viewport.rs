@@ -44,7 +44,7 @@ impl Viewport {
     /// Same as `get_viewport_centered_at(point, &ctx)` function, but centers it on the player
     /// instead.
     pub fn get_viewport_on_player(player: &Player, ctx: &Context) -> Viewport {
-        let playercenter = player.position + Vec2::new(player.width, player.height) / 2.0;
+        let playercenter = player.get_position() + Vec2::new(player.width, player.height) / 2.0;
         Viewport::get_viewport_centered_at(playercenter, ctx)
     }
 